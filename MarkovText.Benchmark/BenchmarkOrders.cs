@@ -0,0 +1,106 @@
+﻿using System.Text;
+using BenchmarkDotNet.Attributes;
+using BenchmarkDotNet.Columns;
+using BenchmarkDotNet.Reports;
+using BenchmarkDotNet.Running;
+
+namespace MarkovText.Benchmark;
+
+/// <summary>
+/// Training throughput of the string-based model on the bundled corpus, in tokens per second, at several orders
+/// </summary>
+[Config(typeof(TrainingThroughputConfig))]
+[MemoryDiagnoser]
+public class BenchmarkTrainingThroughput
+{
+    [Params(1, 2, 3)]
+    public int Order;
+
+    private string corpus = null!;
+
+    [GlobalSetup]
+    public void Setup()
+    {
+        corpus = File.ReadAllText(ArrayBasedMarkovTextGenerator.DefaultCorpusPath);
+    }
+
+    [Benchmark]
+    public void BuildMarkovModel()
+    {
+        new StringBasedMarkovTextGenerator().BuildMarkovModel(corpus, Order);
+    }
+
+    public class TrainingThroughputConfig : AntiVirusFriendlyConfig
+    {
+        public TrainingThroughputConfig()
+        {
+            AddColumn(new TokensPerSecondColumn());
+        }
+    }
+
+    // The number of corpus tokens trained on per second, from the mean time of a build
+    public class TokensPerSecondColumn : IColumn
+    {
+        private static readonly Lazy<int> TokenCount = new(() =>
+            new WhitespaceTokenizer().Tokenize(Preprocessing.Default.Apply(File.ReadAllText(ArrayBasedMarkovTextGenerator.DefaultCorpusPath))).Count());
+
+        public string Id => nameof(TokensPerSecondColumn);
+        public string ColumnName => "Tokens/s";
+        public bool AlwaysShow => true;
+        public ColumnCategory Category => ColumnCategory.Custom;
+        public int PriorityInCategory => 0;
+        public bool IsNumeric => true;
+        public UnitType UnitType => UnitType.Dimensionless;
+        public string Legend => "Corpus tokens trained on per second";
+
+        public string GetValue(Summary summary, BenchmarkCase benchmarkCase) => GetValue(summary, benchmarkCase, SummaryStyle.Default);
+
+        public string GetValue(Summary summary, BenchmarkCase benchmarkCase, SummaryStyle style) =>
+            summary[benchmarkCase]?.ResultStatistics is { } statistics
+                ? (TokenCount.Value / (statistics.Mean / 1e9)).ToString("N0", style.CultureInfo)
+                : "NA";
+
+        public bool IsDefault(Summary summary, BenchmarkCase benchmarkCase) => false;
+
+        public bool IsAvailable(Summary summary) => true;
+    }
+}
+
+/// <summary>
+/// Generation latency on the bundled corpus at several orders, of the string-based model and the frozen model writing to a reused buffer
+/// </summary>
+[Config(typeof(AntiVirusFriendlyConfig))]
+[MemoryDiagnoser]
+public class BenchmarkGenerationLatency
+{
+    private const string Seed = "6a4156e2"; // Using a constant seed for deterministic benchmarking
+
+    [Params(1, 2, 3)]
+    public int Order;
+
+    private StringBasedMarkovTextGenerator generator = null!;
+    private FrozenMarkovTextGenerator frozen = null!;
+    private readonly StringBuilder buffer = new();
+
+    [GlobalSetup]
+    public void Setup()
+    {
+        generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel(File.ReadAllText(ArrayBasedMarkovTextGenerator.DefaultCorpusPath), Order);
+        frozen = generator.Freeze();
+    }
+
+    [Benchmark(Baseline = true)]
+    public string StringBased()
+    {
+        return generator.GenerateSentence(new DefaultRandom(new Random(Seed.GetStableHashCode())));
+    }
+
+    [Benchmark]
+    public int FrozenIntoBuffer()
+    {
+        buffer.Clear();
+        frozen.GenerateSentence(new DefaultRandom(new Random(Seed.GetStableHashCode())), buffer);
+        return buffer.Length;
+    }
+}
@@ -31,6 +31,10 @@ public class BenchmarkGenerateSentence
         yield return new StringBasedMarkovTextGenerator();
         yield return new ArrayBasedMarkovTextGenerator();
         yield return new SpanBasedMarkovTextGenerator();
+        yield return new PackedMarkovTextGenerator();
+        yield return new InternedMarkovTextGenerator();
+        yield return new TrieMarkovTextGenerator();
+        yield return new WeightedMarkovTextGenerator();
     }
 }
 
@@ -60,5 +64,9 @@ public class BenchmarkBuildMarkovModel
         yield return new StringBasedMarkovTextGenerator();
         yield return new ArrayBasedMarkovTextGenerator();
         yield return new SpanBasedMarkovTextGenerator();
+        yield return new PackedMarkovTextGenerator();
+        yield return new InternedMarkovTextGenerator();
+        yield return new TrieMarkovTextGenerator();
+        yield return new WeightedMarkovTextGenerator();
     }
 }
\ No newline at end of file
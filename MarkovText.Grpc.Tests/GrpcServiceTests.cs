@@ -0,0 +1,91 @@
+﻿using Grpc.Core;
+using Shouldly;
+
+namespace MarkovText.Grpc.Tests;
+
+public class GrpcServiceTests
+{
+    [Test]
+    [Description("The corpus is streamed in chunks, split mid-sentence, and the order is read from the first chunk.")]
+    public async Task Trained_models_generate_seeded_sentences()
+    {
+        var service = new MarkovTextGrpcService(new ModelStore());
+
+        var train = await service.Train(new RequestStream(new TrainRequest { Text = "The big dog was ", Order = 2 }, new TrainRequest { Text = "happy. The big dog was sad." }), new CallContext());
+
+        var request = new GenerateRequest { Model = train.Model, Count = 3, Seed = "dog" };
+        var reply = await service.Generate(request, new CallContext());
+
+        reply.Seed.ShouldBe("dog");
+        reply.Sentences.Count.ShouldBe(3);
+        reply.Sentences.ShouldAllBe(sentence => sentence == "The big dog was happy." || sentence == "The big dog was sad.");
+
+        (await service.Generate(request, new CallContext())).Sentences.ShouldBe(reply.Sentences);
+    }
+
+    [Test]
+    public void Invalid_requests_are_rejected()
+    {
+        var service = new MarkovTextGrpcService(new ModelStore());
+
+        Should.Throw<RpcException>(() => service.Train(new RequestStream(new TrainRequest { Text = "Dog." }), new CallContext())).StatusCode.ShouldBe(StatusCode.InvalidArgument);
+        Should.Throw<RpcException>(() => service.Generate(new GenerateRequest { Model = "unknown" }, new CallContext())).StatusCode.ShouldBe(StatusCode.NotFound);
+    }
+
+    [Test]
+    [Description("The least recently used model is evicted when the store is full.")]
+    public void Store_evicts_the_least_recently_used_model()
+    {
+        var store = new ModelStore(maxModels: 2);
+
+        var first = store.Add(new StringBasedMarkovTextGenerator());
+        var second = store.Add(new StringBasedMarkovTextGenerator());
+        store.TryGet(first, out _).ShouldBeTrue();
+        var third = store.Add(new StringBasedMarkovTextGenerator());
+
+        store.TryGet(first, out _).ShouldBeTrue();
+        store.TryGet(second, out _).ShouldBeFalse();
+        store.TryGet(third, out _).ShouldBeTrue();
+    }
+
+    private class RequestStream : IAsyncStreamReader<TrainRequest>
+    {
+        private readonly Queue<TrainRequest> requests;
+
+        public RequestStream(params TrainRequest[] requests)
+        {
+            this.requests = new Queue<TrainRequest>(requests);
+        }
+
+        public TrainRequest Current { get; private set; } = null!;
+
+        public Task<bool> MoveNext(CancellationToken cancellationToken)
+        {
+            if (!requests.TryDequeue(out var request))
+            {
+                return Task.FromResult(false);
+            }
+
+            Current = request;
+            return Task.FromResult(true);
+        }
+    }
+
+    private class CallContext : ServerCallContext
+    {
+        protected override string MethodCore => "";
+        protected override string HostCore => "";
+        protected override string PeerCore => "";
+        protected override DateTime DeadlineCore => DateTime.MaxValue;
+        protected override Metadata RequestHeadersCore { get; } = new();
+        protected override CancellationToken CancellationTokenCore => CancellationToken.None;
+        protected override Metadata ResponseTrailersCore { get; } = new();
+        protected override Status StatusCore { get; set; }
+        protected override WriteOptions? WriteOptionsCore { get; set; }
+        protected override AuthContext AuthContextCore { get; } = new(null, new Dictionary<string, List<AuthProperty>>());
+
+        protected override ContextPropagationToken CreatePropagationTokenCore(ContextPropagationOptions? options) => throw new NotSupportedException();
+
+        protected override Task WriteResponseHeadersAsyncCore(Metadata responseHeaders) => Task.CompletedTask;
+    }
+}
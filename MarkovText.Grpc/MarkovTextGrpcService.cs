@@ -0,0 +1,70 @@
+﻿using Grpc.Core;
+
+namespace MarkovText.Grpc;
+
+/// <summary>
+/// Implements the Train and Generate RPCs of markov.proto
+/// </summary>
+public class MarkovTextGrpcService : MarkovTextService.MarkovTextServiceBase
+{
+    // Upper limit of sentences per request, like GenerationServer.MaxCount
+    private const int MaxCount = GenerationServer.MaxCount;
+
+    private readonly ModelStore models;
+
+    public MarkovTextGrpcService(ModelStore models)
+    {
+        this.models = models;
+    }
+
+    public override async Task<TrainReply> Train(IAsyncStreamReader<TrainRequest> requestStream, ServerCallContext context)
+    {
+        var corpus = await TrainRequestReader.StartAsync(requestStream, context.CancellationToken);
+        var generator = new StringBasedMarkovTextGenerator();
+
+        try
+        {
+            // The chunks are analyzed as they arrive, instead of concatenating the whole corpus first
+            await Task.Run(() => generator.BuildMarkovModel(corpus, new BuildOptions { Order = corpus.Order }, cancellationToken: context.CancellationToken));
+        }
+        catch (ArgumentException e)
+        {
+            throw new RpcException(new Status(StatusCode.InvalidArgument, e.Message));
+        }
+
+        return new TrainReply { Model = models.Add(generator) };
+    }
+
+    public override Task<GenerateReply> Generate(GenerateRequest request, ServerCallContext context)
+    {
+        if (!models.TryGet(request.Model, out var generator))
+        {
+            throw new RpcException(new Status(StatusCode.NotFound, $"Unknown model: {request.Model}"));
+        }
+
+        var count = request.Count > 0 ? request.Count : 1;
+
+        if (count > MaxCount)
+        {
+            throw new RpcException(new Status(StatusCode.InvalidArgument, $"The count must be a number from 1 to {MaxCount}."));
+        }
+
+        var seed = request.Seed.Length > 0 ? request.Seed : Guid.NewGuid().ToString()[..8];
+        var random = new DefaultRandom(new Random(seed.GetStableHashCode()));
+        var reply = new GenerateReply { Seed = seed };
+
+        try
+        {
+            for (var i = 0; i < count; i++)
+            {
+                reply.Sentences.Add(generator.GenerateSentence(random));
+            }
+        }
+        catch (Exception e) when (e is SentenceOverflowException or ContentFilterException or GenerationFailedException or InvalidOperationException)
+        {
+            throw new RpcException(new Status(StatusCode.Internal, e.Message));
+        }
+
+        return Task.FromResult(reply);
+    }
+}
@@ -0,0 +1,64 @@
+﻿namespace MarkovText.Grpc;
+
+/// <summary>
+/// The models trained during the lifetime of the server, by handle.
+/// Only the most recently used models are kept, so clients training models can't exhaust the memory of the server.
+/// </summary>
+public class ModelStore
+{
+    public const int DefaultMaxModels = 100;
+
+    private readonly int maxModels;
+
+    // The models from the most to the least recently used, and their nodes by handle
+    private readonly LinkedList<(string Handle, StringBasedMarkovTextGenerator Generator)> models = new();
+    private readonly Dictionary<string, LinkedListNode<(string Handle, StringBasedMarkovTextGenerator Generator)>> nodes = new();
+
+    public ModelStore(int maxModels = DefaultMaxModels)
+    {
+        if (maxModels < 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(maxModels), maxModels, "At least one model must be kept.");
+        }
+
+        this.maxModels = maxModels;
+    }
+
+    /// <summary>
+    /// Stores the model and returns its handle, evicting the least recently used model if the store is full
+    /// </summary>
+    public string Add(StringBasedMarkovTextGenerator generator)
+    {
+        var handle = Guid.NewGuid().ToString("N");
+
+        lock (models)
+        {
+            if (models.Count >= maxModels)
+            {
+                nodes.Remove(models.Last!.Value.Handle);
+                models.RemoveLast();
+            }
+
+            nodes[handle] = models.AddFirst((handle, generator));
+        }
+
+        return handle;
+    }
+
+    public bool TryGet(string handle, out StringBasedMarkovTextGenerator generator)
+    {
+        lock (models)
+        {
+            if (!nodes.TryGetValue(handle, out var node))
+            {
+                generator = null!;
+                return false;
+            }
+
+            models.Remove(node);
+            models.AddFirst(node);
+            generator = node.Value.Generator;
+            return true;
+        }
+    }
+}
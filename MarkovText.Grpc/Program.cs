@@ -0,0 +1,13 @@
+﻿using MarkovText.Grpc;
+
+var builder = WebApplication.CreateBuilder(args);
+
+builder.Services.AddGrpc();
+builder.Services.AddSingleton<ModelStore>();
+
+var app = builder.Build();
+
+app.MapGrpcService<MarkovTextGrpcService>();
+app.MapGet("/", () => "Markov text gRPC server, see Protos/markov.proto");
+
+app.Run();
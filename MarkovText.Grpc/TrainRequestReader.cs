@@ -0,0 +1,73 @@
+﻿using Grpc.Core;
+
+namespace MarkovText.Grpc;
+
+/// <summary>
+/// Reads the corpus of a Train request stream chunk by chunk, so the corpus is never held in memory as a whole
+/// </summary>
+public sealed class TrainRequestReader : TextReader
+{
+    private readonly IAsyncStreamReader<TrainRequest> requestStream;
+    private readonly CancellationToken cancellationToken;
+
+    // The text of the current chunk, and how much of it has been read
+    private string chunk;
+    private int position;
+
+    /// <summary>
+    /// The order of the Markov chain, read from the first chunk, 2 if unset
+    /// </summary>
+    public int Order { get; }
+
+    private TrainRequestReader(IAsyncStreamReader<TrainRequest> requestStream, TrainRequest? first, CancellationToken cancellationToken)
+    {
+        this.requestStream = requestStream;
+        this.cancellationToken = cancellationToken;
+        chunk = first?.Text ?? "";
+        Order = first is { Order: > 0 } ? first.Order : 2;
+    }
+
+    /// <summary>
+    /// Receives the first chunk to read the order from
+    /// </summary>
+    public static async Task<TrainRequestReader> StartAsync(IAsyncStreamReader<TrainRequest> requestStream, CancellationToken cancellationToken)
+    {
+        var first = await requestStream.MoveNext(cancellationToken) ? requestStream.Current : null;
+        return new TrainRequestReader(requestStream, first, cancellationToken);
+    }
+
+    public override int Peek() => NextChunk() ? chunk[position] : -1;
+
+    public override int Read() => NextChunk() ? chunk[position++] : -1;
+
+    public override int Read(char[] buffer, int index, int count)
+    {
+        if (!NextChunk())
+        {
+            return 0;
+        }
+
+        var read = Math.Min(count, chunk.Length - position);
+        chunk.CopyTo(position, buffer, index, read);
+        position += read;
+        return read;
+    }
+
+    // Receives chunks until there is text left to read, false at the end of the stream.
+    // Training is synchronous, so it is run on a worker thread that can wait for the next chunk.
+    private bool NextChunk()
+    {
+        while (position >= chunk.Length)
+        {
+            if (!requestStream.MoveNext(cancellationToken).GetAwaiter().GetResult())
+            {
+                return false;
+            }
+
+            chunk = requestStream.Current.Text;
+            position = 0;
+        }
+
+        return true;
+    }
+}
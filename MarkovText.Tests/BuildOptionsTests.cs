@@ -0,0 +1,241 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class BuildOptionsTests
+{
+    private StringBasedMarkovTextGenerator generator = null!;
+
+    [SetUp]
+    public void Setup()
+    {
+        generator = new StringBasedMarkovTextGenerator();
+    }
+
+    [Test]
+    [Description("'The dog' and 'the dog' share a state, the sentence start is re-capitalized and 'Dublin' keeps its capital.")]
+    public void Normalized_case_is_restored()
+    {
+        const string corpus = "The dog met Dublin. the dog loves Dublin. The cat met the dog.";
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { NormalizeCase = true });
+
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("The dog loves Dublin.");
+        generator.GenerateSentence(new RandomStub(2)).ShouldBe("The cat met the dog.");
+    }
+
+    [Test]
+    [Description("The learned sentence is lowercased like the corpus, and also added to the reverse model.")]
+    public void Learning_applies_the_build_options()
+    {
+        generator.BuildMarkovModel("The big dog was happy.", new BuildOptions { NormalizeCase = true, BuildReverseModel = true });
+
+        var patch = generator.Learn("The big dog was sad.");
+
+        patch.StarterPhrases.ShouldBe(new[] { new StarterPhraseDelta("the big", 1) });
+        patch.Transitions.ShouldContain(new TransitionDelta("dog was", "was sad.", "sad.", 1));
+        generator.GenerateEndingWith(new RandomStub(0), "sad").ShouldBe("The big dog was sad.");
+    }
+
+    [Test]
+    public void Phrase_comparer_builds_the_same_model()
+    {
+        var corpus = File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath);
+        var stableGenerator = new StringBasedMarkovTextGenerator();
+
+        generator.BuildMarkovModel(corpus);
+        stableGenerator.BuildMarkovModel(corpus, new BuildOptions { PhraseComparer = StableStringComparer.Instance });
+
+        stableGenerator.GenerateSentence(new RandomStub(73)).ShouldBe(generator.GenerateSentence(new RandomStub(73)));
+        stableGenerator.ToJson().ShouldBe(generator.ToJson());
+    }
+
+    [Test]
+    [Description("Two sentences end with 'happy.', and growing backwards from 'was' continues with 'dog' or 'cat'.")]
+    public void Sentences_grow_backwards_from_the_ending()
+    {
+        Should.Throw<InvalidOperationException>(() => generator.GenerateEndingWith(new RandomStub(0), "happy"));
+
+        generator.BuildMarkovModel("The big dog was happy. A cat was happy. The big dog was sad.", new BuildOptions { BuildReverseModel = true });
+
+        generator.GenerateEndingWith(new RandomStub(0), "happy").ShouldBe("The big dog was happy.");
+        generator.GenerateEndingWith(new RandomStub(1), "Happy!").ShouldBe("A cat was happy.");
+        generator.GenerateEndingWith(new RandomStub(1), "sad").ShouldBe("The big dog was sad.");
+        Should.Throw<ArgumentException>(() => generator.GenerateEndingWith(new RandomStub(0), "dog"));
+    }
+
+    [Test]
+    [Description("Both phrases containing 'cat', 'A cat' and 'cat was', grow into the only sentence with a cat.")]
+    public void Sentences_grow_in_both_directions_from_the_keyword()
+    {
+        generator.BuildMarkovModel("The big dog was happy. A cat was happy. The big dog was sad.", new BuildOptions { BuildReverseModel = true });
+
+        generator.GenerateContaining(new RandomStub(0), "cat").ShouldBe("A cat was happy.");
+        generator.GenerateContaining(new RandomStub(1), "Cat!").ShouldBe("A cat was happy.");
+        Should.Throw<ArgumentException>(() => generator.GenerateContaining(new RandomStub(0), "bird"));
+    }
+
+    [Test]
+    [Description("The stub picks the first transition in both directions, unless the content filter bans it.")]
+    public void Sentences_grow_in_both_directions_with_the_options()
+    {
+        generator.BuildMarkovModel("The big dog was happy. A cat was happy. A cat was sad.", new BuildOptions { BuildReverseModel = true });
+
+        generator.GenerateContaining(new RandomStub(0), "happy").ShouldBe("The big dog was happy.");
+        generator.GenerateContaining(new RandomStub(0), "happy", new GenerationOptions { ContentFilter = new ContentFilter(new[] { "dog" }) }).ShouldBe("A cat was happy.");
+        generator.GenerateContaining(new RandomStub(0), "cat").ShouldBe("A cat was happy.");
+        generator.GenerateContaining(new RandomStub(0), "cat", new GenerationOptions { ContentFilter = new ContentFilter(new[] { "happy" }) }).ShouldBe("A cat was sad.");
+    }
+
+    [Test]
+    [Description("Without the reverse model, 'sad.' is only reached by rejecting the sentences without it, and the stub always generates the first sentence.")]
+    public void Sentences_without_the_keyword_are_rejected()
+    {
+        generator.BuildMarkovModel("The big dog was happy. A cat was happy. The big dog was sad.");
+
+        generator.GenerateContaining(new DefaultRandom(new Random(1)), "sad").ShouldBe("The big dog was sad.");
+        Should.Throw<GenerationFailedException>(() => generator.GenerateContaining(new RandomStub(0), "sad"));
+    }
+
+    [Test]
+    public void Chunked_corpus_builds_the_same_model()
+    {
+        const string corpus = "The dog was happy. The dog was sad! A cat was happy? The cat was very happy.";
+        var chunkedGenerator = new StringBasedMarkovTextGenerator();
+
+        generator.BuildMarkovModel(corpus);
+        chunkedGenerator.BuildMarkovModel(new StringReader(corpus), new BuildOptions(), chunkSize: 20);
+
+        chunkedGenerator.ToJson().ShouldBe(generator.ToJson());
+    }
+
+    [Test]
+    [Description("The note isn't split between chunks, and the license after the end marker is skipped though it is in a chunk of its own.")]
+    public void Chunked_corpus_strips_notes_and_boilerplate_like_the_whole_corpus()
+    {
+        const string corpus = "*** START OF THE PROJECT GUTENBERG EBOOK DOGS ***\nThe dog was happy. [Illustration: The cat was sad.] The dog was sad! " +
+                              "A cat was happy? The cat was very happy.\n*** END OF THE PROJECT GUTENBERG EBOOK DOGS ***\nThe license was long. The license was very long.";
+        var chunkedGenerator = new StringBasedMarkovTextGenerator();
+
+        generator.BuildMarkovModel(corpus);
+        chunkedGenerator.BuildMarkovModel(new StringReader(corpus), new BuildOptions(), chunkSize: 60);
+
+        chunkedGenerator.ToJson().ShouldBe(generator.ToJson());
+        chunkedGenerator.GetPhrases().ShouldNotContain("The license");
+    }
+
+    [Test]
+    [Description("'A cat' -> 'cat was' and 'The cat' -> 'cat was' are seen once, which orphans 'cat was' though 'cat was' -> 'was happy.' is seen twice.")]
+    public void Pruning_removes_rare_transitions_and_orphaned_phrases()
+    {
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. A cat was happy. The cat was happy.");
+
+        generator.Prune(2).ShouldBe(new PruneReport(TransitionsRemoved: 3, OccurrencesRemoved: 4, PhrasesRemoved: 3));
+
+        generator.GetPhrases().ShouldBe(new[] { "The dog", "dog was" });
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog was happy.");
+        generator.GenerateSentence(new RandomStub(2)).ShouldBe("A cat");
+    }
+
+    [Test]
+    [Description("Exceeding the memory limit prunes the transition 'dog was' -> 'was sad.' that is seen only once.")]
+    public void Memory_limit_prunes_singleton_transitions()
+    {
+        const string corpus = "The dog was happy. The dog was happy. The dog was sad.";
+
+        generator.BuildMarkovModel(corpus);
+        generator.GenerateSentence(new RandomStub(2)).ShouldBe("The dog was sad.");
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { MaxMemoryBytes = 1 });
+        generator.GenerateSentence(new RandomStub(2)).ShouldBe("The dog was happy.");
+        generator.MemoryPruning.ShouldBe(new PruneReport(TransitionsRemoved: 1, OccurrencesRemoved: 1, PhrasesRemoved: 0));
+    }
+
+    [Test]
+    [Description("The learned transition 'dog was' -> 'was sad.' is pruned again right away, so the patch only adds 'The dog' -> 'dog was'.")]
+    public void Learning_keeps_the_memory_limit()
+    {
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. The dog was sad.", new BuildOptions { MaxMemoryBytes = 1 });
+
+        var patch = generator.Learn("The dog was sad.");
+
+        patch.Transitions.ShouldBe(new[] { new TransitionDelta("The dog", "dog was", "was", 1) });
+        generator.GenerateSentence(new RandomStub(3)).ShouldBe("The dog was happy.");
+        generator.MemoryPruning.ShouldBe(new PruneReport(TransitionsRemoved: 2, OccurrencesRemoved: 2, PhrasesRemoved: 0));
+    }
+
+    [Test]
+    [Description("Transitions to the stopword 'the' get a quarter of the weight, so 'dog ate' -> 'ate some' covers 100 of 125 random values.")]
+    public void Stopword_transitions_are_down_weighted()
+    {
+        const string corpus = "A dog ate the cat. A dog ate some cat.";
+
+        generator.BuildMarkovModel(corpus);
+        generator.GenerateSentence(new RandomStub(30)).ShouldBe("A dog ate the cat.");
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { Stopwords = new[] { "the" }, StopwordWeight = 0.25 });
+        generator.GenerateSentence(new RandomStub(30)).ShouldBe("A dog ate some cat.");
+        generator.GenerateSentence(new RandomStub(20)).ShouldBe("A dog ate the cat.");
+    }
+
+    [Test]
+    public void Stopword_starters_are_excluded()
+    {
+        const string corpus = "The dog ate. A cat sat.";
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { Stopwords = new[] { "the" }, ExcludeStopwordStarters = true });
+
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("A cat sat.");
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("A cat sat.");
+    }
+
+    [Test]
+    [Description("Only the most frequent successor 'dog was' -> 'was happy.' is kept.")]
+    public void Successors_per_phrase_are_capped()
+    {
+        const string corpus = "The dog was sad. The dog was happy. The dog was happy.";
+
+        generator.BuildMarkovModel(corpus);
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog was sad.");
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { MaxSuccessorsPerPhrase = 1 });
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog was happy.");
+        generator.GetSuccessors("dog was").ShouldBe(new[] { ("was happy.", "happy.", 2) });
+    }
+
+    [Test]
+    [Description("'happy.' replaces 'sad.' and inherits its count, so 'happy.' is kept when 'mad.' replaces 'glad.', the least frequent successor.")]
+    public void Late_frequent_successors_replace_the_least_frequent()
+    {
+        const string corpus = "The dog was sad. The dog was glad. The dog was happy. The dog was happy. The dog was happy. The dog was mad.";
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { MaxSuccessorsPerPhrase = 2 });
+
+        generator.GetSuccessors("dog was").ShouldBe(new[] { ("was happy.", "happy.", 3), ("was mad.", "mad.", 1) });
+    }
+
+    [Test]
+    public void Quote_stored_corpus_sentences()
+    {
+        const string corpus = "The big dog was happy. The small dog was sad.";
+
+        generator.BuildMarkovModel(corpus);
+        Should.Throw<InvalidOperationException>(() => generator.Quote(new RandomStub(0)));
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { StoreSentences = true });
+        generator.Quote(new RandomStub(0)).ShouldBe("The big dog was happy.");
+        generator.Quote(new RandomStub(1)).ShouldBe("The small dog was sad.");
+    }
+
+    [Test]
+    [Description("Lines start the phrases and end without crossing into the next line, although they have no full stop.")]
+    public void Line_mode_generates_stanzas()
+    {
+        const string corpus = "Roses are red\nViolets are blue\nSugar is sweet\n";
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { Order = 1, LineMode = true });
+
+        generator.GenerateStanza(new RandomStub(1), 2).ShouldBe("Violets are blue\nViolets are blue");
+        generator.GetSuccessors("red").ShouldBeEmpty();
+    }
+}
@@ -0,0 +1,26 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ByteMarkovModelTests
+{
+    [Test]
+    [Description("Both records start with 0x01 0x02, which is followed by 0x03 in the first and by 0xFF in the second.")]
+    public void Records_are_split_after_the_separator()
+    {
+        var model = new ByteMarkovModel(order: 2);
+        model.Add(new byte[] { 0x01, 0x02, 0x03, 0x00, 0x01, 0x02, 0xFF, 0x00 }, separator: 0x00);
+
+        model.Generate(new RandomStub(0)).ShouldBe(new byte[] { 0x01, 0x02, 0x03, 0x00 });
+        model.Generate(new RandomStub(1)).ShouldBe(new byte[] { 0x01, 0x02, 0xFF, 0x00 });
+    }
+
+    [Test]
+    public void Cyclic_data_is_cut_off()
+    {
+        var model = new ByteMarkovModel(order: 1) { MaxLength = 5 };
+        model.Add(new byte[] { 0xAB, 0xCD, 0xAB });
+
+        model.Generate(new RandomStub(0)).ShouldBe(new byte[] { 0xAB, 0xCD, 0xAB, 0xCD, 0xAB });
+    }
+}
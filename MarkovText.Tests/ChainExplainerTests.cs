@@ -0,0 +1,21 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ChainExplainerTests
+{
+    [Test]
+    public void Explain_construction_table_and_generation()
+    {
+        var output = new StringWriter();
+        new ChainExplainer(output).Explain("The big dog was happy. The big dog was sad.", 2, new RandomStub(1));
+
+        var text = output.ToString();
+
+        text.ShouldContain("start: [The big]");
+        text.ShouldContain("[big dog] => was");
+        text.ShouldContain("[dog was] => happy. 1/2, sad. 1/2");
+        text.ShouldContain("[dog was] => sad. (1/2)");
+        text.ShouldContain("The big dog was sad.");
+    }
+}
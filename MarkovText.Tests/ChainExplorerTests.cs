@@ -0,0 +1,24 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ChainExplorerTests
+{
+    [Test]
+    [Description("Shows the successors of 'dog was', then steps through the first two words of a sentence.")]
+    public void Explore_and_step()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+
+        var output = new StringWriter();
+        new ChainExplorer(generator, new RandomStub(0), new StringReader("phrase dog was\nstep\nstep\nquit\n"), output).Run();
+
+        var text = output.ToString();
+
+        text.ShouldContain("Words following 'dog was':");
+        text.ShouldContain("happy. " + new string('█', 40) + " 1 (50%)");
+        text.ShouldContain("Start with 'The big': The big");
+        text.ShouldContain("'The big' => 'dog': The big dog");
+    }
+}
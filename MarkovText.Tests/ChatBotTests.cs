@@ -0,0 +1,70 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ChatBotTests
+{
+    [Test]
+    [Description("The bot learns 'The big dog was sad.' from the channel and can then generate it.")]
+    public void Bot_learns_messages_and_replies_to_command()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy.");
+
+        var source = new QueueSource(new ChatMessage("#dogs", "alice", "The big dog was sad."), new ChatMessage("#dogs", "bob", "!markov"));
+        var sink = new ListSink();
+
+        new ChatBot(generator, source, sink, new RandomStub(1)).Run(CancellationToken.None);
+
+        sink.Sent.ShouldBe(new[] { ("#dogs", "The big dog was sad.") });
+    }
+
+    [Test]
+    [Description("Overlong sentences are truncated instead of stopping the bot.")]
+    public void Bot_replies_despite_overflowing_sentences()
+    {
+        var generator = new StringBasedMarkovTextGenerator { DefaultOptions = new GenerationOptions { MaxWordCount = 2 } };
+        generator.BuildMarkovModel("The big dog was happy, and the big dog was sad.");
+
+        var source = new QueueSource(new ChatMessage("#dogs", "bob", "!markov"), new ChatMessage("#dogs", "bob", "!markov"));
+        var sink = new ListSink();
+
+        new ChatBot(generator, source, sink, new RandomStub(1)).Run(CancellationToken.None);
+
+        sink.Sent.Count.ShouldBe(2);
+    }
+
+    [Test]
+    public void Irc_connection_joins_answers_pings_and_receives_messages()
+    {
+        var reader = new StringReader(":server\n:server 001 markovbot :Welcome\nPING :server\n:alice!alice@host PRIVMSG #dogs :The big dog was sad.\n");
+        var writer = new StringWriter { NewLine = "\n" };
+
+        using var irc = new IrcConnection(reader, writer, "markovbot", new[] { "#dogs" });
+
+        irc.Receive(CancellationToken.None).ShouldBe(new ChatMessage("#dogs", "alice", "The big dog was sad."));
+        irc.Send("#dogs", "Woof.");
+        irc.Receive(CancellationToken.None).ShouldBeNull();
+
+        writer.ToString().ShouldBe("NICK markovbot\nUSER markovbot 0 * :markovbot\nJOIN #dogs\nPONG :server\nPRIVMSG #dogs :Woof.\n");
+    }
+
+    private class QueueSource : IMessageSource
+    {
+        private readonly Queue<ChatMessage> messages;
+
+        public QueueSource(params ChatMessage[] messages)
+        {
+            this.messages = new Queue<ChatMessage>(messages);
+        }
+
+        public ChatMessage? Receive(CancellationToken cancellationToken) => messages.TryDequeue(out var message) ? message : null;
+    }
+
+    private class ListSink : IMessageSink
+    {
+        public readonly List<(string Channel, string Text)> Sent = new();
+
+        public void Send(string channel, string text) => Sent.Add((channel, text));
+    }
+}
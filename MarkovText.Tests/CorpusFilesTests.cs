@@ -0,0 +1,108 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class CorpusFilesTests
+{
+    [Test]
+    public void Glob_patterns_expand_to_matching_files()
+    {
+        var directory = Directory.CreateTempSubdirectory().FullName;
+
+        try
+        {
+            File.WriteAllText(Path.Combine(directory, "b.txt"), "");
+            File.WriteAllText(Path.Combine(directory, "a.txt"), "");
+            File.WriteAllText(Path.Combine(directory, "c.md"), "");
+
+            CorpusFiles.Expand(new[] { Path.Combine(directory, "*.txt"), "missing.txt" }).ShouldBe(new[]
+            {
+                Path.Combine(directory, "a.txt"),
+                Path.Combine(directory, "b.txt"),
+                "missing.txt",
+            });
+        }
+        finally
+        {
+            Directory.Delete(directory, recursive: true);
+        }
+    }
+
+    [Test]
+    public void Csv_column_is_read_as_documents()
+    {
+        const string csv = "id,review_text,stars\r\n" +
+                           "1,Great fit.,5\r\n" +
+                           "2,\"Too tight, sadly\",2\r\n" +
+                           "3,,1\r\n" +
+                           "4,\"She said \"\"wow\"\"\nand left!\",4\r\n";
+
+        CorpusFiles.ReadCsvColumn(new StringReader(csv), "review_text").ShouldBe(new[] { "Great fit.", "Too tight, sadly", "She said \"wow\"\nand left!" });
+
+        Should.Throw<InvalidDataException>(() => CorpusFiles.ReadCsvColumn(new StringReader(csv), "title").ToList());
+    }
+
+    [Test]
+    public void Json_lines_field_is_read_as_documents()
+    {
+        const string jsonl = "{\"user\":{\"text\":\"Hello there.\"}}\n" +
+                             "\n" +
+                             "{\"user\":{\"name\":\"Ada\"}}\n" +
+                             "{\"user\":{\"text\":42}}\n" +
+                             "{\"user\":{\"text\":\"Nice \\\"corset\\\"!\"}}\n";
+
+        CorpusFiles.ReadJsonLinesField(new StringReader(jsonl), "user.text").ShouldBe(new[] { "Hello there.", "Nice \"corset\"!" });
+
+        Should.Throw<InvalidDataException>(() => CorpusFiles.ReadJsonLinesField(new StringReader("{\"text\":"), "text").ToList());
+    }
+
+    [Test]
+    [Description("Chunks end after the last sentence delimiter they contain, and text without delimiters is cut at whitespace once it exceeds the chunk size.")]
+    public void Chunks_end_at_sentence_boundaries()
+    {
+        const string text = "One two. Three four five six seven eight. Nine.";
+
+        CorpusFiles.ReadSentenceChunks(new StringReader(text), chunkSize: 12).ShouldBe(new[]
+        {
+            "One two.",
+            " Three four five six seven",
+            " eight.",
+            " Nine.",
+        });
+    }
+
+    [Test]
+    public void Chunks_do_not_end_inside_brackets()
+    {
+        CorpusFiles.ReadSentenceChunks(new StringReader("One. [Two. Three.] Four. Five."), chunkSize: 10).ShouldBe(new[]
+        {
+            "One.",
+            " [Two. Three.] Four.",
+            " Five.",
+        });
+    }
+
+    [Test]
+    public void Recursive_search_filters_extensions()
+    {
+        var directory = Directory.CreateTempSubdirectory().FullName;
+
+        try
+        {
+            Directory.CreateDirectory(Path.Combine(directory, "sub"));
+            File.WriteAllText(Path.Combine(directory, "a.txt"), "");
+            File.WriteAllText(Path.Combine(directory, "sub", "b.TXT"), "");
+            File.WriteAllText(Path.Combine(directory, "sub", "c.md"), "");
+
+            CorpusFiles.FindRecursive(directory, new[] { "txt" }).ShouldBe(new[]
+            {
+                Path.Combine(directory, "a.txt"),
+                Path.Combine(directory, "sub", "b.TXT"),
+            });
+        }
+        finally
+        {
+            Directory.Delete(directory, recursive: true);
+        }
+    }
+}
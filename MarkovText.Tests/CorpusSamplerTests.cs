@@ -0,0 +1,27 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class CorpusSamplerTests
+{
+    [Test]
+    public void Sample_is_reproducible()
+    {
+        var corpus = File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath);
+
+        var sample = CorpusSampler.Sample(corpus, 0.1, "seed");
+
+        sample.ShouldBe(CorpusSampler.Sample(corpus, 0.1, "seed"));
+        sample.Length.ShouldBeLessThan(corpus.Length / 2);
+        SentenceSplitter.Split(sample).ShouldAllBe(sentence => corpus.Contains(sentence));
+    }
+
+    [Test]
+    public void Full_sample_keeps_all_sentences()
+    {
+        const string corpus = "The first sentence. The second sentence! The third sentence?";
+
+        CorpusSampler.Sample(corpus, 1, "seed").ShouldBe("The first sentence.\nThe second sentence!\nThe third sentence?");
+        Should.Throw<ArgumentOutOfRangeException>(() => CorpusSampler.Sample(corpus, 0, "seed"));
+    }
+}
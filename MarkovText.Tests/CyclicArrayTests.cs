@@ -33,4 +33,51 @@ public class CyclicArrayTests
         Assert.That(offset[1], Is.EqualTo(2));
         Assert.That(offset[2], Is.EqualTo(3));
     }
+
+    [Test]
+    [Description("Pushing 4, 5, 6, 7 into an array of 3 overwrites 4, and clearing empties the window.")]
+    public void Pushed_items_form_the_latest_window()
+    {
+        var window = new CyclicArray<int>(3);
+
+        window.Push(4);
+        window.Push(5);
+
+        Assert.That(window.IsFull, Is.False);
+        Assert.That(window.LatestWindow(), Is.EqualTo(new[] { 4, 5 }));
+
+        window.Push(6);
+        window.Push(7);
+
+        Assert.That(window.IsFull, Is.True);
+        Assert.That(window.Oldest, Is.EqualTo(5));
+        Assert.That(window.Latest, Is.EqualTo(7));
+        Assert.That(window.LatestWindow(), Is.EqualTo(new[] { 5, 6, 7 }));
+
+        window.Clear();
+        window.Push(8);
+
+        Assert.That(window.LatestWindow(), Is.EqualTo(new[] { 8 }));
+    }
+
+    [Test]
+    public void Window_is_enumerated_in_logical_order()
+    {
+        var window = new CyclicArray<string>(3);
+
+        foreach (var word in new[] { "The", "big", "dog", "was" })
+        {
+            window.Push(word);
+        }
+
+        Assert.That(string.Join(" ", window), Is.EqualTo("big dog was"));
+        Assert.That(window.Count, Is.EqualTo(3));
+        Assert.That(window.Capacity, Is.EqualTo(3));
+    }
+
+    [Test]
+    public void Rotated_returns_window_from_offset()
+    {
+        Assert.That(cyclic.Rotated(4), Is.EqualTo(new[] { 1, 2, 3 }));
+    }
 }
@@ -0,0 +1,70 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ExportTests
+{
+    [Test]
+    public void Dot_export_writes_transition_graph()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad. The big dog was happy.");
+
+        var writer = new StringWriter();
+        generator.ExportDot(writer);
+
+        var dot = writer.ToString();
+
+        dot.ShouldStartWith("digraph markov {");
+        dot.ShouldContain("\"The big\" [shape=box];");
+        dot.ShouldContain("\"dog was\" -> \"was happy.\" [label=2];");
+        dot.ShouldContain("\"dog was\" -> \"was sad.\" [label=1];");
+    }
+
+    [Test]
+    public void Dot_export_keeps_most_frequent_edges()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad. The big dog was happy.");
+
+        var writer = new StringWriter();
+        generator.ExportDot(writer, new DotExportOptions { MaxStates = 1, MaxEdgesPerState = 1 });
+
+        var dot = writer.ToString();
+
+        dot.ShouldContain("\"The big\" -> \"big dog\" [label=3];");
+        dot.ShouldNotContain("\"dog was\" ->");
+    }
+
+    [Test]
+    [Description("'A cat' has the fewest transitions, so it isn't drawn even though it starts a sentence.")]
+    public void Dot_export_only_marks_kept_starters()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad. A cat was happy.");
+
+        var writer = new StringWriter();
+        generator.ExportDot(writer, new DotExportOptions { MaxStates = 1 });
+
+        var dot = writer.ToString();
+
+        dot.ShouldContain("\"The big\" [shape=box];");
+        dot.ShouldNotContain("\"A cat\"");
+    }
+
+    [Test]
+    public void Csv_export_writes_transition_frequencies()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad, very sad. The big dog was happy.", 3);
+
+        var writer = new StringWriter();
+        generator.ExportCsv(writer);
+
+        var rows = writer.ToString().Split(Environment.NewLine);
+
+        rows[0].ShouldBe("from_phrase,to_word,count,probability");
+        rows.ShouldContain("big dog was,happy.,2,0.6666666666666666");
+        rows.ShouldContain("\"dog was sad,\",very,1,1");
+    }
+}
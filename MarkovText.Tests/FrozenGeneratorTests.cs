@@ -0,0 +1,145 @@
+﻿using System.Text;
+using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class FrozenGeneratorTests
+{
+    [Test]
+    [Description("The random values 0 and 1 fall in the range of 'happy.', which occurs twice, and 2 falls in the range of 'sad.'.")]
+    public void Continuations_are_sampled_by_cumulative_count()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. The dog was sad.");
+
+        var frozen = generator.Freeze();
+
+        frozen.GenerateSentence(new RandomStub(0)).ShouldBe("The dog was happy.");
+        frozen.GenerateSentence(new RandomStub(1)).ShouldBe("The dog was happy.");
+        frozen.GenerateSentence(new RandomStub(2)).ShouldBe("The dog was sad.");
+        Should.Throw<NotSupportedException>(() => frozen.BuildMarkovModel("The dog was happy."));
+    }
+
+    [Test]
+    [Description("'was' is followed by 'happy.' twice and 'sad.' once, 'was happy.' ends the sentence, and 'was glad.' is not a phrase of the model.")]
+    public void Frozen_model_can_be_inspected()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. The dog was sad.");
+
+        var frozen = generator.Freeze();
+
+        frozen.GetStarterPhrases().ShouldBe(new[] { ("The dog", 3) });
+        frozen.GetSuccessors("dog was").ShouldBe(new[] { ("happy.", 2), ("sad.", 1) });
+        frozen.GetSuccessors("was happy.").ShouldBeEmpty();
+        frozen.GetSuccessors("was glad.").ShouldBeNull();
+    }
+
+    [Test]
+    public void Empty_models_are_rejected()
+    {
+        Should.Throw<ArgumentException>(() => new FrozenMarkovTextGenerator(new ModelPatch { Order = 2 }, new WhitespaceTokenizer()))
+            .Message.ShouldContain("no starter phrases");
+    }
+
+    [Test]
+    public void Sentences_are_appended_to_the_buffer()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The dog was happy. The dog was sad.");
+
+        var buffer = new StringBuilder();
+        var frozen = generator.Freeze();
+
+        frozen.GenerateSentence(new RandomStub(0), buffer);
+        frozen.GenerateSentence(new RandomStub(1), buffer);
+
+        buffer.ToString().ShouldBe("The dog was happy. The dog was sad.");
+    }
+
+    [Test]
+    public void Frozen_model_uses_less_memory()
+    {
+        var corpus = File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath);
+        var generator = new StringBasedMarkovTextGenerator();
+        var higherOrderGenerator = new StringBasedMarkovTextGenerator();
+
+        generator.BuildMarkovModel(corpus);
+        higherOrderGenerator.BuildMarkovModel(corpus, order: 3);
+
+        generator.Freeze().EstimateMemoryBytes().ShouldBeInRange(1, generator.EstimateMemoryBytes() - 1);
+        higherOrderGenerator.EstimateMemoryBytes().ShouldBeGreaterThan(generator.EstimateMemoryBytes());
+    }
+
+    [Test]
+    [Description("Sentences generated concurrently from one model match the sentences generated one at a time with the same seeds.")]
+    public void Built_model_is_shared_by_threads()
+    {
+        var builder = new ModelBuilder().Add(File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath));
+        var model = builder.Build();
+
+        builder.Add("The corset was invented yesterday.");
+
+        var expected = Enumerable.Range(0, 100).Select(seed => model.GenerateSentence(new DefaultRandom(new Random(seed)))).ToList();
+        var actual = new string[expected.Count];
+
+        Parallel.For(0, actual.Length, seed => actual[seed] = model.GenerateSentence(new DefaultRandom(new Random(seed))));
+
+        actual.ShouldBe(expected);
+        builder.Build().GenerateSentence(new RandomStub(0)).ShouldNotBeNull();
+    }
+
+    [Test]
+    public void Batches_do_not_depend_on_the_threads()
+    {
+        var model = new ModelBuilder().Add(File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath)).Build();
+
+        var batch = model.GenerateBatch(200, "6a4156e2");
+
+        batch.Count.ShouldBe(200);
+        batch.ShouldBe(model.GenerateBatch(200, "6a4156e2", maxDegreeOfParallelism: 1));
+        batch.ShouldNotBe(model.GenerateBatch(200, "7189a168"));
+    }
+
+    [Test]
+    [Description("The second corpus is sanitized and lowercased like the first, so 'The dog' and 'the dog' share a state without the brackets.")]
+    public void Every_corpus_is_built_with_the_options()
+    {
+        var model = new ModelBuilder(new BuildOptions { NormalizeCase = true })
+            .Add("The dog was happy.")
+            .Add("the dog (was) sad.")
+            .Build();
+
+        model.GetStarterPhrases().ShouldBe(new[] { ("the dog", 2) });
+        model.GetSuccessors("dog was")!.Select(successor => successor.Word).ShouldBe(new[] { "happy.", "sad." });
+    }
+
+    [Test]
+    public void Builder_without_corpus_has_no_model()
+    {
+        Should.Throw<InvalidOperationException>(() => new ModelBuilder().Build());
+    }
+
+    [Test]
+    [Description("The frozen and mapped models share the same layout, so they generate the same sentences for the bundled corpus.")]
+    public void Frozen_model_generates_like_the_mapped_model()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel(File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath));
+
+        var path = Path.GetTempFileName();
+
+        try
+        {
+            generator.SaveMapped(path);
+
+            using var mapped = MappedMarkovTextGenerator.Open(path);
+
+            generator.Freeze().GenerateSentence(new DefaultRandom(new Random(5))).ShouldBe(mapped.GenerateSentence(new DefaultRandom(new Random(5))));
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+}
@@ -0,0 +1,383 @@
+﻿using System.Text.RegularExpressions;
+using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class GenerationOptionsTests
+{
+    private StringBasedMarkovTextGenerator generator = null!;
+
+    [SetUp]
+    public void Setup()
+    {
+        generator = new StringBasedMarkovTextGenerator();
+    }
+
+    [Test]
+    [Description("Cuts the infinite sentence 'The big dog was happy, but the small dog was happy, but...' back to the last comma.")]
+    public void Truncate_at_clause_boundary()
+    {
+        const string corpus = "The big dog was happy, but the small dog was very sad.";
+
+        generator.BuildMarkovModel(corpus);
+
+        var result = generator.Generate(new RandomStub(0), new GenerationOptions { MaxWordCount = 10, TruncateOnOverflow = true });
+
+        result.Text.ShouldBe("The big dog was happy.");
+        result.Truncated.ShouldBeTrue();
+    }
+
+    [Test]
+    [Description("The other fallback choice would continue past 'happy,' and end the sentence differently.")]
+    public void Truncated_choices_replay_the_truncated_sentence()
+    {
+        const string corpus = "The big dog was happy, but the small dog was very sad.";
+
+        generator.BuildMarkovModel(corpus);
+
+        var result = generator.Generate(new RandomStub(0), new GenerationOptions { MaxWordCount = 10, TruncateOnOverflow = true, RecordChoices = true });
+
+        result.Choices!.Count.ShouldBe(5);
+        result.Choices[^1].ShouldBe(Choice.Truncation);
+
+        var replayed = generator.Replay(result.Choices, new RandomStub(1));
+
+        replayed.Text.ShouldBe("The big dog was happy.");
+        replayed.Truncated.ShouldBeTrue();
+    }
+
+    [Test]
+    [Description("The truncated sentence has the 5 words up to its clause boundary.")]
+    public void Retries_count_the_words_of_truncated_sentences()
+    {
+        const string corpus = "The big dog was happy, but the small dog was very sad.";
+
+        generator.BuildMarkovModel(corpus);
+
+        var options = new GenerationOptions { MaxWordCount = 10, TruncateOnOverflow = true };
+
+        generator.GenerateWithRetries(new RandomStub(0), options, new RetryOptions { MinWordCount = 5 }).Text.ShouldBe("The big dog was happy.");
+
+        var exception = Should.Throw<GenerationFailedException>(() => generator.GenerateWithRetries(new RandomStub(0), options, new RetryOptions { MaxAttempts = 1, MinWordCount = 6 }));
+        exception.Failures[0].ShouldBe("Too short, 5 of at least 6 words: The big dog was happy.");
+    }
+
+    [Test]
+    [Description("Index 0 loops through 'happy, but the small dog was' until the word limit, which the default options truncate.")]
+    public void Default_options_apply_without_options()
+    {
+        const string corpus = "The big dog was happy, but the small dog was very sad.";
+
+        generator.BuildMarkovModel(corpus);
+        generator.DefaultOptions = new GenerationOptions { MaxWordCount = 10, TruncateOnOverflow = true };
+
+        generator.GenerateBest(new RandomStub(0), 1, new LengthScorer(3)).Text.ShouldBe("The big dog was happy.");
+        generator.Replay(new[] { new Choice(null, 0) }, new RandomStub(0)).Text.ShouldBe("The big dog was happy.");
+    }
+
+    [Test]
+    public void Overflow_throws_without_truncation()
+    {
+        const string corpus = "The big dog was happy, but the small dog was very sad.";
+
+        generator.BuildMarkovModel(corpus);
+
+        Should.Throw<SentenceOverflowException>(() => generator.Generate(new RandomStub(0), new GenerationOptions { MaxWordCount = 10 }));
+        Should.Throw<SentenceOverflowException>(() => generator.GenerateBest(new RandomStub(0), 3, new LengthScorer(3), new GenerationOptions { MaxWordCount = 10 }));
+        generator.Generate(new RandomStub(1), new GenerationOptions { MaxWordCount = 10 }).Truncated.ShouldBeFalse();
+    }
+
+    [Test]
+    [Description("The banned word 'happy' forces the 'dog was' -> 'was very' transition.")]
+    public void Banned_word_is_resampled()
+    {
+        const string corpus = "The big dog was happy but the small dog was very sad.";
+
+        generator.BuildMarkovModel(corpus);
+
+        var options = new GenerationOptions { ContentFilter = new ContentFilter(new[] { "Happy" }) };
+
+        generator.Generate(new RandomStub(0), options).Text.ShouldBe("The big dog was very sad.");
+    }
+
+    [Test]
+    public void Banned_pattern_without_alternatives_fails()
+    {
+        const string corpus = "The first sentence. The second sentence.";
+
+        generator.BuildMarkovModel(corpus);
+
+        var options = new GenerationOptions { ContentFilter = new ContentFilter(Array.Empty<string>(), new[] { new Regex("sentence") }) };
+
+        Should.Throw<ContentFilterException>(() => generator.Generate(new RandomStub(0), options));
+    }
+
+    [Test]
+    [Description("Replays the 'dog was' -> 'was very' choice against a model where the dog is 'very happy' instead.")]
+    public void Replay_recorded_choices_on_edited_model()
+    {
+        generator.BuildMarkovModel("The big dog was happy but the small dog was very sad.");
+
+        var recorded = generator.Generate(new RandomStub(1), new GenerationOptions { RecordChoices = true });
+
+        recorded.Text.ShouldBe("The big dog was very sad.");
+        recorded.Choices.ShouldNotBeNull();
+        generator.Replay(recorded.Choices, new RandomStub(0)).Text.ShouldBe(recorded.Text);
+
+        generator.BuildMarkovModel("The big dog was happy but the small dog was very happy.");
+
+        generator.Replay(recorded.Choices, new RandomStub(0)).Text.ShouldBe("The big dog was very happy.");
+    }
+
+    [Test]
+    [Description("'sad.' appears only once in the corpus, so 'dog was' -> 'was sad.' is excluded.")]
+    public void Rare_words_are_protected()
+    {
+        const string corpus = "The dog was happy. The dog was sad. The dog was happy.";
+
+        generator.BuildMarkovModel(corpus);
+
+        generator.Generate(new RandomStub(1), GenerationOptions.Default).Text.ShouldBe("The dog was sad.");
+        generator.Generate(new RandomStub(1), new GenerationOptions { ProtectRareWords = true }).Text.ShouldBe("The dog was happy.");
+    }
+
+    [Test]
+    public void Cancelled_generation_returns_partial_sentence()
+    {
+        generator.BuildMarkovModel("The big dog was happy but the small dog was very sad.");
+
+        var result = generator.Generate(new RandomStub(0), GenerationOptions.Default, new CancellationToken(true));
+
+        result.Cancelled.ShouldBeTrue();
+        result.Text.ShouldBe("The big");
+    }
+
+    [Test]
+    public void Cancelled_training_throws()
+    {
+        Should.Throw<OperationCanceledException>(() => generator.BuildMarkovModel("Word sentence.", new BuildOptions(), new CancellationToken(true)));
+    }
+
+    [Test]
+    [Description("Two of three corpus sentences open with a quote, so 'The dog sat.' is quoted for two of three random values.")]
+    public void Opening_style_is_reproduced()
+    {
+        const string corpus = "The dog sat. “Hi. “Bye.";
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { Preprocessors = new IPreprocessor[] { new CollapseWhitespacePreprocessor() } });
+
+        generator.OpeningStyles[OpeningStyle.Capitalized].ShouldBe(1);
+        generator.OpeningStyles[OpeningStyle.Quoted].ShouldBe(2);
+
+        var options = new GenerationOptions { ReproduceOpeningStyle = true };
+
+        generator.Generate(new RandomStub(0), options).Text.ShouldBe("The dog sat.");
+        generator.Generate(new RandomStub(1), options).Text.ShouldBe("“The dog sat.”");
+    }
+
+    [Test]
+    [Description("Each opening quote is as likely as in the corpus, and quoted sentences are capitalized.")]
+    public void Opening_quotes_are_sampled_and_capitalized()
+    {
+        const string corpus = "the dog sat. “Hi. ‘Bye.";
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { Preprocessors = new IPreprocessor[] { new CollapseWhitespacePreprocessor() } });
+
+        var options = new GenerationOptions { ReproduceOpeningStyle = true };
+
+        generator.Generate(new RandomStub(0), options).Text.ShouldBe("the dog sat.");
+        generator.Generate(new RandomStub(1), options).Text.ShouldBe("“The dog sat.”");
+        generator.Generate(new RandomStub(2), options).Text.ShouldBe("‘The dog sat.’");
+    }
+
+    [Test]
+    [Description("Picks the short or the long sentence out of 20 candidates depending on the target length.")]
+    public void Best_candidate_is_chosen()
+    {
+        const string corpus = "The dog sat. The dog sat on the big red mat.";
+
+        generator.BuildMarkovModel(corpus);
+
+        var random = new DefaultRandom(new Random(42));
+
+        generator.GenerateBest(random, 20, new LengthScorer(3)).Text.ShouldBe("The dog sat.");
+        generator.GenerateBest(random, 20, new LengthScorer(9)).Text.ShouldBe("The dog sat on the big red mat.");
+    }
+
+    [Test]
+    [Description("'dog was' -> 'was happy.' occurs twice and 'dog was' -> 'was sad.' once, so only 'happy.' is in the top 1.")]
+    public void Top_k_continuations()
+    {
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. The dog was sad.");
+
+        generator.Generate(new RandomStub(2), GenerationOptions.Default).Text.ShouldBe("The dog was sad.");
+        generator.Generate(new RandomStub(2), new GenerationOptions { TopK = 1 }).Text.ShouldBe("The dog was happy.");
+        Should.Throw<ArgumentOutOfRangeException>(() => generator.Generate(new RandomStub(2), new GenerationOptions { TopK = 0 }));
+    }
+
+    [Test]
+    [Description("'happy.' alone has probability 2/3, which reaches p = 0.5 but not p = 0.9.")]
+    public void Top_p_continuations()
+    {
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. The dog was sad.");
+
+        generator.Generate(new RandomStub(2), new GenerationOptions { TopP = 0.5 }).Text.ShouldBe("The dog was happy.");
+        generator.Generate(new RandomStub(2), new GenerationOptions { TopP = 0.9 }).Text.ShouldBe("The dog was sad.");
+        Should.Throw<ArgumentOutOfRangeException>(() => generator.Generate(new RandomStub(2), new GenerationOptions { TopP = 0 }));
+        Should.Throw<ArgumentOutOfRangeException>(() => generator.Generate(new RandomStub(2), new GenerationOptions { TopP = 1.5 }));
+    }
+
+    [Test]
+    [Description("'sad.' and 'happy.' are equally frequent, 'sad.' comes first in the corpus and 'happy.' comes first lexicographically.")]
+    public void Tie_breaking_among_equally_frequent_continuations()
+    {
+        generator.BuildMarkovModel("The dog was sad. The dog was happy.");
+
+        var corpusOrder = new GenerationOptions { TopK = 1 };
+        var lexicographic = corpusOrder with { TieBreaking = TieBreaking.Lexicographic };
+
+        generator.Generate(new RandomStub(0), corpusOrder).Text.ShouldBe("The dog was sad.");
+        generator.Generate(new RandomStub(0), lexicographic).Text.ShouldBe("The dog was happy.");
+    }
+
+    [Test]
+    public void Greedy_picks_most_frequent_continuation()
+    {
+        generator.BuildMarkovModel("The dog was sad. The dog was happy. The dog was happy.");
+
+        var options = new GenerationOptions { Greedy = true };
+
+        generator.Generate(new RandomStub(0), options).Text.ShouldBe("The dog was happy.");
+        generator.Generate(new RandomStub(1), options).Text.ShouldBe("The dog was happy.");
+        generator.Generate(new RandomStub(2), options).Text.ShouldBe("The dog was happy.");
+    }
+
+    [Test]
+    [Description("'happy.' is the most frequent continuation, but banned, so 'sad.' is the most frequent allowed one.")]
+    public void Greedy_picks_most_frequent_allowed_continuation()
+    {
+        generator.BuildMarkovModel("The dog was sad. The dog was happy. The dog was happy.");
+
+        var options = new GenerationOptions { Greedy = true, ContentFilter = new ContentFilter(new[] { "happy" }) };
+
+        generator.Generate(new RandomStub(1), options).Text.ShouldBe("The dog was sad.");
+    }
+
+    [Test]
+    public void Beam_search_finds_most_probable_sentences()
+    {
+        generator.BuildMarkovModel("The dog was sad. The dog was happy. The dog was happy.");
+
+        generator.GenerateMostProbable(2, 2).ShouldBe(new[] { "The dog was happy.", "The dog was sad." });
+    }
+
+    [Test]
+    [Description("'sad.' and 'happy.' are equally probable, 'sad.' comes first in the corpus and 'happy.' comes first lexicographically.")]
+    public void Beam_search_breaks_ties()
+    {
+        generator.BuildMarkovModel("The dog was sad. The dog was happy.");
+
+        generator.GenerateMostProbable(2).ShouldBe(new[] { "The dog was sad." });
+        generator.GenerateMostProbable(2, tieBreaking: TieBreaking.Lexicographic).ShouldBe(new[] { "The dog was happy." });
+    }
+
+    [Test]
+    [Description("'dog was' -> 'was very' has probability 2/3, so looping through 'happy but the small dog' is less probable per word.")]
+    public void Beam_search_escapes_infinite_sentence()
+    {
+        generator.BuildMarkovModel("The big dog was very sad. The big dog was happy but the small dog was very sad.");
+
+        generator.GenerateMostProbable(3).ShouldBe(new[] { "The big dog was very sad." });
+    }
+
+    [Test]
+    [Description("Two of three sentences start with 'The dog', but each of the two distinct starter phrases is equally likely in uniform mode.")]
+    public void Uniform_starter_selection()
+    {
+        generator.BuildMarkovModel("The dog sat. The dog sat. A cat ran.");
+
+        var options = new GenerationOptions { StarterSelection = StarterSelection.Uniform };
+
+        generator.Generate(new RandomStub(1), GenerationOptions.Default).Text.ShouldBe("The dog sat.");
+        generator.Generate(new RandomStub(1), options).Text.ShouldBe("A cat ran.");
+        generator.GetStarterPhrases().ShouldBe(new[] { ("The dog", 2), ("A cat", 1) });
+    }
+
+    [Test]
+    [Description("'The big dog was happy.' is a verbatim copy and 'The big dog was sad.' is new.")]
+    public void Retry_rejects_verbatim_copies()
+    {
+        generator.BuildMarkovModel("The big dog was happy. The small dog was sad.");
+
+        var retryOptions = new RetryOptions { MaxAttempts = 3 };
+
+        var exception = Should.Throw<GenerationFailedException>(() => generator.GenerateWithRetries(new RandomStub(0), GenerationOptions.Default, retryOptions));
+        exception.Failures.Count.ShouldBe(3);
+        exception.Failures[0].ShouldBe("Verbatim copy of a corpus sentence: The big dog was happy.");
+
+        var result = generator.GenerateWithRetries(new DefaultRandom(new Random(42)), GenerationOptions.Default, new RetryOptions { MaxAttempts = 50 });
+        result.Text.ShouldBeOneOf("The big dog was sad.", "The small dog was happy.");
+    }
+
+    [Test]
+    public void Retry_rejects_short_sentences()
+    {
+        generator.BuildMarkovModel("The dog sat.");
+
+        var exception = Should.Throw<GenerationFailedException>(() =>
+            generator.GenerateWithRetries(new RandomStub(0), GenerationOptions.Default, new RetryOptions { MinWordCount = 4, RejectVerbatimCopies = false }));
+
+        exception.Failures[0].ShouldBe("Too short, 3 of at least 4 words: The dog sat.");
+    }
+
+    [Test]
+    public void Time_budget_returns_best_candidate_so_far()
+    {
+        generator.BuildMarkovModel("The dog sat.");
+
+        var result = generator.GenerateBest(new RandomStub(0), 100, new LengthScorer(3), timeBudget: TimeSpan.Zero);
+        result.Cancelled.ShouldBeTrue();
+        result.Text.ShouldBe("The dog");
+
+        result = generator.GenerateBest(new RandomStub(0), 100, new LengthScorer(3), timeBudget: TimeSpan.FromMinutes(1));
+        result.Cancelled.ShouldBeFalse();
+        result.Text.ShouldBe("The dog sat.");
+    }
+
+    [Test]
+    [Description("The observer sees every transition and vetoes 'happy.', so 'sad.' is chosen instead.")]
+    public void Observer_can_veto_transitions()
+    {
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+
+        var observer = new VetoingObserver("happy.");
+
+        generator.Generate(new RandomStub(0), new GenerationOptions { Observer = observer }).Text.ShouldBe("The big dog was sad.");
+
+        observer.Steps.ShouldBe(new[]
+        {
+            new TransitionStep("The big", "dog", 1),
+            new TransitionStep("big dog", "was", 1),
+            new TransitionStep("dog was", "happy.", 2),
+            new TransitionStep("dog was", "sad.", 2),
+        });
+    }
+
+    private class VetoingObserver : IGenerationObserver
+    {
+        private readonly string vetoedWord;
+
+        public readonly List<TransitionStep> Steps = new();
+
+        public VetoingObserver(string vetoedWord)
+        {
+            this.vetoedWord = vetoedWord;
+        }
+
+        public bool OnTransition(TransitionStep step)
+        {
+            Steps.Add(step);
+            return step.Word != vetoedWord;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+﻿using System.Net;
+using System.Net.Sockets;
+using System.Text.Json;
+using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class GenerationServerTests
+{
+    private static GenerationServer CreateServer()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+        return new GenerationServer(generator, "http://localhost:8080/");
+    }
+
+    [Test]
+    [Description("The same seed and count always return the same sentences.")]
+    public void Generate_returns_seeded_sentences()
+    {
+        var server = CreateServer();
+
+        var (statusCode, json) = server.Handle("GET", "/generate?count=3&seed=dog");
+
+        statusCode.ShouldBe(200);
+
+        var response = JsonDocument.Parse(json).RootElement;
+        response.GetProperty("seed").GetString().ShouldBe("dog");
+        response.GetProperty("sentences").GetArrayLength().ShouldBe(3);
+
+        server.Handle("GET", "/generate?count=3&seed=dog").Json.ShouldBe(json);
+    }
+
+    [Test]
+    public void Invalid_requests_are_rejected()
+    {
+        var server = CreateServer();
+
+        server.Handle("GET", "/generate?count=0").StatusCode.ShouldBe(400);
+        server.Handle("GET", $"/generate?count={GenerationServer.MaxCount + 1}").StatusCode.ShouldBe(400);
+        server.Handle("POST", "/generate").StatusCode.ShouldBe(405);
+        server.Handle("GET", "/favicon.ico").StatusCode.ShouldBe(404);
+    }
+
+    [Test]
+    [Description("A request failing with an unexpected exception is answered with 500 and the server keeps serving.")]
+    public async Task Failed_requests_do_not_stop_the_server()
+    {
+        var port = FreePort();
+
+        // Generating without a model fails with an InvalidOperationException
+        var server = new GenerationServer(new StringBasedMarkovTextGenerator(), $"http://localhost:{port}/");
+        using var cancellation = new CancellationTokenSource();
+        var running = Task.Run(() => server.Run(cancellation.Token));
+
+        using var client = new HttpClient();
+
+        for (var i = 0; i < 2; i++)
+        {
+            var response = await client.GetAsync($"http://localhost:{port}/generate");
+            response.StatusCode.ShouldBe(HttpStatusCode.InternalServerError);
+        }
+
+        cancellation.Cancel();
+        await running;
+    }
+
+    private static int FreePort()
+    {
+        var listener = new TcpListener(IPAddress.Loopback, 0);
+        listener.Start();
+        var port = ((IPEndPoint)listener.LocalEndpoint).Port;
+        listener.Stop();
+        return port;
+    }
+}
@@ -0,0 +1,76 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class InterpolatedGeneratorTests
+{
+    private const string Corpus = "The big cat sat. A big dog ran. My dog ran.";
+
+    private readonly InterpolatedMarkovTextGenerator generator = new();
+
+    [SetUp]
+    public void SetUp()
+    {
+        generator.Weights = null;
+        generator.Smoothing = Smoothing.Interpolation;
+        generator.BuildMarkovModel(Corpus);
+    }
+
+    [Test]
+    [Description("The stub picks the highest order, so the sentence follows 'big cat' like an order 2 chain.")]
+    public void Highest_order_is_followed()
+    {
+        generator.GenerateSentence(new RandomStub(3)).ShouldBe("The big cat sat.");
+    }
+
+    [Test]
+    [Description("With order 2 weighted 0, 'big' is continued by order 1 with 'dog', which never follows 'The big' in the corpus.")]
+    public void Lower_orders_combine_the_corpus_sentences()
+    {
+        generator.Weights = new[] { 1.0, 0.0 };
+
+        generator.GenerateSentence(new RandomStub(3)).ShouldBe("The big dog ran.");
+    }
+
+    [Test]
+    [Description("'dog' never follows 'The big', and follows 'big' half of the time.")]
+    public void Probabilities_are_interpolated()
+    {
+        generator.Probability(new[] { "The", "big" }, "dog").ShouldBe(0.25, 1e-9);
+        generator.Probability(new[] { "The", "big" }, "cat").ShouldBe(0.75, 1e-9);
+
+        generator.Weights = new[] { 1.0 };
+        Should.Throw<InvalidOperationException>(() => generator.Probability(new[] { "big" }, "cat"));
+    }
+
+    [Test]
+    [Description("Order 2 has no continuation seen twice, so its singletons are discounted by half, and 'dog' gets 0.6 of the order 1 estimate of 1/3 * 1/2.")]
+    public void Katz_backoff_discounts_seen_continuations()
+    {
+        generator.Smoothing = Smoothing.KatzBackoff;
+        var context = new[] { "The", "big" };
+
+        generator.Probability(context, "cat").ShouldBe(0.5, 1e-9);
+        generator.Probability(context, "dog").ShouldBe(0.1, 1e-9);
+        new[] { "The", "big", "cat", "sat.", "A", "dog", "ran.", "My" }.Sum(word => generator.Probability(context, word)).ShouldBe(1, 1e-9);
+        generator.GenerateSentence(new RandomStub(3)).ShouldBe("The big cat sat.");
+    }
+
+    [Test]
+    [Description("'sat.' never follows 'dog', so the sentence is impossible with interpolation but not with Kneser-Ney smoothing, which keeps some probability for every word of the vocabulary.")]
+    public void Kneser_ney_smoothing_scores_unseen_transitions()
+    {
+        const string sentence = "The big dog sat.";
+        var vocabulary = new[] { "The", "big", "cat", "sat.", "A", "dog", "ran.", "My" };
+
+        generator.Perplexity(sentence).ShouldBe(double.PositiveInfinity);
+
+        generator.Smoothing = Smoothing.KneserNey;
+
+        generator.Perplexity(sentence).ShouldBeInRange(1, vocabulary.Length);
+        generator.Perplexity("The big cat sat.").ShouldBeLessThan(generator.Perplexity(sentence));
+        vocabulary.Sum(word => generator.Probability(new[] { "The", "big" }, word)).ShouldBe(1, 1e-9);
+        vocabulary.Sum(word => generator.Probability(new[] { "big", "sat." }, word)).ShouldBe(1, 1e-9);
+        generator.GenerateSentence(new RandomStub(3)).ShouldBe("The big cat sat.");
+    }
+}
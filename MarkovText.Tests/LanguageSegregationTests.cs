@@ -0,0 +1,42 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class LanguageSegregationTests
+{
+    private const string Corpus = "The dog is in the house. Der Hund ist in dem Haus.";
+
+    [Test]
+    public void Detects_sentence_language()
+    {
+        var detector = new StopwordLanguageDetector();
+
+        detector.DetectLanguage("The dog is in the house.".Split(' ')).ShouldBe("en");
+        detector.DetectLanguage("Der Hund ist in dem Haus.".Split(' ')).ShouldBe("de");
+        detector.DetectLanguage("Woof.".Split(' ')).ShouldBeNull();
+    }
+
+    [Test]
+    public void Foreign_sentences_are_dropped()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+
+        generator.BuildMarkovModel(Corpus, new BuildOptions { LanguageDetector = new StopwordLanguageDetector(), Language = "de" });
+
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("Der Hund ist in dem Haus.");
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("Der Hund ist in dem Haus.");
+    }
+
+    [Test]
+    public void Sentences_are_routed_to_per_language_models()
+    {
+        var generator = new LanguageSegregatedGenerator();
+
+        generator.BuildMarkovModel(Corpus);
+
+        generator.Languages.ShouldBe(new[] { "en", "de" }, ignoreOrder: true);
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog is in the house.");
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("Der Hund ist in dem Haus.");
+        generator.GenerateSentence("de", new RandomStub(0)).ShouldBe("Der Hund ist in dem Haus.");
+    }
+}
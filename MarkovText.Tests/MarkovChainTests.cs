@@ -0,0 +1,66 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class MarkovChainTests
+{
+    [Test]
+    [Description("Both melodies start with C then E, and E is followed by G in the first and by A in the second.")]
+    public void Sequences_of_any_items_are_generated()
+    {
+        var chain = new MarkovChain<int>(1);
+        chain.Add(new[] { 60, 64, 67 });
+        chain.Add(new[] { 60, 64, 69 });
+
+        chain.Generate(new RandomStub(0)).ShouldBe(new[] { 60, 64, 67 });
+        chain.Generate(new RandomStub(1)).ShouldBe(new[] { 60, 64, 69 });
+    }
+
+    [Test]
+    public void Short_sequences_are_skipped()
+    {
+        var chain = new MarkovChain<char>(3);
+        chain.Add("ab");
+
+        chain.SequenceCount.ShouldBe(0);
+        Should.Throw<InvalidOperationException>(() => chain.Generate(new RandomStub(0)).ToList());
+
+        chain.Add("abcd");
+
+        new string(chain.Generate(new RandomStub(0)).ToArray()).ShouldBe("abcd");
+    }
+
+    [Test]
+    [Description("Removing the last transition to c and the last sequence starting with a leaves the transition added first, whose state is shared.")]
+    public void Negative_counts_remove_the_last_occurrences()
+    {
+        var chain = new MarkovChain<char>(1);
+        chain.Add("ab");
+        chain.Add("ac");
+
+        chain.AddTransition(new[] { 'a' }, new[] { 'c' }, -1);
+        chain.AddStarter(new[] { 'a' }, -1);
+
+        chain.StarterCounts.Values.ShouldBe(new[] { 1 });
+        chain.TryGetTransitions(new[] { 'a' }, out var transitions).ShouldBeTrue();
+        transitions.Select(next => next[0]).ShouldBe(new[] { 'b' });
+        chain.AddTransition(new[] { 'a' }, new[] { 'b' }).ShouldBeSameAs(transitions[0]);
+    }
+
+    [Test]
+    [Description("C is followed by E twice and by G once, and G by the high C once, so the state G has no transitions left.")]
+    public void Rare_transitions_are_removed()
+    {
+        var chain = new MarkovChain<int>(1);
+        chain.Add(new[] { 60, 64 });
+        chain.Add(new[] { 60, 64 });
+        chain.Add(new[] { 60, 67, 72 });
+        var removed = new List<(int State, int Next, int Count)>();
+
+        chain.RemoveRareTransitions(2, (state, next, count) => removed.Add((state[0], next[0], count))).ShouldBe(new PruneReport(2, 2, 1));
+
+        removed.ShouldBe(new[] { (60, 67, 1), (67, 72, 1) });
+        chain.ContinuedStates.Select(state => state[0]).ShouldBe(new[] { 60 });
+        chain.Generate(new RandomStub(2)).ShouldBe(new[] { 60, 64 });
+    }
+}
@@ -117,17 +117,27 @@ public class MarkovTextGenerator
 
         IGenerator generator1 = new ArrayBasedMarkovTextGenerator();
         IGenerator generator2 = new SpanBasedMarkovTextGenerator();
+        IGenerator generator3 = new PackedMarkovTextGenerator();
+        IGenerator generator4 = new InternedMarkovTextGenerator();
 
         generator1.BuildMarkovModel(corpus);
         generator2.BuildMarkovModel(corpus);
+        generator3.BuildMarkovModel(corpus);
+        generator4.BuildMarkovModel(corpus);
 
         var sentence1 = generator1.GenerateSentence(seed);
         var sentence2 = generator2.GenerateSentence(seed);
+        var sentence3 = generator3.GenerateSentence(seed);
+        var sentence4 = generator4.GenerateSentence(seed);
 
         Console.WriteLine(sentence1);
         Console.WriteLine(sentence2);
+        Console.WriteLine(sentence3);
+        Console.WriteLine(sentence4);
 
         sentence1.ShouldBe(sentence2);
+        sentence1.ShouldBe(sentence3);
+        sentence1.ShouldBe(sentence4);
     }
 
     private static IEnumerable<IGenerator> Generators()
@@ -135,5 +145,8 @@ public class MarkovTextGenerator
         yield return new StringBasedMarkovTextGenerator();
         yield return new ArrayBasedMarkovTextGenerator();
         yield return new SpanBasedMarkovTextGenerator();
+        yield return new PackedMarkovTextGenerator();
+        yield return new InternedMarkovTextGenerator();
+        yield return new TrieMarkovTextGenerator();
     }
 }
\ No newline at end of file
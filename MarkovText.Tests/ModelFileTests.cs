@@ -0,0 +1,207 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ModelFileTests
+{
+    [Test]
+    public void Model_file_round_trip()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.", new BuildOptions { Tokenizer = new PunctuationTokenizer() });
+
+        using var stream = new MemoryStream();
+        generator.Save(stream);
+        stream.Position = 0;
+
+        var loaded = StringBasedMarkovTextGenerator.Load(stream);
+
+        loaded.GenerateSentence(new RandomStub(0)).ShouldBe("The big dog was happy.");
+        loaded.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+        loaded.CreatePatch(generator).Transitions.ShouldBeEmpty();
+    }
+
+    [Test]
+    public void Compressed_model_file_is_detected()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+
+        using var stream = new MemoryStream();
+        generator.Save(stream, compress: true);
+        stream.Position = 0;
+
+        stream.ReadByte().ShouldBe(0x1F);
+        stream.Position = 0;
+
+        StringBasedMarkovTextGenerator.Load(stream).GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+    }
+
+    [Test]
+    public void Model_file_rejects_other_versions()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy.");
+
+        using var stream = new MemoryStream();
+        generator.Save(stream);
+
+        var bytes = stream.ToArray();
+        bytes[4] = ModelFile.CurrentFormatVersion + 1;   // The version follows the 4 magic bytes
+
+        Should.Throw<InvalidDataException>(() => StringBasedMarkovTextGenerator.Load(new MemoryStream(bytes))).Message.ShouldContain("version");
+        Should.Throw<InvalidDataException>(() => StringBasedMarkovTextGenerator.Load(new MemoryStream("not a model"u8.ToArray())));
+    }
+
+    [Test]
+    public void Json_round_trip()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+
+        var table = TransitionTable.FromJson(generator.ToJson());
+
+        table.StarterPhrases["The big"].ShouldBe(2);
+        table.Transitions["dog was"]["happy."].ShouldBe(1);
+
+        var loaded = StringBasedMarkovTextGenerator.FromJson(generator.ToJson());
+
+        loaded.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+        loaded.CreatePatch(generator).Transitions.ShouldBeEmpty();
+    }
+
+    [Test]
+    public void Mapped_model_generates_like_the_saved_model()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.", new BuildOptions { Tokenizer = new PunctuationTokenizer() });
+
+        var path = Path.GetTempFileName();
+
+        try
+        {
+            generator.SaveMapped(path);
+
+            using var mapped = MappedMarkovTextGenerator.Open(path);
+
+            mapped.GenerateSentence(new RandomStub(0)).ShouldBe("The big dog was happy.");
+            mapped.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+            Should.Throw<NotSupportedException>(() => mapped.BuildMarkovModel("The big dog was happy."));
+
+            // Disposing again when leaving the using block must not release the mapped pointer twice
+            mapped.Dispose();
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+
+    [Test]
+    public void Mapped_model_rejects_other_files()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy.");
+
+        var path = Path.GetTempFileName();
+
+        try
+        {
+            generator.Save(path);
+
+            Should.Throw<InvalidDataException>(() => MappedMarkovTextGenerator.Open(path));
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+
+    [Test]
+    [Description("The second symbol offset is overwritten to point past the end of the file.")]
+    public void Mapped_model_rejects_corrupt_symbol_offsets()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy.");
+
+        var path = Path.GetTempFileName();
+
+        try
+        {
+            generator.SaveMapped(path);
+
+            using (var stream = File.OpenWrite(path))
+            {
+                stream.Position = 36;
+                stream.Write(BitConverter.GetBytes(int.MaxValue));
+            }
+
+            Should.Throw<InvalidDataException>(() => MappedMarkovTextGenerator.Open(path));
+        }
+        finally
+        {
+            File.Delete(path);
+        }
+    }
+
+    [Test]
+    [Description("'the dog' is followed by 'barks' or ends the sentence with equal probability, so 'the' leads to 'dog' and its ending half of the time each.")]
+    public void Arpa_import_drives_generation()
+    {
+        const string arpa = """
+            \data\
+            ngram 1=5
+            ngram 2=5
+
+            \1-grams:
+            -99 <s> -0.3
+            -0.6 the -0.3
+            -0.6 dog -0.3
+            -0.9 barks -0.3
+            -0.6 </s>
+
+            \2-grams:
+            0 <s> the
+            0 the dog
+            -0.30103 dog barks
+            -0.30103 dog </s>
+            0 barks </s>
+            \end\
+            """;
+
+        var generator = StringBasedMarkovTextGenerator.FromArpa(new StringReader(arpa));
+
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("the dog barks");
+        generator.GenerateSentence(new RandomStub(99)).ShouldBe("the dog");
+        generator.GetSuccessors("the").ShouldBe(new[] { ("dog", "dog", 50), ("dog</s>", "dog", 50) });
+        generator.PredictNext("the").ShouldBe(new[] { ("dog", 1.0) });
+        generator.Validate().Warnings.ShouldBeEmpty();
+
+        var frozen = generator.Freeze();
+
+        frozen.GenerateSentence(new RandomStub(0)).ShouldBe("the dog barks");
+        frozen.GenerateSentence(new RandomStub(99)).ShouldBe("the dog");
+        generator.ContinueText("the", new RandomStub(0)).ShouldBe("the dog barks");
+    }
+
+    [Test]
+    public void Arpa_export_round_trip()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+
+        var writer = new StringWriter();
+        generator.ExportArpa(writer);
+
+        var arpa = writer.ToString();
+
+        arpa.ShouldContain("ngram 3=7");
+        arpa.ShouldContain("-0.30103\tdog was happy.");
+        arpa.ShouldContain("0\twas sad. </s>");
+
+        var loaded = StringBasedMarkovTextGenerator.FromArpa(new StringReader(arpa));
+
+        loaded.GenerateSentence(new RandomStub(0)).ShouldBe("The big dog was happy.");
+        loaded.GenerateSentence(new RandomStub(99)).ShouldBe("The big dog was sad.");
+    }
+}
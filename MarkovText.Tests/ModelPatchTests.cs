@@ -0,0 +1,144 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ModelPatchTests
+{
+    [Test]
+    [Description("Patches a model of 'The big dog was happy.' to also contain 'The big dog was sad.'")]
+    public void Patch_adds_new_transitions()
+    {
+        var oldGenerator = new StringBasedMarkovTextGenerator();
+        var newGenerator = new StringBasedMarkovTextGenerator();
+
+        oldGenerator.BuildMarkovModel("The big dog was happy.");
+        newGenerator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+
+        var patch = ModelPatch.FromJson(newGenerator.CreatePatch(oldGenerator).ToJson());
+
+        patch.StarterPhrases.Count.ShouldBe(1);
+        patch.Transitions.Count.ShouldBe(3);
+
+        oldGenerator.ApplyPatch(patch);
+
+        oldGenerator.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+        oldGenerator.CreatePatch(newGenerator).Transitions.ShouldBeEmpty();
+    }
+
+    [Test]
+    public void Patch_removes_old_transitions()
+    {
+        var oldGenerator = new StringBasedMarkovTextGenerator();
+        var newGenerator = new StringBasedMarkovTextGenerator();
+
+        oldGenerator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+        newGenerator.BuildMarkovModel("The big dog was sad.");
+
+        oldGenerator.ApplyPatch(newGenerator.CreatePatch(oldGenerator));
+
+        oldGenerator.GenerateSentence(new RandomStub(0)).ShouldBe("The big dog was sad.");
+        oldGenerator.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+    }
+
+    [Test]
+    [Description("'happy.' is no longer in the vocabulary once patched out, so smoothing spreads the same probability as in the new model.")]
+    public void Patch_removes_words_from_the_smoothed_vocabulary()
+    {
+        var oldGenerator = new StringBasedMarkovTextGenerator();
+        var newGenerator = new StringBasedMarkovTextGenerator();
+
+        oldGenerator.BuildMarkovModel("The big dog was happy. The big dog was sad.", new BuildOptions { AdditiveSmoothing = 1 });
+        newGenerator.BuildMarkovModel("The big dog was sad.", new BuildOptions { AdditiveSmoothing = 1 });
+
+        oldGenerator.ApplyPatch(newGenerator.CreatePatch(oldGenerator));
+
+        oldGenerator.ScoreSentence("The big dog was glad.").ShouldBe(newGenerator.ScoreSentence("The big dog was glad."), 1e-9);
+    }
+
+    [Test]
+    [Description("Replays a learned sentence from the journal on top of a copy of the base model.")]
+    public void Journal_replays_learned_sentences()
+    {
+        var path = Path.GetTempFileName();
+        var journal = new ModelJournal(path);
+
+        try
+        {
+            var learningGenerator = new StringBasedMarkovTextGenerator();
+            learningGenerator.BuildMarkovModel("The big dog was happy.");
+
+            journal.Append(learningGenerator.Learn("The big dog was sad."));
+            learningGenerator.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+
+            var restoredGenerator = new StringBasedMarkovTextGenerator();
+            restoredGenerator.BuildMarkovModel("The big dog was happy.");
+
+            journal.Replay(restoredGenerator);
+
+            restoredGenerator.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was sad.");
+            restoredGenerator.CreatePatch(learningGenerator).Transitions.ShouldBeEmpty();
+        }
+        finally
+        {
+            journal.Clear();
+        }
+    }
+
+    [Test]
+    [Description("'sad.' follows 'dog was' a third of the time in the first corpus and always in the second, while 'was' always follows 'The dog' in both.")]
+    public void Diff_compares_the_models_of_two_corpora()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        var otherGenerator = new StringBasedMarkovTextGenerator();
+
+        generator.BuildMarkovModel("The dog was happy. The dog was happy. The dog was sad.");
+        otherGenerator.BuildMarkovModel("The dog was sad. The cat was sad.");
+
+        var diff = generator.Diff(otherGenerator);
+
+        diff.PhrasesOnlyInThis.ShouldBe(new[] { "was happy." });
+        diff.PhrasesOnlyInOther.ShouldBe(new[] { "The cat", "cat was" }, ignoreOrder: true);
+        diff.TransitionsOnlyInThis.ShouldBe(new[] { ("dog was", "happy.") });
+        diff.TransitionsOnlyInOther.ShouldBe(new[] { ("The cat", "was"), ("cat was", "sad.") }, ignoreOrder: true);
+        diff.Shifts.Select(shift => (shift.Phrase, shift.Word)).ShouldBe(new[] { ("dog was", "sad.") });
+        diff.Shifts[0].Shift.ShouldBe(-2.0 / 3, 1e-9);
+
+        otherGenerator.BuildMarkovModel("The dog was sad.", order: 3);
+        Should.Throw<ArgumentException>(() => generator.Diff(otherGenerator));
+    }
+
+    [Test]
+    [Description("'cat sat' ends the corpus without a sentence delimiter, which is valid, while the patched transitions don't lead to the phrase ending with their word.")]
+    public void Validation_reports_broken_invariants()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The dog was happy. My cat sat");
+
+        var report = generator.Validate();
+
+        report.IsValid.ShouldBeTrue();
+        report.Warnings.ShouldBe(new[] { "Phrase 'cat sat' has no transitions and does not end a sentence." });
+
+        var corrupted = new StringBasedMarkovTextGenerator();
+        corrupted.ApplyPatch(new ModelPatch
+        {
+            Order = 2,
+            StarterPhrases = { new StarterPhraseDelta("The dog", 1) },
+            Transitions =
+            {
+                new TransitionDelta("The dog", "dog was", "was", 1),
+                new TransitionDelta("dog was", "dog barked", "barked", 1),
+                new TransitionDelta("dog was", "was ", "", 1),
+            },
+        });
+
+        report = corrupted.Validate();
+
+        report.IsValid.ShouldBeFalse();
+        report.Errors.ShouldBe(new[]
+        {
+            "Phrase 'dog was' has a transition with word 'barked' to the unrelated phrase 'dog barked'.",
+            "Phrase 'dog was' has a transition to the malformed token ''.",
+        });
+    }
+}
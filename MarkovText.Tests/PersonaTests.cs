@@ -0,0 +1,34 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class PersonaTests
+{
+    [Test]
+    [Description("A persona restores the model, tokenizer, and casing it was saved with.")]
+    public void Persona_round_trip()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("the dog saw Dublin.", new BuildOptions { Tokenizer = new PunctuationTokenizer(), NormalizeCase = true });
+
+        var persona = Persona.FromJson(generator.ToPersona("Dog", "A dog in Dublin").ToJson());
+
+        persona.Name.ShouldBe("Dog");
+        persona.Tokenizer.ShouldBe("punctuation");
+
+        StringBasedMarkovTextGenerator.FromPersona(persona).GenerateSentence(new RandomStub(0)).ShouldBe("The dog saw Dublin.");
+    }
+
+    [Test]
+    public void Persona_keeps_sampling_defaults()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The (big dog was happy.", new BuildOptions { Preprocessors = Array.Empty<IPreprocessor>() });
+        generator.DefaultOptions = new GenerationOptions { PostProcessors = new[] { new BalancedPunctuationPostProcessor(strip: true) } };
+
+        var loaded = StringBasedMarkovTextGenerator.FromPersona(Persona.FromJson(generator.ToPersona("Dog").ToJson()));
+
+        loaded.DefaultOptions.PostProcessors.Count.ShouldBe(1);
+        loaded.GenerateSentence(new RandomStub(0)).ShouldBe("The big dog was happy.");
+    }
+}
@@ -0,0 +1,56 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class PoetryTests
+{
+    [Test]
+    public void English_syllables_are_counted()
+    {
+        var counter = new EnglishSyllableCounter();
+
+        new[] { "The", "make", "little", "happy.", "Dublin's", "—" }.Select(counter.CountSyllables).ShouldBe(new[] { 1, 1, 2, 2, 2, 0 });
+    }
+
+    [Test]
+    [Description("'A cat was sad.' has 4 syllables and 'The big dog was happy.' has 6, and no sentence has 5.")]
+    public void Lines_have_the_syllables()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy. A cat was sad. The old cat sat.");
+
+        generator.GenerateSyllableStanza(new RandomStub(0), new[] { 4, 6 }).ShouldBe("A cat was sad.\nThe big dog was happy.");
+        Should.Throw<GenerationFailedException>(() => generator.GenerateSyllableStanza(new RandomStub(0), new[] { 5 }))
+            .Message.ShouldBe("No line of 5 syllables was found, 0 of 1 lines were generated.");
+    }
+
+    [Test]
+    public void Rhymes_are_matched_by_spelling()
+    {
+        var rhymer = new SuffixRhymer();
+
+        new[] { "cat", "Make", "boat", "the", "love", "move", "—" }.Select(rhymer.RhymeKey).ShouldBe(new[] { "at", "ake", "oat", "e", "ove", "ove", "" });
+    }
+
+    [Test]
+    [Description("'mat.' rhymes with 'sat.' and the reverse model grows 'The old cat sat.' from it, but nothing else ends with a rhyme of 'happy.'.")]
+    public void Couplet_lines_rhyme()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The dog sat on the mat. A cat was happy. The old cat sat.", new BuildOptions { BuildReverseModel = true });
+
+        generator.GenerateCouplet(new RandomStub(0)).ShouldBe("The dog sat on the mat.\nThe old cat sat.");
+        Should.Throw<GenerationFailedException>(() => generator.GenerateCouplet(new RandomStub(1)));
+    }
+
+    [Test]
+    [Description("The sentence endings of the reverse model start with the '.' token, which is skipped to find the last word.")]
+    public void Endings_skip_punctuation_tokens()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The dog sat on the mat. A cat was happy. The old cat sat.", new BuildOptions { Tokenizer = new PunctuationTokenizer(), BuildReverseModel = true });
+
+        generator.GenerateEndingWith(new RandomStub(0), "happy").ShouldBe("A cat was happy.");
+        generator.GenerateCouplet(new RandomStub(0)).ShouldBe("The dog sat on the mat.\nThe old cat sat.");
+    }
+}
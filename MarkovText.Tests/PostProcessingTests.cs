@@ -0,0 +1,41 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class PostProcessingTests
+{
+    [Test]
+    public void Unmatched_quotes_and_brackets_are_closed()
+    {
+        var postProcessor = new BalancedPunctuationPostProcessor();
+
+        postProcessor.Process("(He said “hello.").ShouldBe("(He said “hello”).");
+        postProcessor.Process("He said \"hello.").ShouldBe("He said \"hello\".");
+        postProcessor.Process("He said) hello.").ShouldBe("He said hello.");
+        postProcessor.Process("He said (“hello”).").ShouldBe("He said (“hello”).");
+    }
+
+    [Test]
+    public void Unmatched_quotes_and_brackets_are_stripped()
+    {
+        var postProcessor = new BalancedPunctuationPostProcessor(strip: true);
+
+        postProcessor.Process("He said) \"hello.").ShouldBe("He said hello.");
+        postProcessor.Process("He (said) “hello.").ShouldBe("He (said) hello.");
+    }
+
+    [Test]
+    [Description("The sentence 'He said “hello.' is generated from the middle of the quoted dialogue and balanced.")]
+    public void Generated_sentence_is_balanced()
+    {
+        const string corpus = "He said “hello. How are you?”";
+
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel(corpus, new BuildOptions { Preprocessors = new IPreprocessor[] { new CollapseWhitespacePreprocessor() } });
+
+        var options = new GenerationOptions { PostProcessors = new IPostProcessor[] { new BalancedPunctuationPostProcessor() } };
+
+        generator.Generate(new RandomStub(0), options).Text.ShouldBe("He said “hello”.");
+        generator.Generate(new RandomStub(1), options).Text.ShouldBe("How are you?");
+    }
+}
@@ -0,0 +1,64 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class PreprocessingTests
+{
+    [Test]
+    public void Default_pipeline_sanitizes_corpus()
+    {
+        const string text = "The “corset” [12]\nis _very_ (too)   tight.";
+
+        Preprocessing.Default.Apply(text).ShouldBe("The corset is very too tight.");
+    }
+
+    [Test]
+    public void Gutenberg_boilerplate_is_stripped()
+    {
+        const string text = "The Project Gutenberg eBook of The Corset\n*** START OF THE PROJECT GUTENBERG EBOOK THE CORSET ***\nThe corset is tight.\n*** END OF THE PROJECT GUTENBERG EBOOK THE CORSET ***\nSection 1. General Terms of Use.";
+
+        // The line breaks around the text become spaces
+        Preprocessing.Default.Apply(text).ShouldBe(" The corset is tight. ");
+        new StripGutenbergBoilerplatePreprocessor().Process("The corset is tight.").ShouldBe("The corset is tight.");
+    }
+
+    [Test]
+    public void Html_is_converted_to_text()
+    {
+        const string html = "<html><head><style>p { color: red; }</style><script>alert('<p>');</script></head>" +
+                            "<body><!-- nav --><p>Fish &amp; chips</p><p>are&nbsp;<b>tasty</b>.</p></body></html>";
+
+        var preprocessors = new IPreprocessor[] { new HtmlToTextPreprocessor(), new CollapseWhitespacePreprocessor() };
+
+        preprocessors.Apply(html).Trim().ShouldBe("Fish & chips are tasty.");
+    }
+
+    [Test]
+    public void Markdown_syntax_is_stripped()
+    {
+        const string markdown = "## Running the code\n\n" +
+                                "Run the **generator** with `dotnet run`, see [the docs](https://example.com).\n\n" +
+                                "```\ncd MarkovText\n```\n\n" +
+                                "- It is *fast*.\n" +
+                                "> It is ![logo](logo.png)fun.\n\n" +
+                                "---\n";
+
+        var preprocessors = new IPreprocessor[] { new StripMarkdownPreprocessor(), new CollapseWhitespacePreprocessor() };
+
+        preprocessors.Apply(markdown).Trim().ShouldBe("Running the code Run the generator with dotnet run, see the docs. It is fast. It is fun.");
+    }
+
+    [Test]
+    [Description("A custom pipeline without quote stripping keeps the dialogue quotes.")]
+    public void Custom_pipeline_keeps_quotes()
+    {
+        const string corpus = "He said “hello” to me.";
+
+        var generator = new StringBasedMarkovTextGenerator();
+        var preprocessors = new IPreprocessor[] { new NormalizeQuotesPreprocessor(strip: false), new CollapseWhitespacePreprocessor() };
+
+        generator.BuildMarkovModel(corpus, new BuildOptions { Preprocessors = preprocessors });
+
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("He said \"hello\" to me.");
+    }
+}
@@ -0,0 +1,98 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class ScoringTests
+{
+    private readonly StringBasedMarkovTextGenerator generator = new();
+
+    [SetUp]
+    public void SetUp()
+    {
+        generator.BuildMarkovModel("The big dog was happy. The big dog was sad.");
+    }
+
+    [Test]
+    [Description("Both sentences take the happy/sad branch with probability 0.5.")]
+    public void Sentence_log_probability()
+    {
+        generator.ScoreSentence("The big dog was happy.").ShouldBe(Math.Log(0.5), 1e-9);
+        generator.ScoreSentence("The big dog was sad.").ShouldBe(Math.Log(0.5), 1e-9);
+    }
+
+    [Test]
+    public void Unseen_sentence_is_impossible()
+    {
+        generator.ScoreSentence("The big cat was happy.").ShouldBe(double.NegativeInfinity);
+        generator.ScoreSentence("A big dog was happy.").ShouldBe(double.NegativeInfinity);
+        generator.ScoreSentence("The big dog was happy. Very").ShouldBe(double.NegativeInfinity);
+    }
+
+    [Test]
+    [Description("With k = 1, 'The big' starts 3 of 4 smoothed sentences, 'cat' gets 1 of the 2 + 7 smoothed transitions after 'The big', and the unseen phrases spread their probability over the 6 words and one unseen word.")]
+    public void Additive_smoothing_scores_unseen_transitions()
+    {
+        var smoothed = new StringBasedMarkovTextGenerator();
+        smoothed.BuildMarkovModel("The big dog was happy. The big dog was sad.", new BuildOptions { AdditiveSmoothing = 1 });
+
+        smoothed.ScoreSentence("The big dog was happy.").ShouldBe(Math.Log(0.75 * 3 / 9 * 3 / 9 * 2 / 9), 1e-9);
+        smoothed.ScoreSentence("The big cat was happy.").ShouldBe(Math.Log(0.75 / 9 / 7 / 7), 1e-9);
+        smoothed.ScoreSentence("A big dog was happy.").ShouldBeGreaterThan(double.NegativeInfinity);
+        smoothed.Perplexity("The big cat was happy.").ShouldBeLessThan(double.PositiveInfinity);
+    }
+
+    [Test]
+    [Description("The starter phrase and the next two words are certain and 'happy.' has probability 0.5, so the perplexity over 4 predictions is 2^(1/4).")]
+    public void Perplexity_of_held_out_text()
+    {
+        generator.Perplexity("The big dog was happy.").ShouldBe(Math.Pow(2, 1.0 / 4), 1e-9);
+        generator.Perplexity("The big cat was happy.").ShouldBe(double.PositiveInfinity);
+    }
+
+    [Test]
+    [Description("The held-out sentence has 4 predictions at order 2, of which only the starter phrase is seen, and 5 at order 1, where 'The', 'big', and 'happy.' are seen.")]
+    public void Held_out_split_evaluates_the_orders()
+    {
+        const string corpus = "The big dog was happy. The big dog was sad. The big dog was happy. The big cat was happy.";
+
+        var secondOrder = StringBasedMarkovTextGenerator.EvaluateSplit(corpus, 0.75);
+        var firstOrder = StringBasedMarkovTextGenerator.EvaluateSplit(corpus, 0.75, order: 1);
+        var smoothed = StringBasedMarkovTextGenerator.EvaluateSplit(corpus, 0.75, new BuildOptions { AdditiveSmoothing = 1 });
+
+        secondOrder.ShouldBe(new SplitEvaluation(2, 3, 1, double.PositiveInfinity, 0.25));
+        firstOrder.Coverage.ShouldBe(0.6, 1e-9);
+        smoothed.Perplexity.ShouldBeLessThan(double.PositiveInfinity);
+        Should.Throw<ArgumentOutOfRangeException>(() => StringBasedMarkovTextGenerator.EvaluateSplit(corpus, 1));
+    }
+
+    [Test]
+    public void Vocabulary_counts_the_corpus_words()
+    {
+        generator.Vocabulary().ShouldBe(new[] { ("The", 2), ("big", 2), ("dog", 2), ("was", 2), ("happy.", 1), ("sad.", 1) });
+        generator.TokenCount.ShouldBe(10);
+
+        generator.Learn("The big cat.");
+        generator.TokenCount.ShouldBe(13);
+    }
+
+    [Test]
+    [Description("Only the last two words of the context are looked up, and 'happy.' and 'sad.' are equally likely after 'dog was'.")]
+    public void Next_words_are_predicted_from_the_context()
+    {
+        generator.PredictNext("Yesterday my dog was").ShouldBe(new[] { ("happy.", 0.5), ("sad.", 0.5) });
+        generator.PredictNext("The big dog was", topN: 1).ShouldBe(new[] { ("happy.", 0.5) });
+        generator.PredictNext("The big").ShouldBe(new[] { ("dog", 1.0) });
+        generator.PredictNext("was sad.").ShouldBeEmpty();
+        generator.PredictNext("dog").ShouldBeEmpty();
+    }
+
+    [Test]
+    [Description("'cat was' is unseen, so the continuation backs off to 'dog was', the only phrase ending with 'was'.")]
+    public void Text_is_continued_with_back_off()
+    {
+        generator.ContinueText("Yesterday my dog was", new RandomStub(0)).ShouldBe("Yesterday my dog was happy.");
+        generator.ContinueText("My cat was ", new RandomStub(1)).ShouldBe("My cat was sad.");
+        generator.ContinueText("The big", new RandomStub(0), maxWords: 1).ShouldBe("The big dog");
+        generator.ContinueText("Hello!", new RandomStub(0)).ShouldBe("Hello! The big dog was happy.");
+    }
+}
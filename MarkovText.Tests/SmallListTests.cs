@@ -0,0 +1,54 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class SmallListTests
+{
+    [Test]
+    public void Items_overflow_the_inline_storage()
+    {
+        var list = new SmallList<int>();
+
+        for (var i = 0; i < 20; i++)
+        {
+            list.Add(i * 10);
+        }
+
+        list.Count.ShouldBe(20);
+        Enumerable.Range(0, 20).Select(i => list[i]).ShouldBe(Enumerable.Range(0, 20).Select(i => i * 10));
+        Should.Throw<ArgumentOutOfRangeException>(() => list[20]);
+    }
+
+    [Test]
+    public void Copies_keep_their_inline_items()
+    {
+        var list = new SmallList<string>();
+        list.Add("dog");
+
+        var copy = list;
+        copy.Add("cat");
+
+        list.Count.ShouldBe(1);
+        copy[1].ShouldBe("cat");
+    }
+
+    [Test]
+    [Description("Removing from a copy moves the following items forward without changing the overflow items of the original.")]
+    public void Removed_items_keep_the_order_of_the_rest()
+    {
+        var list = new SmallList<int>();
+
+        for (var i = 0; i < 10; i++)
+        {
+            list.Add(i);
+        }
+
+        var copy = list;
+        copy.RemoveAll(i => i % 3 == 0).ShouldBe(4);
+        copy.RemoveAt(0);
+
+        copy.ShouldBe(new[] { 2, 4, 5, 7, 8 });
+        list.ShouldBe(Enumerable.Range(0, 10));
+        Should.Throw<ArgumentOutOfRangeException>(() => copy.RemoveAt(5));
+    }
+}
@@ -0,0 +1,29 @@
+﻿using System.Text.Json;
+using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class StdioServerTests
+{
+    [Test]
+    public void Stdio_answers_each_request_line()
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel("The big dog was happy.");
+
+        var input = new StringReader("{\"cmd\":\"generate\",\"count\":2,\"id\":7}\n\n{\"cmd\":\"fetch\"}\nnot json\n");
+        var output = new StringWriter();
+
+        new StdioServer(generator, input, output).Run();
+
+        var responses = output.ToString().Split(Environment.NewLine, StringSplitOptions.RemoveEmptyEntries);
+        responses.Length.ShouldBe(3);
+
+        var generated = JsonDocument.Parse(responses[0]).RootElement;
+        generated.GetProperty("id").GetInt32().ShouldBe(7);
+        generated.GetProperty("sentences")[1].GetString().ShouldBe("The big dog was happy.");
+
+        JsonDocument.Parse(responses[1]).RootElement.GetProperty("error").GetString().ShouldBe("Unknown cmd: fetch");
+        JsonDocument.Parse(responses[2]).RootElement.TryGetProperty("error", out _).ShouldBeTrue();
+    }
+}
@@ -0,0 +1,29 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class StringPoolTests
+{
+    [Test]
+    public void Joined_phrases_share_one_instance()
+    {
+        var pool = new StringPool();
+        var words = new[] { "the", "big", "dog", "the", "big" };
+
+        var first = pool.Join(words, 0, 2);
+        var second = pool.Join(words, 3, 2);
+
+        first.ShouldBe("the big");
+        second.ShouldBeSameAs(first);
+        pool.Join(words, 1, 2).ShouldBe("big dog");
+        pool.Count.ShouldBe(2);
+    }
+
+    [Test]
+    public void Long_phrases_grow_the_buffer()
+    {
+        var words = Enumerable.Repeat(new string('x', 100), 10).ToArray();
+
+        new StringPool().Join(words, 0, words.Length).ShouldBe(string.Join(' ', words));
+    }
+}
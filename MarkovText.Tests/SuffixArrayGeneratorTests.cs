@@ -0,0 +1,32 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class SuffixArrayGeneratorTests
+{
+    private readonly SuffixArrayMarkovTextGenerator generator = new();
+
+    [Test]
+    [Description("Selects the second transition alternative 'dog was' -> 'very sad.' ")]
+    public void Branching_sentence()
+    {
+        generator.BuildMarkovModel("The big dog was happy but the small dog was very sad.");
+
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("The big dog was very sad.");
+        Should.Throw<SentenceOverflowException>(() => generator.GenerateSentence(new RandomStub(0)));
+    }
+
+    [Test]
+    public void Order_is_chosen_when_generating()
+    {
+        generator.BuildMarkovModel("The dog was happy. The cat was sad. A cat.", 2);
+
+        generator.GetSuccessors("was").ShouldBe(new[] { ("happy.", 1), ("sad.", 1) });
+        generator.GetSuccessors("dog was").ShouldBe(new[] { ("happy.", 1) });
+        generator.GetSuccessors("cat").ShouldBe(new[] { ("was", 1) });
+        generator.GetSuccessors("horse").ShouldBeEmpty();
+
+        generator.GenerateSentence(new RandomStub(1), 3).ShouldBe("The cat was sad.");
+        Should.Throw<ArgumentException>(() => generator.GenerateSentence(new RandomStub(0), 5));
+    }
+}
@@ -0,0 +1,93 @@
+﻿using System.Text;
+using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class TokenizerTests
+{
+    [Test]
+    public void Punctuation_is_split_into_tokens()
+    {
+        var tokenizer = new PunctuationTokenizer();
+
+        tokenizer.Tokenize("(Well, the dog?!) Isn't it...").ShouldBe(new[] { "(", "Well", ",", "the", "dog", "?!)", "Isn't", "it", "..." });
+    }
+
+    [Test]
+    [Description("The full stop is followed by the closing quote in the same punctuation token.")]
+    public void Sentences_end_before_closing_quotes()
+    {
+        var tokenizer = new PunctuationTokenizer();
+
+        tokenizer.Tokenize("He said \"stop.\"").ShouldBe(new[] { "He", "said", "\"", "stop", ".\"" });
+        tokenizer.IsSentenceEnd(".\"").ShouldBeTrue();
+        tokenizer.IsSentenceEnd("?)").ShouldBeTrue();
+        tokenizer.IsSentenceEnd("\"").ShouldBeFalse();
+    }
+
+    [Test]
+    [Description("'dog.' and 'dog,' share the state 'The dog' and the punctuation is re-attached in the output.")]
+    public void Punctuation_tokens_are_reattached()
+    {
+        const string corpus = "The dog. The dog, a cat.";
+
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel(corpus, new BuildOptions { Tokenizer = new PunctuationTokenizer() });
+
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog.");
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("The dog, a cat.");
+    }
+
+    [Test]
+    [Description("Keeps the combining accent with its letter and splits the Japanese text into characters.")]
+    public void Unicode_text_is_tokenized_and_joined()
+    {
+        const string text = "Cafe\u0301 東京に行く。Isn't it?";
+
+        var tokenizer = new UnicodeTokenizer();
+        var tokens = tokenizer.Tokenize(text).ToList();
+
+        tokens.ShouldBe(new[] { "Cafe\u0301", "東", "京", "に", "行", "く", "。", "Isn't", "it", "?" });
+        tokenizer.IsSentenceEnd("。").ShouldBeTrue();
+
+        var builder = new StringBuilder();
+        tokens.ForEach(token => tokenizer.Append(builder, token));
+
+        builder.ToString().ShouldBe(text);
+    }
+
+    [Test]
+    [Description("The full stops are followed by the closing quotes in the same punctuation tokens.")]
+    public void Unicode_sentences_end_before_closing_quotes()
+    {
+        var tokenizer = new UnicodeTokenizer();
+
+        tokenizer.Tokenize("“Stop.” 「止まれ。」").ShouldBe(new[] { "“", "Stop", ".”", "「", "止", "ま", "れ", "。」" });
+        tokenizer.IsSentenceEnd(".”").ShouldBeTrue();
+        tokenizer.IsSentenceEnd("。」").ShouldBeTrue();
+        tokenizer.IsSentenceEnd("!»").ShouldBeTrue();
+        tokenizer.IsSentenceEnd("»").ShouldBeFalse();
+    }
+
+    [Test]
+    public void Line_breaks_and_indentation_are_tokens()
+    {
+        var tokenizer = new WhitespacePreservingTokenizer();
+
+        tokenizer.Tokenize("def f():\n    return 1\n\n").ShouldBe(new[] { "def", "f():", "\n", "\u00A0\u00A0\u00A0\u00A0", "return", "1", "\n\n" });
+        tokenizer.IsSentenceEnd("\n\n").ShouldBeTrue();
+        tokenizer.IsSentenceEnd("1.").ShouldBeFalse();
+    }
+
+    [Test]
+    [Description("Generates the first function of the code corpus with its line structure.")]
+    public void Code_is_generated_with_line_structure()
+    {
+        const string corpus = "def f():\n    return 1\n\ndef g():\n    return 2\n";
+
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel(corpus, new BuildOptions { Tokenizer = new WhitespacePreservingTokenizer(), Preprocessors = Array.Empty<IPreprocessor>() });
+
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("def f():\n    return 1\n\n");
+    }
+}
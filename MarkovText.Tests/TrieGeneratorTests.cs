@@ -0,0 +1,36 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class TrieGeneratorTests
+{
+    private const string Corpus = "The big dog was happy. The small dog was sad. A cat was happy.";
+
+    private readonly TrieMarkovTextGenerator generator = new();
+
+    [SetUp]
+    public void Setup()
+    {
+        generator.BuildMarkovModel(Corpus);
+    }
+
+    [Test]
+    public void Phrases_are_found_by_prefix()
+    {
+        generator.GetPhrases("The").ShouldBe(new[] { "The big", "The small" });
+        generator.GetPhrases("dog").ShouldBe(new[] { "dog was" });
+        generator.GetPhrases("dog was").ShouldBe(new[] { "dog was" });
+        generator.GetPhrases("horse").ShouldBeEmpty();
+        generator.GetPhrases().Count().ShouldBe(9);
+    }
+
+    [Test]
+    [Description("Only 'The big' and 'The small' start sentences with 'The', the second is chosen.")]
+    public void Sentence_starts_with_prompt()
+    {
+        generator.GenerateSentence(new RandomStub(1), "The").ShouldBe("The small dog was sad.");
+
+        Should.Throw<ArgumentException>(() => generator.GenerateSentence(new RandomStub(0), "The cat"));
+        Should.Throw<ArgumentException>(() => generator.GenerateSentence(new RandomStub(0), "The big dog"));
+    }
+}
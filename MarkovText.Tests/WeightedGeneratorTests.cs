@@ -0,0 +1,111 @@
+﻿using Shouldly;
+
+namespace MarkovText.Tests;
+
+public class WeightedGeneratorTests
+{
+    private const string Corpus = "The dog was happy. The dog was happy. The dog was sad.";
+
+    private readonly WeightedMarkovTextGenerator generator = new();
+
+    [SetUp]
+    public void Setup()
+    {
+        generator.BuildMarkovModel(Corpus);
+    }
+
+    [Test]
+    public void Transitions_have_explicit_probabilities()
+    {
+        generator.GetStarterProbabilities().Count.ShouldBe(1);
+        generator.GetStarterProbabilities()["The dog"].ShouldBe(1);
+
+        var probabilities = generator.GetTransitionProbabilities("dog was");
+        probabilities.Count.ShouldBe(2);
+        probabilities["happy."].ShouldBe(2.0 / 3, 1e-9);
+        probabilities["sad."].ShouldBe(1.0 / 3, 1e-9);
+
+        generator.GetTransitionProbabilities("was sad.").ShouldBeEmpty();
+    }
+
+    [Test]
+    [Description("The random values 0 and 1 fall in the range of 'happy.', which occurs twice, and 2 falls in the range of 'sad.'.")]
+    public void Continuations_are_sampled_by_cumulative_count()
+    {
+        generator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog was happy.");
+        generator.GenerateSentence(new RandomStub(1)).ShouldBe("The dog was happy.");
+        generator.GenerateSentence(new RandomStub(2)).ShouldBe("The dog was sad.");
+    }
+
+    [Test]
+    [Description("Of the 3 x 3 combinations of column and coin for the counts 1, 2, and 6, each outcome is picked as often as its count.")]
+    public void Alias_table_samples_exact_probabilities()
+    {
+        var distribution = new ProbabilityDistribution<string>(new Dictionary<string, int> { ["a"] = 1, ["b"] = 2, ["c"] = 6 }, aliasTable: true);
+        var picks = new Dictionary<string, int>();
+
+        for (var column = 0; column < 3; column++)
+        {
+            for (var coin = 0; coin < 9; coin++)
+            {
+                var outcome = distribution.Sample(new SequenceRandom(column, coin));
+                picks[outcome] = picks.GetValueOrDefault(outcome) + 1;
+            }
+        }
+
+        picks["a"].ShouldBe(1 * 3);
+        picks["b"].ShouldBe(2 * 3);
+        picks["c"].ShouldBe(6 * 3);
+    }
+
+    [Test]
+    public void Alias_sampling_generates_corpus_sentences()
+    {
+        var aliasGenerator = new WeightedMarkovTextGenerator { AliasSampling = true };
+        aliasGenerator.BuildMarkovModel(Corpus);
+
+        aliasGenerator.GetTransitionProbabilities("dog was")["happy."].ShouldBe(2.0 / 3, 1e-9);
+        aliasGenerator.GenerateSentence(new RandomStub(0)).ShouldBe("The dog was happy.");
+    }
+
+    [Test]
+    [Description("Both generators produce identical transition tables for the bundled corpus, and the exchanged tables drive the same sentences.")]
+    public void Transition_tables_match_string_generator()
+    {
+        var corpus = File.ReadAllText(StringBasedMarkovTextGenerator.DefaultCorpusPath);
+
+        var stringGenerator = new StringBasedMarkovTextGenerator();
+        stringGenerator.BuildMarkovModel(corpus);
+        generator.BuildMarkovModel(corpus);
+
+        var expected = TransitionTable.FromJson(stringGenerator.ToJson());
+        var actual = generator.ToTransitionTable();
+
+        actual.StarterPhrases.OrderBy(starter => starter.Key, StringComparer.Ordinal)
+            .ShouldBe(expected.StarterPhrases.OrderBy(starter => starter.Key, StringComparer.Ordinal));
+
+        actual.Transitions.Count.ShouldBe(expected.Transitions.Count);
+
+        foreach (var (phrase, words) in expected.Transitions)
+        {
+            actual.Transitions[phrase].OrderBy(word => word.Key, StringComparer.Ordinal)
+                .ShouldBe(words.OrderBy(word => word.Key, StringComparer.Ordinal));
+        }
+
+        var imported = WeightedMarkovTextGenerator.FromTransitionTable(expected);
+        imported.GenerateSentence(new DefaultRandom(new Random(5))).ShouldBe(generator.GenerateSentence(new DefaultRandom(new Random(5))));
+    }
+
+    // Returns the given values in order
+    private class SequenceRandom : IRandomNumberGenerator
+    {
+        private readonly Queue<int> values;
+
+        public SequenceRandom(params int[] values)
+        {
+            this.values = new Queue<int>(values);
+        }
+
+        public int Next(int maxValue) => values.Dequeue();
+    }
+}
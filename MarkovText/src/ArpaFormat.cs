@@ -0,0 +1,261 @@
+﻿using System.Globalization;
+
+namespace MarkovText;
+
+/// <summary>
+/// Reads and writes n-gram language models in the standard ARPA format of toolkits like KenLM and SRILM.
+/// The highest order n-grams become the transitions of a Markov model of order n - 1, e.g., trigrams drive a model of order 2:
+/// - "&lt;s&gt; w1 ... wn-1" n-grams become starter phrases, weighted by the probability of the whole phrase starting a sentence
+/// - Other n-grams become transitions from their first n - 1 words to the last
+/// - "w1 ... wn-1 &lt;/s&gt;" n-grams become the sentence ending "w1 ... wn-1&lt;/s&gt;" of the phrase, a phrase without transitions:
+///   the transitions into the phrase are split between the phrase and its ending by the probability of the sentence ending there
+/// Probabilities are scaled to integer counts with a resolution of 1/100, and backoff weights are ignored. Starter phrases
+/// always continue if they can, so sentences as short as a starter phrase are only generated if the phrase always ends them.
+/// Written models contain the n-grams of every order up to n, estimated from the counts of the higher order n-grams, without backoff weights.
+/// </summary>
+public static class ArpaFormat
+{
+    // The tokens ARPA models use for the start and end of a sentence
+    public const string SentenceStart = "<s>";
+    public const string SentenceEnd = "</s>";
+
+    // Probabilities are scaled to counts with this resolution, like the weights of stopwords
+    private const int CountResolution = 100;
+
+    /// <summary>
+    /// The phrase without transitions that the sentence ends with after the words of the phrase, e.g., "the dog&lt;/s&gt;"
+    /// </summary>
+    public static string Ending(string phrase) => phrase + SentenceEnd;
+
+    /// <summary>
+    /// Whether the phrase is the sentence ending of a phrase, see Ending
+    /// </summary>
+    public static bool IsEnding(string phrase) => phrase.EndsWith(SentenceEnd, StringComparison.Ordinal);
+
+    public static ModelPatch Read(TextReader reader)
+    {
+        // The log10 probability of each n-gram, by n - 1
+        var ngrams = new List<Dictionary<string, double>>();
+        var section = 0;
+
+        while (reader.ReadLine()?.Trim() is { } line)
+        {
+            if (line.Length == 0 || line == "\\data\\" || line.StartsWith("ngram "))
+            {
+                continue;
+            }
+
+            if (line == "\\end\\")
+            {
+                break;
+            }
+
+            if (line.StartsWith('\\') && line.EndsWith("-grams:"))
+            {
+                section = int.Parse(line[1..line.IndexOf('-')], CultureInfo.InvariantCulture);
+
+                while (ngrams.Count < section)
+                {
+                    ngrams.Add(new Dictionary<string, double>());
+                }
+
+                continue;
+            }
+
+            var fields = line.Split((char[]?)null, StringSplitOptions.RemoveEmptyEntries);
+
+            if (section == 0 || fields.Length < section + 1 || !double.TryParse(fields[0], NumberStyles.Float, CultureInfo.InvariantCulture, out var logProbability))
+            {
+                throw new InvalidDataException($"Invalid ARPA n-gram line: {line}");
+            }
+
+            ngrams[section - 1][string.Join(' ', fields, 1, section)] = logProbability;
+        }
+
+        if (ngrams.Count < 2)
+        {
+            throw new InvalidDataException("The ARPA model needs bigrams or higher order n-grams to drive generation.");
+        }
+
+        var model = new ModelPatch { Order = ngrams.Count - 1 };
+        var endProbabilities = EndProbabilities(ngrams[^1]);
+
+        foreach (var (ngram, logProbability) in ngrams[^1])
+        {
+            var tokens = ngram.Split(' ');
+
+            if (tokens[^1] == SentenceEnd)
+            {
+                continue;   // Sentence endings split the transitions into their phrase
+            }
+
+            var suffixPhrase = string.Join(' ', tokens[1..]);
+
+            if (tokens[0] == SentenceStart)
+            {
+                if (!tokens.Contains(SentenceEnd) && StarterLogProbability(ngrams, tokens) is { } starterLogProbability)
+                {
+                    model.StarterPhrases.Add(new StarterPhraseDelta(suffixPhrase, ToCount(starterLogProbability)));
+                }
+
+                continue;
+            }
+
+            var phrase = string.Join(' ', tokens[..^1]);
+            var endProbability = endProbabilities.GetValueOrDefault(suffixPhrase);
+
+            if (endProbability < 1)
+            {
+                model.Transitions.Add(new TransitionDelta(phrase, suffixPhrase, tokens[^1], ToCount(logProbability + Math.Log10(1 - endProbability))));
+            }
+
+            if (endProbability > 0)
+            {
+                model.Transitions.Add(new TransitionDelta(phrase, Ending(suffixPhrase), tokens[^1], ToCount(logProbability + Math.Log10(endProbability))));
+            }
+        }
+
+        if (model.StarterPhrases.Count == 0)
+        {
+            throw new InvalidDataException($"The ARPA model contains no {ngrams.Count}-grams starting with {SentenceStart}.");
+        }
+
+        return model;
+    }
+
+    // The probability of the sentence ending after each phrase, relative to the probabilities of the words that can follow it
+    private static Dictionary<string, double> EndProbabilities(Dictionary<string, double> ngrams)
+    {
+        var phraseProbabilities = new Dictionary<string, double>();
+        var endProbabilities = new Dictionary<string, double>();
+
+        foreach (var (ngram, logProbability) in ngrams.Where(ngram => !ngram.Key.StartsWith($"{SentenceStart} ")))
+        {
+            var phrase = ngram.Key[..ngram.Key.LastIndexOf(' ')];
+            phraseProbabilities[phrase] = phraseProbabilities.GetValueOrDefault(phrase) + Math.Pow(10, logProbability);
+
+            if (ngram.Key.EndsWith($" {SentenceEnd}"))
+            {
+                endProbabilities[phrase] = Math.Pow(10, logProbability);
+            }
+        }
+
+        return endProbabilities.ToDictionary(end => end.Key, end => end.Value / phraseProbabilities[end.Key]);
+    }
+
+    // The probability of the words starting a sentence, as the product of the probabilities of each word given the preceding ones
+    // Null if a lower order n-gram is missing
+    private static double? StarterLogProbability(List<Dictionary<string, double>> ngrams, string[] tokens)
+    {
+        var logProbability = 0.0;
+
+        for (var i = 1; i < tokens.Length; i++)
+        {
+            if (!ngrams[i].TryGetValue(string.Join(' ', tokens[..(i + 1)]), out var wordLogProbability))
+            {
+                return null;
+            }
+
+            logProbability += wordLogProbability;
+        }
+
+        return logProbability;
+    }
+
+    // Every n-gram keeps at least one count, however improbable
+    private static int ToCount(double logProbability) => Math.Max(1, (int)Math.Round(Math.Pow(10, logProbability) * CountResolution));
+
+    public static void Write(TextWriter writer, ModelPatch model)
+    {
+        var order = model.Order + 1;
+
+        // The counts of the highest order n-grams, padded with the sentence start and end tokens
+        var ngramCounts = new Dictionary<string, int>();
+        var phraseCounts = new Dictionary<string, int>();
+        var continuedPhrases = new HashSet<string>();
+
+        foreach (var (phrase, count) in model.StarterPhrases.Where(starter => starter.Delta > 0))
+        {
+            ngramCounts.Increment($"{SentenceStart} {phrase}", count);
+            phraseCounts.Increment(phrase, count);
+        }
+
+        foreach (var (phrase, suffixPhrase, word, count) in model.Transitions.Where(transition => transition.Delta > 0))
+        {
+            ngramCounts.Increment($"{phrase} {word}", count);
+            phraseCounts.Increment(suffixPhrase, count);
+            continuedPhrases.Add(phrase);
+        }
+
+        // Phrases without transitions end the sentence, like the sentence endings of imported models
+        foreach (var (phrase, count) in phraseCounts)
+        {
+            if (IsEnding(phrase))
+            {
+                ngramCounts.Increment($"{phrase[..^SentenceEnd.Length]} {SentenceEnd}", count);
+            }
+            else if (!continuedPhrases.Contains(phrase))
+            {
+                ngramCounts.Increment($"{phrase} {SentenceEnd}", count);
+            }
+        }
+
+        // The lower order n-grams are the prefixes of the highest order n-grams, by n - 1
+        var prefixCounts = Enumerable.Range(0, order - 1).Select(_ => new Dictionary<string, int>()).ToList();
+
+        // Each word of the corpus is the last word of one n-gram, or one of the first words of a sentence
+        var wordCounts = new Dictionary<string, int>();
+
+        foreach (var (ngram, count) in ngramCounts)
+        {
+            var tokens = ngram.Split(' ');
+
+            for (var n = 1; n < order; n++)
+            {
+                prefixCounts[n - 1].Increment(string.Join(' ', tokens[..n]), count);
+            }
+
+            foreach (var token in tokens[0] == SentenceStart ? tokens[1..] : tokens[^1..])
+            {
+                wordCounts.Increment(token, count);
+            }
+        }
+
+        var totalWordCount = wordCounts.Values.Sum();
+        var sections = new List<List<(string NGram, double LogProbability)>>
+        {
+            wordCounts.Select(word => (word.Key, Math.Log10((double)word.Value / totalWordCount))).Prepend((SentenceStart, -99)).ToList()
+        };
+
+        // The probability of the last word of each n-gram given the preceding words
+        double LogProbability(string ngram, int count, int n) => Math.Log10((double)count / prefixCounts[n - 2][ngram[..ngram.LastIndexOf(' ')]]);
+
+        for (var n = 2; n < order; n++)
+        {
+            sections.Add(prefixCounts[n - 1].Select(prefix => (prefix.Key, LogProbability(prefix.Key, prefix.Value, n))).ToList());
+        }
+
+        sections.Add(ngramCounts.Select(ngram => (ngram.Key, LogProbability(ngram.Key, ngram.Value, order))).ToList());
+
+        writer.WriteLine("\\data\\");
+
+        for (var n = 1; n <= order; n++)
+        {
+            writer.WriteLine($"ngram {n}={sections[n - 1].Count}");
+        }
+
+        for (var n = 1; n <= order; n++)
+        {
+            writer.WriteLine();
+            writer.WriteLine($"\\{n}-grams:");
+
+            foreach (var (ngram, logProbability) in sections[n - 1])
+            {
+                writer.WriteLine($"{logProbability.ToString("0.######", CultureInfo.InvariantCulture)}\t{ngram}");
+            }
+        }
+
+        writer.WriteLine();
+        writer.WriteLine("\\end\\");
+    }
+}
@@ -106,7 +106,6 @@ public partial class ArrayBasedMarkovTextGenerator : IGenerator
         // Normalize multiple consecutive spaces into a single space
         corpus = MultipleWhitespaceRegex().Replace(corpus, " ");
 
-        var wordCount = 0;
         var slidingWindow = new CyclicArray<string>(Order);
         string[]? previousPhrase = null;
 
@@ -117,20 +116,20 @@ public partial class ArrayBasedMarkovTextGenerator : IGenerator
                 continue;
             }
 
-            slidingWindow[wordCount] = word;
+            slidingWindow.Push(word);
 
-            if (++wordCount < Order)
+            if (!slidingWindow.IsFull)
             {
                 if (SentenceDelimiters.Contains(word[^1]))
                 {
                     previousPhrase = null;
-                    wordCount = 0;
+                    slidingWindow.Clear();
                 }
 
                 continue;
             }
 
-            var phrase = slidingWindow.CreateOffsetArray(wordCount);
+            var phrase = slidingWindow.LatestWindow();
 
             if (previousPhrase == null)
             {
@@ -146,7 +145,7 @@ public partial class ArrayBasedMarkovTextGenerator : IGenerator
             if (SentenceDelimiters.Contains(word[^1]))
             {
                 previousPhrase = null;
-                wordCount = 0;
+                slidingWindow.Clear();
             }
         }
     }
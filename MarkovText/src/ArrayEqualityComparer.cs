@@ -7,9 +7,12 @@
 /// <typeparam name="T"></typeparam>
 public sealed class ArrayEqualityComparer<T> : IEqualityComparer<T[]>
 {
-    // You could make this a per-instance field with a constructor parameter
-    private static readonly EqualityComparer<T> ElementComparer
-        = EqualityComparer<T>.Default;
+    private readonly IEqualityComparer<T> ElementComparer;
+
+    public ArrayEqualityComparer(IEqualityComparer<T>? elementComparer = null)
+    {
+        ElementComparer = elementComparer ?? EqualityComparer<T>.Default;
+    }
 
     public bool Equals(T[]? first, T[]? second)
     {
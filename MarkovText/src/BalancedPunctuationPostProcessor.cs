@@ -0,0 +1,110 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Fixes unmatched quotes and parentheses, which are common when the corpus has dialogue and the sanitization pipeline keeps quotes.
+/// Either strips all unmatched quotes and brackets, or closes the unmatched ones (and strips unmatched closing brackets).
+/// </summary>
+public class BalancedPunctuationPostProcessor : IPostProcessor
+{
+    private readonly bool strip;
+
+    // Maps closing brackets to opening brackets
+    private static readonly Dictionary<char, char> Pairs = new()
+    {
+        [')'] = '(',
+        [']'] = '[',
+        ['}'] = '{',
+        ['”'] = '“',
+    };
+
+    public BalancedPunctuationPostProcessor(bool strip = false)
+    {
+        this.strip = strip;
+    }
+
+    // True if unmatched quotes and brackets are stripped instead of closed
+    public bool Strip => strip;
+
+    public string Process(string sentence)
+    {
+        var unmatched = new HashSet<int>();
+        var open = new Stack<(char Character, int Index)>();
+
+        for (var i = 0; i < sentence.Length; i++)
+        {
+            var character = sentence[i];
+
+            if (character == '"')
+            {
+                // Straight quotes are both opening and closing quotes
+                if (open.Count > 0 && open.Peek().Character == '"')
+                {
+                    open.Pop();
+                }
+                else
+                {
+                    open.Push((character, i));
+                }
+            }
+            else if (Pairs.ContainsValue(character))
+            {
+                open.Push((character, i));
+            }
+            else if (Pairs.TryGetValue(character, out var opening))
+            {
+                if (open.Count > 0 && open.Peek().Character == opening)
+                {
+                    open.Pop();
+                }
+                else
+                {
+                    unmatched.Add(i);   // A closing bracket without an opening bracket can only be stripped
+                }
+            }
+        }
+
+        if (strip)
+        {
+            foreach (var (_, index) in open)
+            {
+                unmatched.Add(index);
+            }
+        }
+
+        var stringBuilder = new StringBuilder(sentence.Length + open.Count);
+
+        for (var i = 0; i < sentence.Length; i++)
+        {
+            if (!unmatched.Contains(i))
+            {
+                stringBuilder.Append(sentence[i]);
+            }
+        }
+
+        if (!strip && open.Count > 0)
+        {
+            // Close the brackets before the final sentence punctuation, innermost first
+            var end = stringBuilder.Length;
+
+            while (end > 0 && char.IsPunctuation(stringBuilder[end - 1]) && !IsQuoteOrBracket(stringBuilder[end - 1]))
+            {
+                end--;
+            }
+
+            var closing = new StringBuilder();
+
+            foreach (var (character, _) in open)
+            {
+                closing.Append(character == '"' ? '"' : Pairs.First(pair => pair.Value == character).Key);
+            }
+
+            stringBuilder.Insert(end, closing.ToString());
+        }
+
+        return stringBuilder.ToString();
+    }
+
+    private static bool IsQuoteOrBracket(char character) => character == '"' || Pairs.ContainsKey(character) || Pairs.ContainsValue(character);
+}
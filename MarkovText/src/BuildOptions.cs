@@ -0,0 +1,64 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Options that control how the Markov model is built from a corpus
+/// </summary>
+public class BuildOptions
+{
+    // The number of preceding words that determine the next word
+    public int Order { get; init; } = 2;
+
+    // Sanitization pipeline the corpus is run through before it is tokenized, use an empty list to disable cleaning
+    public IReadOnlyList<IPreprocessor> Preprocessors { get; init; } = Preprocessing.Default;
+
+    // Splits the sanitized corpus into tokens and joins generated tokens back into text
+    public ITokenizer Tokenizer { get; init; } = new WhitespaceTokenizer();
+
+    // Lowercase all tokens, e.g., so "The" and "the" are the same state. Generated sentences are re-capitalized
+    // at the start, and words mostly capitalized mid-sentence in the corpus (like names) keep their capitalization.
+    public bool NormalizeCase { get; init; }
+
+    // Common words like "the", "of", and "and" whose transitions are down-weighted by StopwordWeight during generation
+    public IReadOnlyCollection<string> Stopwords { get; init; } = Array.Empty<string>();
+
+    // Relative probability of choosing a transition to a stopword, between 0 and 1
+    public double StopwordWeight { get; init; } = 0.5;
+
+    // Add-k (Laplace) smoothing for scoring: k is added to the count of every word of the vocabulary after each phrase, so
+    // ScoreSentence and Perplexity are finite for sentences with unseen transitions. 0 disables it, 1 is Laplace smoothing.
+    public double AdditiveSmoothing { get; init; }
+
+    // Sentences starting with a stopword don't become sentence starter phrases
+    public bool ExcludeStopwordStarters { get; init; }
+
+    // Approximate limit for the size of the model in memory. Each time it is exceeded, the least frequent transitions are pruned,
+    // so unattended training on huge corpora can't run out of memory. See StringBasedMarkovTextGenerator.MemoryPruning.
+    // The limit includes the count of each distinct transition that is kept to find the least frequent ones.
+    public long? MaxMemoryBytes { get; init; }
+
+    // Keeps at most N distinct successors of each phrase, which bounds the memory used by very common phrases like "of the"
+    // in huge corpora. A new successor replaces the least frequent one and takes over its count, so any successor following
+    // the phrase more than 1 / N of the time is kept, however late in the corpus it first occurs.
+    public int? MaxSuccessorsPerPhrase { get; init; }
+
+    // Lines instead of sentences are the unit, e.g., for poetry and lyrics: starter phrases begin lines and transitions
+    // never cross line breaks. Generate multi-line stanzas with StringBasedMarkovTextGenerator.GenerateStanza.
+    public bool LineMode { get; init; }
+
+    // Also build a model of the reversed sentences, whose starter phrases are the sentence endings, to grow sentences backwards
+    // with StringBasedMarkovTextGenerator.GenerateEndingWith. Roughly doubles the size of the model.
+    public bool BuildReverseModel { get; init; }
+
+    // Keep the sanitized corpus sentences in memory for StringBasedMarkovTextGenerator.Quote
+    public bool StoreSentences { get; init; }
+
+    // Hashes the tokens of the phrases of the Markov chain, whose lookups dominate training time, e.g., StableStringComparer.Instance
+    // for trusted corpora. Null uses the default .NET string comparer.
+    public IEqualityComparer<string>? PhraseComparer { get; init; }
+
+    // Detects the language of each sentence in the corpus, used together with Language
+    public ILanguageDetector? LanguageDetector { get; init; }
+
+    // Only sentences in this language are added to the model, e.g., "en"
+    public string? Language { get; init; }
+}
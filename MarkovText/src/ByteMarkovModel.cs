@@ -0,0 +1,49 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Markov model of raw byte transitions, for generating binary-ish test data, and for corpora in unknown encodings where
+/// decoding and word segmentation are unreliable. The bytes are modeled as they are, without decoding or tokenizing them.
+/// </summary>
+public class ByteMarkovModel
+{
+    // Generated byte sequences are cut off at this length, since data without separators may never reach a final state
+    public int MaxLength = 4096;
+
+    private readonly MarkovChain<byte> Chain;
+
+    public ByteMarkovModel(int order = 4)
+    {
+        Chain = new MarkovChain<byte>(order);
+    }
+
+    public int Order => Chain.Order;
+
+    /// <summary>
+    /// Adds the records of the data, split after each separator byte like a line break, or the whole data as one record
+    /// </summary>
+    public void Add(ReadOnlySpan<byte> data, byte? separator = null)
+    {
+        while (!data.IsEmpty)
+        {
+            var end = separator is { } value && data.IndexOf(value) is >= 0 and var index ? index + 1 : data.Length;
+
+            Chain.Add(data[..end].ToArray());
+            data = data[end..];
+        }
+    }
+
+    public void AddFile(string path, byte? separator = null) => Add(File.ReadAllBytes(path), separator);
+
+    /// <summary>
+    /// Generates a random record of at most MaxLength bytes
+    /// </summary>
+    public byte[] Generate(IRandomNumberGenerator random)
+    {
+        if (Chain.SequenceCount == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(Add)} first.");
+        }
+
+        return Chain.Generate(random).Take(MaxLength).ToArray();
+    }
+}
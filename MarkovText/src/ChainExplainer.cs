@@ -0,0 +1,143 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Explains step by step how a Markov model is built and used, for teaching: prints the sliding window over each
+/// corpus sentence, the resulting transition table, and an annotated walk through the generation of a sentence
+/// </summary>
+public class ChainExplainer
+{
+    // Only the first sentences of the corpus are explained in detail
+    public int MaxExplainedSentences = 5;
+
+    // The transition table is only printed for small models
+    public int MaxTablePhrases = 50;
+
+    private readonly TextWriter output;
+
+    public ChainExplainer(TextWriter output)
+    {
+        this.output = output;
+    }
+
+    public void Explain(string corpus, int order, IRandomNumberGenerator random)
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.BuildMarkovModel(corpus, order);
+
+        ExplainConstruction(corpus, order);
+        ExplainTransitionTable(generator);
+        ExplainGeneration(generator, random);
+    }
+
+    private void ExplainConstruction(string corpus, int order)
+    {
+        var tokenizer = new WhitespaceTokenizer();
+        var sentence = new List<string>();
+        var sentenceCount = 0;
+
+        output.WriteLine($"1. A window of {order} words slides over each sentence of the corpus.");
+        output.WriteLine("   The first window is a starter phrase, and each window is followed by the next word.");
+        output.WriteLine();
+
+        foreach (var word in tokenizer.Tokenize(Preprocessing.Default.Apply(corpus)))
+        {
+            sentence.Add(word);
+
+            if (!tokenizer.IsSentenceEnd(word))
+            {
+                continue;
+            }
+
+            if (++sentenceCount > MaxExplainedSentences)
+            {
+                output.WriteLine("   ...");
+                output.WriteLine();
+                break;
+            }
+
+            ExplainSentence(sentence, order);
+            sentence.Clear();
+        }
+    }
+
+    private void ExplainSentence(List<string> sentence, int order)
+    {
+        output.WriteLine($"   {string.Join(' ', sentence)}");
+
+        if (sentence.Count < order)
+        {
+            output.WriteLine($"     skipped, the sentence is shorter than {order} words");
+            output.WriteLine();
+            return;
+        }
+
+        output.WriteLine($"     start: [{string.Join(' ', sentence.Take(order))}]");
+
+        for (var i = order; i < sentence.Count; i++)
+        {
+            output.WriteLine($"     [{string.Join(' ', sentence.Skip(i - order).Take(order))}] => {sentence[i]}");
+        }
+
+        output.WriteLine();
+    }
+
+    private void ExplainTransitionTable(StringBasedMarkovTextGenerator generator)
+    {
+        var phrases = generator.GetPhrases().ToList();
+
+        output.WriteLine("2. Counting the windows gives the probability of each next word.");
+        output.WriteLine();
+
+        if (phrases.Count > MaxTablePhrases)
+        {
+            output.WriteLine($"   The model has {phrases.Count} phrases, too many to print.");
+            output.WriteLine();
+            return;
+        }
+
+        foreach (var phrase in phrases)
+        {
+            var successors = generator.GetSuccessors(phrase);
+            var total = successors.Sum(successor => successor.Count);
+
+            var probabilities = successors.Select(successor => $"{successor.Word} {successor.Count}/{total}");
+            output.WriteLine($"   [{phrase}] => {string.Join(", ", probabilities)}");
+        }
+
+        output.WriteLine();
+    }
+
+    private void ExplainGeneration(StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random)
+    {
+        var starters = generator.GetStarterPhrases();
+        var starterTotal = starters.Sum(starter => starter.Count);
+        var phrase = starters.WeightedRandom(random, starter => starter.Count).Phrase;
+        var sentence = new List<string>(phrase.Split(' '));
+
+        output.WriteLine("3. A sentence is generated by picking a starter phrase and then random next words until the sentence ends.");
+        output.WriteLine();
+
+        var starterCount = starters.First(starter => starter.Phrase == phrase).Count;
+        output.WriteLine($"   Start with [{phrase}] ({starterCount}/{starterTotal} of the starter phrases)");
+
+        for (var successors = generator.GetSuccessors(phrase); successors.Count > 0; successors = generator.GetSuccessors(phrase))
+        {
+            if (sentence.Count >= generator.MaxWordCount)
+            {
+                output.WriteLine($"   Stop after {sentence.Count} words, the sentence never ends");
+                break;
+            }
+
+            var total = successors.Sum(successor => successor.Count);
+            var (suffixPhrase, word, count) = successors.WeightedRandom(random, successor => successor.Count);
+
+            output.WriteLine($"   [{phrase}] => {word} ({count}/{total})");
+
+            sentence.Add(word);
+            phrase = suffixPhrase;
+        }
+
+        output.WriteLine();
+        output.WriteLine($"   {string.Join(' ', sentence)}");
+    }
+}
@@ -0,0 +1,154 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Interactive terminal explorer for teaching how Markov text works: browse phrases, see the distribution of their
+/// successors as bar charts, and step through the generation of a sentence one word at a time
+/// </summary>
+public class ChainExplorer
+{
+    // Width of the longest bar in the charts, in characters
+    private const int BarWidth = 40;
+
+    private readonly StringBasedMarkovTextGenerator generator;
+    private readonly IRandomNumberGenerator random;
+    private readonly TextReader input;
+    private readonly TextWriter output;
+
+    // The current phrase of the sentence being stepped through, null before the first step
+    private string? phrase;
+    private readonly List<string> sentence = new();
+
+    public ChainExplorer(StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, TextReader input, TextWriter output)
+    {
+        this.generator = generator;
+        this.random = random;
+        this.input = input;
+        this.output = output;
+    }
+
+    /// <summary>
+    /// Reads and runs commands until the input ends or the user quits
+    /// </summary>
+    public void Run()
+    {
+        WriteHelp();
+
+        while (true)
+        {
+            output.Write("> ");
+
+            var line = input.ReadLine();
+
+            if (line == null)
+            {
+                return;
+            }
+
+            var command = line.Trim();
+            var argument = "";
+            var space = command.IndexOf(' ');
+
+            if (space >= 0)
+            {
+                argument = command[(space + 1)..].Trim();
+                command = command[..space];
+            }
+
+            switch (command)
+            {
+                case "" or "step":
+                    Step();
+                    break;
+                case "new":
+                    phrase = null;
+                    sentence.Clear();
+                    Step();
+                    break;
+                case "starters":
+                    WriteChart(generator.GetStarterPhrases());
+                    break;
+                case "phrase":
+                    WriteSuccessors(argument);
+                    break;
+                case "quit" or "exit":
+                    return;
+                default:
+                    WriteHelp();
+                    break;
+            }
+        }
+    }
+
+    // Adds the next word to the sentence, or starts a new sentence if there is none
+    private void Step()
+    {
+        if (phrase == null)
+        {
+            var starters = generator.GetStarterPhrases();
+            WriteChart(starters);
+
+            phrase = starters.WeightedRandom(random, starter => starter.Count).Phrase;
+            sentence.AddRange(phrase.Split(' '));
+
+            output.WriteLine($"Start with '{phrase}': {string.Join(' ', sentence)}");
+            return;
+        }
+
+        var successors = generator.GetSuccessors(phrase);
+
+        if (successors.Count == 0)
+        {
+            output.WriteLine($"'{phrase}' ends the sentence: {string.Join(' ', sentence)}");
+            output.WriteLine("Press enter to start a new sentence.");
+
+            phrase = null;
+            sentence.Clear();
+            return;
+        }
+
+        WriteSuccessors(phrase);
+
+        var (suffixPhrase, word, _) = successors.WeightedRandom(random, successor => successor.Count);
+        sentence.Add(word);
+
+        output.WriteLine($"'{phrase}' => '{word}': {string.Join(' ', sentence)}");
+        phrase = suffixPhrase;
+    }
+
+    private void WriteSuccessors(string from)
+    {
+        var successors = generator.GetSuccessors(from);
+
+        if (successors.Count == 0)
+        {
+            output.WriteLine($"No word follows '{from}'.");
+            return;
+        }
+
+        output.WriteLine($"Words following '{from}':");
+        WriteChart(successors.Select(successor => (successor.Word, successor.Count)).ToList());
+    }
+
+    private void WriteChart(IReadOnlyList<(string Label, int Count)> rows)
+    {
+        var total = rows.Sum(row => row.Count);
+        var max = rows.Max(row => row.Count);
+        var labelWidth = rows.Max(row => row.Label.Length);
+
+        foreach (var (label, count) in rows)
+        {
+            var bar = new string('█', Math.Max(1, count * BarWidth / max));
+            output.WriteLine($"  {label.PadRight(labelWidth)} {bar} {count} ({100 * count / total}%)");
+        }
+    }
+
+    private void WriteHelp()
+    {
+        output.WriteLine("Commands:");
+        output.WriteLine("  step (or enter)  add the next word to the sentence");
+        output.WriteLine("  new              start a new sentence");
+        output.WriteLine("  starters         show the sentence starter phrases");
+        output.WriteLine("  phrase <words>   show the words following a phrase");
+        output.WriteLine("  quit             exit the explorer");
+    }
+}
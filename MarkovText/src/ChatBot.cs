@@ -0,0 +1,61 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Chat bot that keeps learning from the messages it receives and replies with a generated sentence when asked with the command,
+/// e.g., "!markov". Learned messages are appended to the journal, if any, so the bot remembers them after a restart.
+/// </summary>
+public class ChatBot
+{
+    private readonly StringBasedMarkovTextGenerator generator;
+    private readonly IMessageSource source;
+    private readonly IMessageSink sink;
+    private readonly IRandomNumberGenerator random;
+
+    // Messages starting with the command are answered instead of learned
+    public string Command { get; init; } = "!markov";
+
+    public ModelJournal? Journal { get; init; }
+
+    public ChatBot(StringBasedMarkovTextGenerator generator, IMessageSource source, IMessageSink sink, IRandomNumberGenerator random)
+    {
+        this.generator = generator;
+        this.source = source;
+        this.sink = sink;
+        this.random = random;
+    }
+
+    /// <summary>
+    /// Handles messages until cancelled or the source is closed
+    /// </summary>
+    public void Run(CancellationToken cancellationToken)
+    {
+        while (!cancellationToken.IsCancellationRequested && source.Receive(cancellationToken) is { } message)
+        {
+            Handle(message);
+        }
+    }
+
+    public void Handle(ChatMessage message)
+    {
+        if (message.Text.TrimStart().StartsWith(Command, StringComparison.OrdinalIgnoreCase))
+        {
+            // An overlong sentence is cut short instead of stopping the bot, and no reply is sent if the content filter bans everything
+            try
+            {
+                sink.Send(message.Channel, generator.Generate(random, generator.DefaultOptions with { TruncateOnOverflow = true }).Text);
+            }
+            catch (ContentFilterException)
+            {
+            }
+
+            return;
+        }
+
+        var patch = generator.Learn(message.Text);
+
+        if (patch.StarterPhrases.Count > 0 || patch.Transitions.Count > 0)
+        {
+            Journal?.Append(patch);
+        }
+    }
+}
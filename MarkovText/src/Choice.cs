@@ -0,0 +1,13 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// A single recorded generation step: the phrase the chain was in (null for the sentence starter)
+/// and the index of the chosen candidate among its possible transitions, or Truncation where a truncated sentence was cut short
+/// </summary>
+public readonly record struct Choice(string? Phrase, int Index)
+{
+    /// <summary>
+    /// Ends the choice log of a truncated sentence, so replaying it cuts the sentence at the same clause boundary
+    /// </summary>
+    public static readonly Choice Truncation = new(null, -1);
+}
@@ -0,0 +1,14 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Normalizes multiple consecutive whitespace characters into a single space
+/// </summary>
+public partial class CollapseWhitespacePreprocessor : IPreprocessor
+{
+    [GeneratedRegex(@"\s+")]
+    private static partial Regex MultipleWhitespaceRegex();
+
+    public string Process(string text) => MultipleWhitespaceRegex().Replace(text, " ");
+}
@@ -2,14 +2,39 @@
 
 namespace MarkovText;
 
-public class CommandLineOptions
+/// <summary>
+/// Options of the default verb, which builds the model from the corpus on every run, see the train, generate, and serve verbs otherwise
+/// </summary>
+[Verb("run", isDefault: true, HelpText = "Build a model from the corpus and generate sentences from it.")]
+public class CommandLineOptions : CorpusCommandLineOptions
 {
-    [Option('o', "order", Required = false, HelpText = "The order of the Markov chain.", Default = 2)]
-    public required int Order { get; set; }
+    [Option('n', "count", Required = false, HelpText = "The number of sentences to generate.", Default = 1)]
+    public required int Count { get; set; }
 
-    [Option('c', "corpus", Required = false, HelpText = "Path to the corpus text file(s).", Default = new [] { ArrayBasedMarkovTextGenerator.DefaultCorpusPath })]
-    public required IEnumerable<string> Corpus { get; set; }
+    [Option("separator", Required = false, HelpText = "Separates the generated sentences, e.g., \" \" to join them into a paragraph. \\n and \\t are unescaped.", Default = "\\n")]
+    public required string Separator { get; set; }
 
-    [Option('s', "seed", Required = false, HelpText = "Seed used to initialize random number generator.")]
-    public required string Seed { get; set; }
+    [Option("persona", Required = false, HelpText = "Path to a .persona file to generate from instead of a corpus.")]
+    public string? Persona { get; set; }
+
+    [Option("interned", Required = false, HelpText = "Intern the words as integer IDs to save memory, with the packed model for order 2. Only generates sentences.")]
+    public bool Interned { get; set; }
+
+    [Option("save-persona", Required = false, HelpText = "Save the model built from the corpus as a .persona file.")]
+    public string? SavePersona { get; set; }
+
+    [Option("name", Required = false, HelpText = "Name of the persona saved with --save-persona.", Default = "")]
+    public required string Name { get; set; }
+
+    [Option("perplexity", Required = false, HelpText = "Path to a held-out text file to evaluate the perplexity of the model on.")]
+    public string? Perplexity { get; set; }
+
+    [Option("explore", Required = false, HelpText = "Interactively browse the phrases of the model and step through a generation.")]
+    public bool Explore { get; set; }
+
+    [Option("teach", Required = false, HelpText = "Explain step by step how the Markov model is built from a small corpus and how a sentence is generated.")]
+    public bool Teach { get; set; }
+
+    [Option("stdio", Required = false, HelpText = "Answer newline-delimited JSON requests like {\"cmd\":\"generate\",\"count\":3} on standard input.")]
+    public bool Stdio { get; set; }
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Generates sentences of a StringBasedMarkovTextGenerator that satisfy constraints, e.g., a minimum length or a keyword
+/// </summary>
+public static class ConstrainedGeneration
+{
+    /// <summary>
+    /// Generates sentences until one satisfies the retry constraints, e.g., a minimum length or not copying a corpus sentence verbatim.
+    /// Sentences that exceed the word limit or have no allowed continuation are also retried.
+    /// Throws GenerationFailedException with the reason each attempt failed if the retry budget is exhausted.
+    /// </summary>
+    public static GenerationResult GenerateWithRetries(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, GenerationOptions options,
+        RetryOptions retryOptions)
+    {
+        // The choices are needed to reconstruct the tokens of the sentence
+        var recordingOptions = options with { RecordChoices = true };
+        var failures = new List<string>();
+
+        for (var attempt = 0; attempt < retryOptions.MaxAttempts; attempt++)
+        {
+            GenerationResult result;
+
+            try
+            {
+                result = generator.Generate(random, recordingOptions);
+            }
+            catch (Exception e) when (e is SentenceOverflowException or ContentFilterException)
+            {
+                failures.Add(e.Message);
+                continue;
+            }
+
+            var tokens = ChosenTokens(generator, result.Choices!, options.StarterSelection);
+
+            if (tokens.Count < retryOptions.MinWordCount)
+            {
+                failures.Add($"Too short, {tokens.Count} of at least {retryOptions.MinWordCount} words: {result.Text}");
+            }
+            else if (retryOptions.RequiredWord is { } requiredWord && !ContainsWord(tokens, requiredWord))
+            {
+                failures.Add($"Without the word {requiredWord}: {result.Text}");
+            }
+            else if (retryOptions.RejectVerbatimCopies && generator.Statistics.ContainsSentence(tokens))
+            {
+                failures.Add($"Verbatim copy of a corpus sentence: {result.Text}");
+            }
+            else
+            {
+                return options.RecordChoices ? result : result with { Choices = null };
+            }
+        }
+
+        throw new GenerationFailedException(failures);
+    }
+
+    /// <summary>
+    /// Generates a random sentence containing the keyword, ignoring case and punctuation, e.g., for naming or marketing generators.
+    /// With the reverse model (see BuildOptions.BuildReverseModel), the sentence grows in both directions from a random phrase
+    /// containing the keyword, with the options in both directions (the observer sees the backward steps first, with the reversed
+    /// phrases of the reverse model). Without it, sentences are generated with the options until one contains the keyword.
+    /// </summary>
+    public static string GenerateContaining(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, string keyword,
+        GenerationOptions? options = null, int maxAttempts = 100)
+    {
+        generator.ThrowIfNoModel();
+
+        if (generator.ReverseModel == null)
+        {
+            var retryOptions = new RetryOptions { MaxAttempts = maxAttempts, RequiredWord = keyword, RejectVerbatimCopies = false };
+            return generator.GenerateWithRetries(random, options ?? generator.DefaultOptions, retryOptions).Text;
+        }
+
+        options ??= generator.DefaultOptions;
+        TransitionSampling.ThrowIfInvalidSampling(options);
+
+        var keywordPhrases = generator.KeywordPhrases ??= IndexKeywordPhrases(generator);
+
+        if (!keywordPhrases.TryGetValue(keyword.TrimPunctuation(), out var states))
+        {
+            throw new ArgumentException($"No phrase contains {keyword}.", nameof(keyword));
+        }
+
+        if (options.ContentFilter is { } filter)
+        {
+            states = states.Where(state => filter.IsAllowedPhrase(generator.Phrase(state))).ToList();
+
+            if (states.Count == 0)
+            {
+                throw new ContentFilterException($"All phrases containing {keyword} are banned by the content filter.");
+            }
+        }
+
+        var maxWordCount = options.MaxWordCount ?? generator.MaxWordCount;
+        var isAllowedTransition = generator.IsAllowedTransition(options);
+        var transitionWeight = generator.TransitionWeight();
+        var truncated = false;
+
+        var state = states.Random(random);
+        var tokens = generator.GrowBackwards(Enumerable.Reverse(state).ToArray(), random, options, isAllowedTransition, transitionWeight);
+        var keywordEnd = tokens.Count;  // The number of tokens up to the end of the phrase
+
+        tokens.Reverse();
+
+        while (generator.Chain.TryGetTransitions(state, out var transitions))
+        {
+            if (tokens.Count >= maxWordCount)    // Safety check to prevent infinite loops
+            {
+                if (!options.TruncateOnOverflow)
+                {
+                    throw new SentenceOverflowException($"Word limit {tokens.Count} reached for sentence:\n{string.Join(' ', tokens)}");
+                }
+
+                truncated = true;
+                break;
+            }
+
+            if (!generator.TryChooseTransition(state, transitions, random, null, options, isAllowedTransition, transitionWeight, out var index))
+            {
+                throw new ContentFilterException($"All transitions from '{generator.Phrase(state)}' are excluded by the generation options for sentence:\n{string.Join(' ', tokens)}");
+            }
+
+            state = transitions[index];
+            tokens.Add(generator.Word(state));
+        }
+
+        var text = generator.Render(tokens);
+
+        if (truncated)
+        {
+            // The sentence is never cut short before the keyword
+            var stringBuilder = new StringBuilder(text);
+            SentenceFormatting.TruncateAtClauseBoundary(stringBuilder, generator.JoinTokens(tokens.Take(keywordEnd).Select(generator.Recase)).Length);
+            text = stringBuilder.ToString();
+        }
+
+        foreach (var postProcessor in options.PostProcessors)
+        {
+            text = postProcessor.Process(text);
+        }
+
+        return text;
+    }
+
+    // The distinct states containing each word, see StringBasedMarkovTextGenerator.KeywordPhrases
+    private static Dictionary<string, List<string[]>> IndexKeywordPhrases(StringBasedMarkovTextGenerator generator)
+    {
+        var keywordPhrases = new Dictionary<string, List<string[]>>(StringComparer.OrdinalIgnoreCase);
+
+        // Sentence ending phrases are only the next states of transitions
+        var states = generator.Chain.ContinuedStates
+            .Concat(generator.Chain.ContinuedStates.SelectMany(state => generator.GetTransitions(state)))
+            .Distinct();
+
+        foreach (var state in states)
+        {
+            foreach (var word in state.Select(token => token.TrimPunctuation()).Distinct(StringComparer.OrdinalIgnoreCase))
+            {
+                keywordPhrases.AddToList(word, state);
+            }
+        }
+
+        return keywordPhrases;
+    }
+
+    private static bool ContainsWord(IEnumerable<string> tokens, string word) =>
+        tokens.Any(token => string.Equals(token.TrimPunctuation(), word.TrimPunctuation(), StringComparison.OrdinalIgnoreCase));
+
+    // The tokens of a generated sentence, reconstructed from the choices made during generation
+    private static List<string> ChosenTokens(StringBasedMarkovTextGenerator generator, IReadOnlyList<Choice> choices, StarterSelection starterSelection)
+    {
+        var starters = starterSelection == StarterSelection.Uniform ? generator.Chain.DistinctStarters : generator.Chain.Starters;
+        var state = starters[choices[0].Index];
+        var tokens = new List<string>(state);
+
+        // A truncated sentence ends with the choices up to its clause boundary
+        foreach (var (_, index) in choices.Skip(1).Where(choice => choice != Choice.Truncation))
+        {
+            state = generator.GetTransitions(state)[index];
+            tokens.Add(generator.Word(state));
+        }
+
+        return tokens;
+    }
+}
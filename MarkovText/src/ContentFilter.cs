@@ -0,0 +1,55 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Blocklist of words and patterns that must not appear in generated sentences
+/// </summary>
+public class ContentFilter
+{
+    // Banned words are matched case-insensitively, ignoring surrounding punctuation, e.g., "dog" bans "Dog,"
+    private readonly HashSet<string> bannedWords;
+
+    // Banned patterns are matched against the word as it appears in the corpus
+    private readonly Regex[] bannedPatterns;
+
+    public ContentFilter(IEnumerable<string> bannedWords, IEnumerable<Regex>? bannedPatterns = null)
+    {
+        this.bannedWords = new HashSet<string>(bannedWords.Select(word => word.TrimPunctuation()), StringComparer.OrdinalIgnoreCase);
+        this.bannedPatterns = bannedPatterns?.ToArray() ?? Array.Empty<Regex>();
+    }
+
+    public bool IsAllowed(string word)
+    {
+        if (bannedWords.Contains(word.TrimPunctuation()))
+        {
+            return false;
+        }
+
+        foreach (var pattern in bannedPatterns)
+        {
+            if (pattern.IsMatch(word))
+            {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    /// <summary>
+    /// Checks every word of a space-separated phrase
+    /// </summary>
+    public bool IsAllowedPhrase(string phrase)
+    {
+        foreach (var word in phrase.Split(' '))
+        {
+            if (!IsAllowed(word))
+            {
+                return false;
+            }
+        }
+
+        return true;
+    }
+}
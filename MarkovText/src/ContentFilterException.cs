@@ -0,0 +1,6 @@
+﻿namespace MarkovText;
+
+public class ContentFilterException : Exception
+{
+    public ContentFilterException(string message) : base(message) { }
+}
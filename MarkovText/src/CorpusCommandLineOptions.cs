@@ -0,0 +1,36 @@
+﻿using CommandLine;
+
+namespace MarkovText;
+
+/// <summary>
+/// The options for reading and sampling the corpus and the order of the model, shared by the verbs that train on a corpus
+/// </summary>
+public abstract class CorpusCommandLineOptions
+{
+    [Option('o', "order", Required = false, HelpText = "The order of the Markov chain.", Default = 2)]
+    public required int Order { get; set; }
+
+    [Option('c', "corpus", Required = false, HelpText = "Path to the corpus text file(s) or glob patterns like texts/*.txt.", Default = new [] { ArrayBasedMarkovTextGenerator.DefaultCorpusPath })]
+    public required IEnumerable<string> Corpus { get; set; }
+
+    [Option("csv-column", Required = false, HelpText = "Column of .csv corpus files to train on, with each row as a document, e.g., review_text.")]
+    public string? CsvColumn { get; set; }
+
+    [Option("json-field", Required = false, HelpText = "Field of .jsonl corpus files to train on, with each record as a document, e.g., text or message.text.")]
+    public string? JsonField { get; set; }
+
+    [Option("url", Required = false, HelpText = "URL(s) of remote corpus texts, e.g., a Project Gutenberg plain text URL, used instead of the default corpus.")]
+    public IEnumerable<string> Url { get; set; } = Array.Empty<string>();
+
+    [Option("recursive", Required = false, HelpText = "Directory whose files with the --extensions are trained on, including subdirectories, instead of the default corpus.")]
+    public string? Recursive { get; set; }
+
+    [Option("extensions", Required = false, HelpText = "File extensions read by --recursive.", Default = new[] { ".txt" })]
+    public required IEnumerable<string> Extensions { get; set; }
+
+    [Option('s', "seed", Required = false, HelpText = "Seed used to initialize random number generator.")]
+    public required string Seed { get; set; }
+
+    [Option("sample", Required = false, HelpText = "Fraction of the corpus sentences to keep, for quick experiments.", Default = 1.0)]
+    public required double Sample { get; set; }
+}
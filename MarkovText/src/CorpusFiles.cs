@@ -0,0 +1,288 @@
+﻿using System.Text;
+using System.Text.Json;
+
+namespace MarkovText;
+
+public static class CorpusFiles
+{
+    // Wildcards in the file name of a glob pattern
+    private static readonly char[] Wildcards = { '*', '?' };
+
+    private static readonly HttpClient HttpClient = new();
+
+    // The number of characters ReadSentenceChunks reads at a time by default
+    public const int DefaultChunkSize = 1 << 20;
+
+    /// <summary>
+    /// Expands glob patterns like "texts/*.txt" into the matching files, in ordinal order per pattern.
+    /// Paths without wildcards are kept as they are, even if the file doesn't exist, so reading it reports the missing file.
+    /// </summary>
+    public static IEnumerable<string> Expand(IEnumerable<string> patterns)
+    {
+        foreach (var pattern in patterns)
+        {
+            var fileName = Path.GetFileName(pattern);
+
+            if (fileName.IndexOfAny(Wildcards) < 0)
+            {
+                yield return pattern;
+                continue;
+            }
+
+            var directory = Path.GetDirectoryName(pattern);
+
+            foreach (var path in Directory.GetFiles(string.IsNullOrEmpty(directory) ? "." : directory, fileName).Order(StringComparer.Ordinal))
+            {
+                yield return path;
+            }
+        }
+    }
+
+    /// <summary>
+    /// The files in the directory and its subdirectories with one of the extensions, e.g., ".txt", in ordinal order
+    /// </summary>
+    public static IEnumerable<string> FindRecursive(string directory, IEnumerable<string> extensions)
+    {
+        var extensionSet = new HashSet<string>(extensions.Select(extension => extension.StartsWith('.') ? extension : $".{extension}"), StringComparer.OrdinalIgnoreCase);
+
+        return Directory.EnumerateFiles(directory, "*", SearchOption.AllDirectories)
+            .Where(path => extensionSet.Contains(Path.GetExtension(path)))
+            .Order(StringComparer.Ordinal);
+    }
+
+    public static bool IsUrl(string source) => source.StartsWith("http://", StringComparison.OrdinalIgnoreCase) || source.StartsWith("https://", StringComparison.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Reads a corpus file, or downloads a remote text like a Project Gutenberg URL.
+    /// HTML files are converted to plain text, and Markdown files are stripped of their syntax.
+    /// If a CSV column or JSON field is given, CSV and JSON Lines files are read as the documents in that column or field.
+    /// </summary>
+    public static string Read(string source, string? csvColumn = null, string? jsonField = null)
+    {
+        var text = IsUrl(source) ? HttpClient.GetStringAsync(source).GetAwaiter().GetResult() : File.ReadAllText(source);
+
+        return Path.GetExtension(source).ToLowerInvariant() switch
+        {
+            ".html" or ".htm" => new HtmlToTextPreprocessor().Process(text),
+            ".md" or ".markdown" => new StripMarkdownPreprocessor().Process(text),
+            ".csv" when csvColumn != null => JoinDocuments(ReadCsvColumn(new StringReader(text), csvColumn)),
+            ".jsonl" when jsonField != null => JoinDocuments(ReadJsonLinesField(new StringReader(text), jsonField)),
+            _ => text
+        };
+    }
+
+    /// <summary>
+    /// Reads the text column of a CSV dataset with a header row, e.g., "review_text", as a corpus with each row as a document
+    /// </summary>
+    public static string ReadCsv(string path, string column)
+    {
+        using var reader = new StreamReader(path);
+        return JoinDocuments(ReadCsvColumn(reader, column));
+    }
+
+    /// <summary>
+    /// Reads a large corpus in chunks of about chunkSize characters that end at a sentence boundary, so only one chunk is in
+    /// memory at a time and no sentence is split between chunks. Chunks don't end inside brackets either, so editorial notes like
+    /// "[Illustration: A dog. A cat.]" are stripped as a whole. Text without sentence endings is only cut, at the last whitespace,
+    /// once more than a chunk of it has been read, so memory stays bounded.
+    /// </summary>
+    public static IEnumerable<string> ReadSentenceChunks(TextReader reader, int chunkSize = DefaultChunkSize)
+    {
+        if (chunkSize < 1)
+        {
+            throw new ArgumentException("The chunk size must be positive.", nameof(chunkSize));
+        }
+
+        var buffer = new char[chunkSize];
+        var carried = new StringBuilder();
+        var inBracket = false;
+        int read;
+
+        while ((read = reader.ReadBlock(buffer, 0, buffer.Length)) > 0)
+        {
+            (var end, inBracket) = ChunkEnd(buffer.AsSpan(0, read), cutAtWhitespace: carried.Length >= chunkSize, inBracket);
+
+            if (end < 0)
+            {
+                carried.Append(buffer, 0, read);
+                continue;
+            }
+
+            carried.Append(buffer, 0, end);
+            yield return carried.ToString();
+            carried.Clear().Append(buffer, end, read - end);
+        }
+
+        if (carried.Length > 0)
+        {
+            yield return carried.ToString();
+        }
+    }
+
+    // The length of the text up to the whitespace after the last sentence delimiter outside brackets, or else optionally the last
+    // whitespace, or -1. Also returns whether a bracket is open at the end of the text, given whether one is open at its start.
+    private static (int End, bool InBracket) ChunkEnd(ReadOnlySpan<char> text, bool cutAtWhitespace, bool inBracket)
+    {
+        var sentenceEnd = -1;
+        var lastWhitespace = -1;
+
+        for (var i = 0; i < text.Length; i++)
+        {
+            if (text[i] is '[' or ']')
+            {
+                inBracket = text[i] == '[';
+            }
+            else if (i > 0 && char.IsWhiteSpace(text[i]))
+            {
+                lastWhitespace = i;
+
+                if (!inBracket && ".!?".Contains(text[i - 1]))
+                {
+                    sentenceEnd = i;
+                }
+            }
+        }
+
+        return (sentenceEnd >= 0 ? sentenceEnd : cutAtWhitespace ? lastWhitespace : -1, inBracket);
+    }
+
+    /// <summary>
+    /// The values of the named column of each row of a CSV file with a header row, skipping empty values.
+    /// Quoted values may contain commas, line breaks, and quotes escaped as "".
+    /// </summary>
+    public static IEnumerable<string> ReadCsvColumn(TextReader reader, string column)
+    {
+        using var records = ParseCsv(reader).GetEnumerator();
+
+        if (!records.MoveNext())
+        {
+            throw new InvalidDataException("The CSV file has no header row.");
+        }
+
+        var index = records.Current.FindIndex(name => string.Equals(name.Trim(), column, StringComparison.OrdinalIgnoreCase));
+
+        if (index < 0)
+        {
+            throw new InvalidDataException($"The CSV file has no column {column}, the columns are {string.Join(", ", records.Current)}.");
+        }
+
+        while (records.MoveNext())
+        {
+            if (index < records.Current.Count && !string.IsNullOrWhiteSpace(records.Current[index]))
+            {
+                yield return records.Current[index];
+            }
+        }
+    }
+
+    /// <summary>
+    /// The string values of the field of each record of a JSON Lines file, e.g., the "text" of tweets or chat messages,
+    /// skipping blank lines and records without the field. Nested fields are separated by dots, e.g., "message.text".
+    /// </summary>
+    public static IEnumerable<string> ReadJsonLinesField(TextReader reader, string field)
+    {
+        var path = field.Split('.');
+        var lineNumber = 0;
+
+        while (reader.ReadLine() is { } line)
+        {
+            lineNumber++;
+
+            if (string.IsNullOrWhiteSpace(line))
+            {
+                continue;
+            }
+
+            JsonDocument record;
+
+            try
+            {
+                record = JsonDocument.Parse(line);
+            }
+            catch (JsonException e)
+            {
+                throw new InvalidDataException($"Invalid JSON on line {lineNumber}: {e.Message}", e);
+            }
+
+            using (record)
+            {
+                if (Lookup(record.RootElement, path) is { ValueKind: JsonValueKind.String } value && value.GetString() is { } text && !string.IsNullOrWhiteSpace(text))
+                {
+                    yield return text;
+                }
+            }
+        }
+    }
+
+    // The nested field of a JSON record, or null if the record doesn't have it
+    private static JsonElement? Lookup(JsonElement element, string[] path)
+    {
+        foreach (var name in path)
+        {
+            if (element.ValueKind != JsonValueKind.Object || !element.TryGetProperty(name, out element))
+            {
+                return null;
+            }
+        }
+
+        return element;
+    }
+
+    // Ends each document with a sentence, so the last sentence of a row or record doesn't run into the first sentence of the next
+    private static string JoinDocuments(IEnumerable<string> documents) =>
+        string.Join("\n", documents.Select(document => document.TrimEnd() is var text && ".!?".Contains(text[^1]) ? text : $"{text}."));
+
+    // The fields of each record, following RFC 4180
+    private static IEnumerable<List<string>> ParseCsv(TextReader reader)
+    {
+        var record = new List<string>();
+        var field = new StringBuilder();
+        var quoted = false;
+        int character;
+
+        while ((character = reader.Read()) >= 0)
+        {
+            if (quoted)
+            {
+                if (character != '"')
+                {
+                    field.Append((char)character);
+                }
+                else if (reader.Peek() == '"')
+                {
+                    field.Append((char)reader.Read());
+                }
+                else
+                {
+                    quoted = false;
+                }
+            }
+            else if (character == '"')
+            {
+                quoted = true;
+            }
+            else if (character == ',')
+            {
+                record.Add(field.ToString());
+                field.Clear();
+            }
+            else if (character == '\n')
+            {
+                record.Add(field.ToString().TrimEnd('\r'));
+                field.Clear();
+                yield return record;
+                record = new List<string>();
+            }
+            else
+            {
+                field.Append((char)character);
+            }
+        }
+
+        if (field.Length > 0 || record.Count > 0)
+        {
+            record.Add(field.ToString().TrimEnd('\r'));
+            yield return record;
+        }
+    }
+}
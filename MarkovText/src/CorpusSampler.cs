@@ -0,0 +1,22 @@
+﻿namespace MarkovText;
+
+public static class CorpusSampler
+{
+    /// <summary>
+    /// Keeps a reproducible random subset of the sentences in the corpus
+    /// </summary>
+    /// <param name="text">The corpus text</param>
+    /// <param name="fraction">The fraction of sentences to keep, between 0 and 1</param>
+    /// <param name="seed">The same seed always keeps the same sentences</param>
+    public static string Sample(string text, double fraction, string seed)
+    {
+        if (fraction is <= 0 or > 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(fraction), fraction, "The sample fraction must be greater than 0 and at most 1.");
+        }
+
+        var random = new Random(seed.GetStableHashCode());
+
+        return string.Join("\n", SentenceSplitter.Split(text).Where(_ => random.NextDouble() < fraction));
+    }
+}
@@ -0,0 +1,47 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Splits the corpus of a StringBasedMarkovTextGenerator into the token arrays of its sentences, or of its lines in line mode
+/// </summary>
+internal static class CorpusSplitting
+{
+    // Removes unwanted characters like page numbers, quotes, parentheses, etc., and splits the corpus into sentences or lines
+    public static IEnumerable<string[]> SplitCorpus(string corpus, BuildOptions options) => options.LineMode
+        ? SplitLines(corpus, options.Preprocessors, options.Tokenizer)
+        : SplitSentences(options.Preprocessors.Apply(corpus), options.Tokenizer);
+
+    // Each line is sanitized separately, so joining lines in the preprocessing pipeline doesn't merge them
+    private static IEnumerable<string[]> SplitLines(string corpus, IEnumerable<IPreprocessor> preprocessors, ITokenizer tokenizer)
+    {
+        foreach (var line in corpus.Split('\n'))
+        {
+            var tokens = tokenizer.Tokenize(preprocessors.Apply(line)).ToArray();
+
+            if (tokens.Length > 0)
+            {
+                yield return tokens;
+            }
+        }
+    }
+
+    public static IEnumerable<string[]> SplitSentences(string corpus, ITokenizer tokenizer)
+    {
+        var sentence = new List<string>();
+
+        foreach (var word in tokenizer.Tokenize(corpus))  // Split the corpus into words
+        {
+            sentence.Add(word);
+
+            if (tokenizer.IsSentenceEnd(word))
+            {
+                yield return sentence.ToArray();
+                sentence.Clear();
+            }
+        }
+
+        if (sentence.Count > 0)
+        {
+            yield return sentence.ToArray();    // The corpus may end without a sentence delimiter
+        }
+    }
+}
@@ -0,0 +1,238 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// What StringBasedMarkovTextGenerator knows about its corpus besides the transitions of its chain: how often each word occurs,
+/// the words starting sentences, hashes of the sentences to detect verbatim copies, how sentences begin, and the original spellings
+/// of lowercased words
+/// </summary>
+internal class CorpusStatistics
+{
+    // Number of occurrences of each word in the corpus
+    private readonly Dictionary<string, int> WordFrequencies = new();
+
+    // Words that start a sentence somewhere in the corpus
+    private readonly HashSet<string> SentenceInitialWords = new();
+
+    // The sanitized corpus sentences, if stored, see BuildOptions.StoreSentences
+    private readonly List<string> Sentences = new();
+
+    // Stable 64-bit hash codes of the space-joined tokens of each corpus sentence, to detect verbatim copies of the corpus.
+    // 32-bit hash codes would collide for a few of the sentences of a book already, rejecting new sentences as copies.
+    private readonly HashSet<long> SentenceHashes = new();
+
+    // Counts the original spellings of lowercased tokens (when not starting a sentence), e.g., "dublin" => { "Dublin": 12 }
+    private readonly Dictionary<string, Dictionary<string, int>> CasingCounts = new();
+
+    // Quotes that can open a sentence of dialogue, and their closing quotes
+    private static readonly Dictionary<char, char> Quotes = new() { ['"'] = '"', ['“'] = '”', ['\''] = '\'', ['‘'] = '’' };
+
+    // The most common original spelling of lowercased tokens, if it isn't lowercase, e.g., "dublin" => "Dublin"
+    public Dictionary<string, string> PreferredCasing { get; } = new();
+
+    // Counts how corpus sentences begin, see GenerationOptions.ReproduceOpeningStyle
+    public Dictionary<OpeningStyle, int> OpeningStyleCounts { get; } = new();
+
+    // Counts the opening quotes of quoted corpus sentences, e.g., '“' => 12
+    public Dictionary<char, int> OpeningQuoteCounts { get; } = new();
+
+    // The number of corpus tokens
+    public long TokenCount { get; private set; }
+
+    // The number of distinct words, the vocabulary of additive smoothing
+    public int WordCount => WordFrequencies.Count;
+
+    // EstimateMemoryBytes of the words, sentence hashes, and stored sentences, kept up to date as they are added and removed
+    public long MemoryBytes { get; private set; }
+
+    // The words and how often each occurs, most frequent first
+    public IEnumerable<(string Word, int Count)> Vocabulary() => WordFrequencies
+        .Where(word => word.Value > 0)
+        .OrderByDescending(word => word.Value)
+        .ThenBy(word => word.Key, StringComparer.Ordinal)
+        .Select(word => (word.Key, word.Value));
+
+    // Counts the words of a corpus sentence, and how it begins
+    public void AddSentence(string[] words)
+    {
+        SentenceInitialWords.Add(words[0]);
+
+        if (SentenceHashes.Add(string.Join(' ', words).GetStableHashCode64()))
+        {
+            MemoryBytes += MemorySize.DictionaryEntryOverhead;
+        }
+
+        foreach (var word in words)
+        {
+            AddWordFrequency(word, 1);
+        }
+    }
+
+    public void AddSentenceInitialWord(string word) => SentenceInitialWords.Add(word);
+
+    // Adds delta occurrences of the word, words without occurrences are removed so the vocabulary size used for smoothing stays exact
+    public void AddWordFrequency(string word, int delta)
+    {
+        var previousCount = WordFrequencies.GetValueOrDefault(word);
+        var count = previousCount + delta;
+        TokenCount += Math.Max(count, 0) - previousCount;
+
+        if (count > 0)
+        {
+            if (previousCount == 0)
+            {
+                MemoryBytes += MemorySize.DictionaryEntryOverhead + MemorySize.String(word);
+            }
+
+            WordFrequencies[word] = count;
+        }
+        else if (WordFrequencies.Remove(word))
+        {
+            MemoryBytes -= MemorySize.DictionaryEntryOverhead + MemorySize.String(word);
+        }
+    }
+
+    // Hapax legomena that never started a sentence in the corpus
+    public bool IsRareWord(string word) => WordFrequencies.GetValueOrDefault(word) == 1 && !SentenceInitialWords.Contains(word);
+
+    // Whether the tokens are those of a corpus sentence
+    public bool ContainsSentence(IEnumerable<string> tokens) => SentenceHashes.Contains(string.Join(' ', tokens).GetStableHashCode64());
+
+    public void StoreSentence(string text)
+    {
+        Sentences.Add(text);
+        MemoryBytes += MemorySize.Reference + MemorySize.String(text);
+    }
+
+    // A random stored corpus sentence, null if none were stored
+    public string? RandomSentence(IRandomNumberGenerator random) => Sentences.Count > 0 ? Sentences.Random(random) : null;
+
+    public void CountOpeningStyle(string firstToken)
+    {
+        var style = GetOpeningStyle(firstToken[0]);
+        OpeningStyleCounts[style] = OpeningStyleCounts.GetValueOrDefault(style) + 1;
+
+        if (style == OpeningStyle.Quoted)
+        {
+            OpeningQuoteCounts[firstToken[0]] = OpeningQuoteCounts.GetValueOrDefault(firstToken[0]) + 1;
+        }
+    }
+
+    private static OpeningStyle GetOpeningStyle(char first)
+    {
+        if (Quotes.ContainsKey(first))
+        {
+            return OpeningStyle.Quoted;
+        }
+
+        if (char.IsDigit(first))
+        {
+            return OpeningStyle.Digit;
+        }
+
+        if (char.IsUpper(first))
+        {
+            return OpeningStyle.Capitalized;
+        }
+
+        return char.IsLower(first) ? OpeningStyle.Lowercase : OpeningStyle.Other;
+    }
+
+    // Capitalizes, lowercases, or quotes the sentence with the same probability as a corpus sentence begins that way
+    public void ApplyRandomOpeningStyle(StringBuilder stringBuilder, IRandomNumberGenerator random)
+    {
+        // Sentences beginning with digits or quotes are left as they are
+        var currentStyle = GetOpeningStyle(stringBuilder[0]);
+
+        if (currentStyle is OpeningStyle.Digit or OpeningStyle.Quoted or OpeningStyle.Other)
+        {
+            return;
+        }
+
+        var capitalized = OpeningStyleCounts.GetValueOrDefault(OpeningStyle.Capitalized);
+        var lowercase = OpeningStyleCounts.GetValueOrDefault(OpeningStyle.Lowercase);
+        var quoted = OpeningStyleCounts.GetValueOrDefault(OpeningStyle.Quoted);
+
+        if (capitalized + lowercase + quoted == 0)
+        {
+            return;
+        }
+
+        var value = random.Next(capitalized + lowercase + quoted);
+
+        if (value < capitalized)
+        {
+            stringBuilder[0] = char.ToUpperInvariant(stringBuilder[0]);
+        }
+        else if (value < capitalized + lowercase)
+        {
+            stringBuilder[0] = char.ToLowerInvariant(stringBuilder[0]);
+        }
+        else
+        {
+            var openingQuote = RandomOpeningQuote(value - capitalized - lowercase);
+
+            // Quoted sentences begin with a capital letter, like the quoted corpus sentences
+            stringBuilder[0] = char.ToUpperInvariant(stringBuilder[0]);
+            stringBuilder.Insert(0, openingQuote);
+            stringBuilder.Append(Quotes[openingQuote]);
+        }
+    }
+
+    // The opening quote of the quoted corpus sentences at the value, from 0 up to their number, so each quote is as likely as in the corpus
+    private char RandomOpeningQuote(int value)
+    {
+        foreach (var (quote, count) in OpeningQuoteCounts)
+        {
+            if (value < count)
+            {
+                return quote;
+            }
+
+            value -= count;
+        }
+
+        return OpeningQuoteCounts.Keys.Last();
+    }
+
+    // Lowercases the tokens of the sentence, counting the original spellings
+    public void NormalizeCase(string[] sentence)
+    {
+        for (var i = 0; i < sentence.Length; i++)
+        {
+            var lowercase = sentence[i].ToLowerInvariant();
+
+            // Sentence starters are capitalized regardless, so they don't say anything about the word
+            if (i > 0)
+            {
+                if (!CasingCounts.TryGetValue(lowercase, out var spellings))
+                {
+                    spellings = new Dictionary<string, int>();
+                    CasingCounts[lowercase] = spellings;
+                }
+
+                spellings[sentence[i]] = spellings.GetValueOrDefault(sentence[i]) + 1;
+            }
+
+            sentence[i] = lowercase;
+        }
+    }
+
+    public void UpdatePreferredCasing()
+    {
+        foreach (var (lowercase, spellings) in CasingCounts)
+        {
+            var preferred = spellings.MaxBy(spelling => spelling.Value).Key;
+
+            if (preferred != lowercase)
+            {
+                PreferredCasing[lowercase] = preferred;
+            }
+            else
+            {
+                PreferredCasing.Remove(lowercase);
+            }
+        }
+    }
+}
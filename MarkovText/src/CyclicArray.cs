@@ -1,9 +1,17 @@
-﻿namespace MarkovText;
+﻿using System.Collections;
 
-public class CyclicArray<T>
+namespace MarkovText;
+
+public class CyclicArray<T> : IEnumerable<T>
 {
     private readonly T[] data;
 
+    // The slot the next pushed item is written to
+    private int next;
+
+    // The number of pushed items in the window, at most the size of the array
+    private int pushed;
+
     public CyclicArray(int size)
     {
         if (size <= 0)
@@ -20,25 +28,98 @@ public class CyclicArray<T>
         set => data[Index(index)] = value;
     }
 
-    private int Index(int index)
+    /// <summary>
+    /// The number of pushed items in the window
+    /// </summary>
+    public int Count => pushed;
+
+    /// <summary>
+    /// The number of items the array holds
+    /// </summary>
+    public int Capacity => data.Length;
+
+    /// <summary>
+    /// True once as many items have been pushed as the array holds
+    /// </summary>
+    public bool IsFull => pushed == data.Length;
+
+    /// <summary>
+    /// The least recently pushed item in the window
+    /// </summary>
+    public T Oldest => data[Index(next - pushed)];
+
+    /// <summary>
+    /// The most recently pushed item
+    /// </summary>
+    public T Latest => data[Index(next - 1)];
+
+    /// <summary>
+    /// Adds the item to the window, overwriting the oldest item if the array is full
+    /// </summary>
+    public void Push(T item)
     {
-        while (index < 0)
+        data[next] = item;
+        next = (next + 1) % data.Length;
+        pushed = Math.Min(pushed + 1, data.Length);
+    }
+
+    /// <summary>
+    /// Empties the window, e.g., at the end of a sentence
+    /// </summary>
+    public void Clear()
+    {
+        next = 0;
+        pushed = 0;
+    }
+
+    /// <summary>
+    /// The pushed items in the window, from the oldest to the latest
+    /// </summary>
+    public T[] LatestWindow()
+    {
+        var window = new T[pushed];
+
+        for (var i = 0; i < pushed; i++)
         {
-            index += data.Length;   // This could simply be calculated
+            window[i] = data[Index(next - pushed + i)];
         }
 
-        return index % data.Length;
+        return window;
     }
 
-    public T[] CreateOffsetArray(int offset)
+    /// <summary>
+    /// Enumerates the pushed items in the window, from the oldest to the latest, e.g., to join a phrase without copying the window
+    /// </summary>
+    public IEnumerator<T> GetEnumerator()
     {
-        var ret = new T[data.Length];
+        for (var i = 0; i < pushed; i++)
+        {
+            yield return data[Index(next - pushed + i)];
+        }
+    }
 
+    IEnumerator IEnumerable.GetEnumerator() => GetEnumerator();
+
+    /// <summary>
+    /// Enumerates all slots of the array starting at the offset, like CreateOffsetArray without copying them
+    /// </summary>
+    public IEnumerable<T> Rotated(int offset)
+    {
         for (var i = 0; i < data.Length; i++)
         {
-            ret[i] = data[(i + offset) % data.Length];
+            yield return data[Index(i + offset)];
         }
+    }
 
-        return ret;
+    private int Index(int index)
+    {
+        while (index < 0)
+        {
+            index += data.Length;   // This could simply be calculated
+        }
+
+        return index % data.Length;
     }
+
+    public T[] CreateOffsetArray(int offset) => Rotated(offset).ToArray();
 }
@@ -0,0 +1,13 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Limits the transition graph written by StringBasedMarkovTextGenerator.ExportDot, since the full graph of a book is unreadable
+/// </summary>
+public class DotExportOptions
+{
+    // Only the phrases with the most outgoing transitions in the corpus are written, all phrases if null
+    public int? MaxStates { get; init; }
+
+    // Only the most frequent continuations of each phrase are written, all continuations if null
+    public int? MaxEdgesPerState { get; init; }
+}
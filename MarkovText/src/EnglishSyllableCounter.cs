@@ -0,0 +1,39 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Estimates the syllables of English words as their groups of vowels, not counting a silent final e, e.g., "make" and "the" as one
+/// and "little" as two. Good enough for haiku, but irregular words like "poem" are miscounted, so use a pronouncing dictionary
+/// like CMUdict when exact counts matter.
+/// </summary>
+public class EnglishSyllableCounter : ISyllableCounter
+{
+    private const string Vowels = "aeiouy";
+
+    public int CountSyllables(string word)
+    {
+        var letters = new string(word.ToLowerInvariant().Where(char.IsLetter).ToArray());
+
+        if (letters.Length == 0)
+        {
+            return 0;
+        }
+
+        var syllables = 0;
+
+        for (var i = 0; i < letters.Length; i++)
+        {
+            if (Vowels.Contains(letters[i]) && (i == 0 || !Vowels.Contains(letters[i - 1])))
+            {
+                syllables++;
+            }
+        }
+
+        // A final e is silent after a consonant, except in a final "le" like "little"
+        if (letters.Length > 2 && letters[^1] == 'e' && !Vowels.Contains(letters[^2]) && !letters.EndsWith("le"))
+        {
+            syllables--;
+        }
+
+        return Math.Max(syllables, 1);
+    }
+}
@@ -12,11 +12,59 @@ public static class ExtensionUtils
         list.Add(value);
     }
 
-    public static T Random<T>(this List<T> list, IRandomNumberGenerator rnd)
+    public static void Increment<T>(this Dictionary<T, int> counts, T key, int count = 1) where T : notnull
+    {
+        counts[key] = counts.GetValueOrDefault(key) + count;
+    }
+
+    public static T Random<T>(this IReadOnlyList<T> list, IRandomNumberGenerator rnd)
     {
         return list[rnd.Next(list.Count)];
     }
 
+    /// <summary>
+    /// The items in a random order, with the Fisher-Yates shuffle
+    /// </summary>
+    public static List<T> Shuffled<T>(this IEnumerable<T> items, IRandomNumberGenerator rnd)
+    {
+        var list = items.ToList();
+
+        for (var i = list.Count - 1; i > 0; i--)
+        {
+            var j = rnd.Next(i + 1);
+            (list[i], list[j]) = (list[j], list[i]);
+        }
+
+        return list;
+    }
+
+    /// <summary>
+    /// Picks a random item with probability proportional to its weight
+    /// </summary>
+    public static T WeightedRandom<T>(this IReadOnlyList<T> list, IRandomNumberGenerator rnd, Func<T, int> weight)
+    {
+        var value = rnd.Next(list.Sum(weight));
+
+        foreach (var item in list)
+        {
+            if (value < weight(item))
+            {
+                return item;
+            }
+
+            value -= weight(item);
+        }
+
+        return list[^1];
+    }
+
+    /// <summary>
+    /// Removes punctuation surrounding a word, e.g., "(dog)," => "dog"
+    /// </summary>
+    public static string TrimPunctuation(this string word) => word.Trim().Trim(PunctuationCharacters);
+
+    private static readonly char[] PunctuationCharacters = { '.', ',', ';', ':', '?', '!', '"', '\'', '(', ')', '-' };
+
     /// <summary>
     /// Thanks to Scott Chamberlain for the idea/implementation for this method.
     /// https://stackoverflow.com/a/36845864
@@ -58,4 +106,23 @@ public static class ExtensionUtils
             return hash1 + (hash2*1566083941);
         }
     }
+
+    /// <summary>
+    /// 64-bit FNV-1a hash that is the same on every run and platform, for sets of hashes where 32-bit collisions are likely,
+    /// e.g., the sentences of a large corpus
+    /// </summary>
+    public static long GetStableHashCode64(this string str)
+    {
+        unchecked
+        {
+            var hash = (long)14695981039346656037;
+
+            foreach (var character in str)
+            {
+                hash = (hash ^ character) * 1099511628211;
+            }
+
+            return hash;
+        }
+    }
 }
\ No newline at end of file
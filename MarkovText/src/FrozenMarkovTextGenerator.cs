@@ -0,0 +1,235 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Read-only Markov text generator with the model finalized into flat arrays, for generation throughput and cache locality.
+/// Phrases are numbered states, and the transitions of all states are stored back to back in compressed sparse row layout:
+/// the transitions of a state are the index range between its edge start and the next state's, and each transition holds
+/// the next state, the appended word, and the cumulative count for sampling. Generation needs no dictionary lookups or hashing.
+/// Created with StringBasedMarkovTextGenerator.Freeze.
+/// </summary>
+public sealed class FrozenMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount { get; init; } = 1000;
+
+    // The tokenizer the model was built with, used to join the generated tokens
+    private readonly ITokenizer tokenizer;
+
+    // The order of the Markov chain (how many words in the "state" of the chain)
+    private readonly int order;
+
+    // The distinct tokens, referenced by their index
+    private readonly string[] symbols;
+
+    // The symbols of the phrase of each state, Order per state
+    private readonly int[] stateTokens;
+
+    // The state and cumulative count of each starter phrase
+    private readonly int[] starterStates;
+    private readonly int[] starterCumulativeCounts;
+
+    // The first edge of each state, and the end of the last state's edges
+    private readonly int[] edgeStarts;
+
+    // The next state, the appended word symbol, and the cumulative count of each transition
+    private readonly int[] edgeNextStates;
+    private readonly int[] edgeWords;
+    private readonly int[] edgeCumulativeCounts;
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // The state of each phrase, only built when the model is inspected, since generation doesn't need it
+    private readonly Lazy<Dictionary<string, int>> phraseStates;
+
+    public FrozenMarkovTextGenerator(ModelPatch model, ITokenizer tokenizer)
+    {
+        if (model.Order < 1)
+        {
+            throw new ArgumentException($"The order of the model must be positive, but it is {model.Order}.", nameof(model));
+        }
+
+        if (!model.StarterPhrases.Any(starter => starter.Delta > 0))
+        {
+            throw new ArgumentException("The model has no starter phrases, so no sentence can be generated.", nameof(model));
+        }
+
+        this.tokenizer = tokenizer;
+        order = model.Order;
+
+        var symbolTable = new SymbolTable();
+        var states = new List<string>();
+        var stateIndices = new Dictionary<string, int>();
+
+        int State(string phrase)
+        {
+            if (!stateIndices.TryGetValue(phrase, out var index))
+            {
+                index = states.Count;
+                stateIndices[phrase] = index;
+                states.Add(phrase);
+            }
+
+            return index;
+        }
+
+        var starters = model.StarterPhrases.Where(starter => starter.Delta > 0).Select(starter => (State: State(starter.Phrase), Count: starter.Delta)).ToList();
+        var stateEdges = new Dictionary<int, List<(int Next, int Word, int Count)>>();
+
+        foreach (var (phrase, suffixPhrase, word, count) in model.Transitions.Where(transition => transition.Delta > 0))
+        {
+            stateEdges.AddToList(State(phrase), (State(suffixPhrase), symbolTable.Intern(word), count));
+        }
+
+        // Tokens never contain spaces, so phrases can be split back into their tokens
+        stateTokens = states.SelectMany(phrase => phrase.Split(' ').Select(symbolTable.Intern)).ToArray();
+
+        if (stateTokens.Length != states.Count * order)
+        {
+            throw new ArgumentException($"Every phrase of the model must have {order} tokens.", nameof(model));
+        }
+
+        starterStates = starters.Select(starter => starter.State).ToArray();
+        starterCumulativeCounts = CumulativeCounts(starters.Select(starter => starter.Count));
+
+        edgeStarts = new int[states.Count + 1];
+        var edges = new List<(int Next, int Word, int Count)>();
+        var cumulativeCounts = new List<int>();
+
+        for (var state = 0; state < states.Count; state++)
+        {
+            edgeStarts[state] = edges.Count;
+
+            if (stateEdges.TryGetValue(state, out var outgoing))
+            {
+                edges.AddRange(outgoing);
+                cumulativeCounts.AddRange(CumulativeCounts(outgoing.Select(edge => edge.Count)));
+            }
+        }
+
+        edgeStarts[states.Count] = edges.Count;
+        edgeNextStates = edges.Select(edge => edge.Next).ToArray();
+        edgeWords = edges.Select(edge => edge.Word).ToArray();
+        edgeCumulativeCounts = cumulativeCounts.ToArray();
+
+        symbols = Enumerable.Range(0, symbolTable.Count).Select(symbol => symbolTable[symbol]).ToArray();
+        phraseStates = new Lazy<Dictionary<string, int>>(() => Enumerable.Range(0, edgeStarts.Length - 1).ToDictionary(Phrase));
+    }
+
+    public override string ToString() => "Frozen";
+
+    public void BuildMarkovModel(string corpus, int order = 2) =>
+        throw new NotSupportedException($"Frozen models are read-only. Build a {nameof(StringBasedMarkovTextGenerator)} and freeze it with Freeze instead.");
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+        GenerateSentence(random, stringBuilder);
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    /// <summary>
+    /// Appends a random Markov text to the builder, separated by the tokenizer from any text already in it.
+    /// Callers reusing their own buffer generate without allocating, e.g., in benchmarks.
+    /// </summary>
+    public void GenerateSentence(IRandomNumberGenerator random, StringBuilder output)
+    {
+        var start = output.Length;
+        var wordCount = order;  // Track the current word count to prevent infinite loops
+
+        // Choose a random starter state, in proportion to how many sentences it starts
+        var state = starterStates[Sample(random, starterCumulativeCounts, 0, starterStates.Length)];
+
+        for (var i = 0; i < order; i++)
+        {
+            tokenizer.Append(output, symbols[stateTokens[state * order + i]]);
+        }
+
+        // Continuously generate words based on the Markov chain, until reaching a state without transitions
+        while (edgeStarts[state] < edgeStarts[state + 1])
+        {
+            if (++wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{output.ToString(start, output.Length - start)}");
+            }
+
+            var edge = Sample(random, edgeCumulativeCounts, edgeStarts[state], edgeStarts[state + 1]);
+
+            state = edgeNextStates[edge];
+            tokenizer.Append(output, symbols[edgeWords[edge]]);
+        }
+    }
+
+    /// <summary>
+    /// The distinct sentence starter phrases and the number of corpus sentences starting with them
+    /// </summary>
+    public IReadOnlyList<(string Phrase, int Count)> GetStarterPhrases() =>
+        starterStates.Select((state, i) => (Phrase(state), CountAt(starterCumulativeCounts, 0, i))).ToList();
+
+    /// <summary>
+    /// The words that can follow a phrase and their number of occurrences in the corpus, e.g., to explore the structure
+    /// of a shipped model. Empty if the phrase ends the sentence, and null if the model has no such phrase.
+    /// </summary>
+    public IReadOnlyList<(string Word, int Count)>? GetSuccessors(string phrase)
+    {
+        if (!phraseStates.Value.TryGetValue(phrase, out var state))
+        {
+            return null;
+        }
+
+        return Enumerable.Range(edgeStarts[state], edgeStarts[state + 1] - edgeStarts[state])
+            .Select(edge => (symbols[edgeWords[edge]], CountAt(edgeCumulativeCounts, edgeStarts[state], edge)))
+            .ToList();
+    }
+
+    /// <summary>
+    /// Generates many sentences concurrently, e.g., thousands of samples for a dataset or a stress test.
+    /// Each sentence has its own random number generator seeded from the seed and its index, so the batch is the same
+    /// for the same seed however the sentences are spread over the threads. Overflowing sentences fail the batch
+    /// with an AggregateException, like Parallel.For.
+    /// </summary>
+    public IReadOnlyList<string> GenerateBatch(int count, string seed, int maxDegreeOfParallelism = -1)
+    {
+        var sentences = new string[count];
+        var seedHash = seed.GetStableHashCode();
+
+        Parallel.For(0, count, new ParallelOptions { MaxDegreeOfParallelism = maxDegreeOfParallelism }, index =>
+            sentences[index] = GenerateSentence(new DefaultRandom(new Random(unchecked(seedHash * 31 + index)))));
+
+        return sentences;
+    }
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the model in bytes, for comparing orders and generators
+    /// </summary>
+    public long EstimateMemoryBytes() =>
+        MemorySize.Array(symbols.Length, MemorySize.Reference) + symbols.Sum(symbol => MemorySize.String(symbol)) +
+        MemorySize.Array(stateTokens) + MemorySize.Array(starterStates) + MemorySize.Array(starterCumulativeCounts) +
+        MemorySize.Array(edgeStarts) + MemorySize.Array(edgeNextStates) + MemorySize.Array(edgeWords) + MemorySize.Array(edgeCumulativeCounts);
+
+    // The tokens of the phrase of the state, joined by spaces
+    private string Phrase(int state) => string.Join(' ', stateTokens.Skip(state * order).Take(order).Select(symbol => symbols[symbol]));
+
+    // The count at the index of cumulative counts starting at the start index
+    private static int CountAt(int[] cumulativeCounts, int start, int index) =>
+        cumulativeCounts[index] - (index > start ? cumulativeCounts[index - 1] : 0);
+
+    private static int[] CumulativeCounts(IEnumerable<int> counts)
+    {
+        var cumulativeCount = 0;
+        return counts.Select(count => cumulativeCount += count).ToArray();
+    }
+
+    // Picks a random index of the range in proportion to its count, by binary search for the first cumulative count exceeding a random value
+    private static int Sample(IRandomNumberGenerator random, int[] cumulativeCounts, int first, int end)
+    {
+        var value = random.Next(cumulativeCounts[end - 1]);
+        var index = Array.BinarySearch(cumulativeCounts, first, end - first, value + 1);
+
+        // The exact match is the first index whose cumulative count exceeds the value, otherwise the insertion point is
+        return index >= 0 ? index : ~index;
+    }
+}
@@ -0,0 +1,22 @@
+﻿using CommandLine;
+
+namespace MarkovText;
+
+/// <summary>
+/// Options of the generate verb, which generates from a model file written by the train verb without analyzing the corpus again
+/// </summary>
+[Verb("generate", HelpText = "Generate sentences from a model file written by train.")]
+public class GenerateCommandLineOptions
+{
+    [Value(0, MetaName = "model", Required = true, HelpText = "Path to the model file, e.g., model.mkv.")]
+    public required string Model { get; set; }
+
+    [Option('s', "seed", Required = false, HelpText = "Seed used to initialize random number generator.")]
+    public required string Seed { get; set; }
+
+    [Option('n', "count", Required = false, HelpText = "The number of sentences to generate.", Default = 1)]
+    public required int Count { get; set; }
+
+    [Option("separator", Required = false, HelpText = "Separates the generated sentences, e.g., \" \" to join them into a paragraph. \\n and \\t are unescaped.", Default = "\\n")]
+    public required string Separator { get; set; }
+}
@@ -0,0 +1,20 @@
+﻿namespace MarkovText;
+
+public class GenerationFailedException : Exception
+{
+    // Why each attempt was rejected, in order
+    public IReadOnlyList<string> Failures { get; }
+
+    public GenerationFailedException(IReadOnlyList<string> failures)
+        : base($"No valid sentence was generated in {failures.Count} {(failures.Count == 1 ? "attempt" : "attempts")}:\n{string.Join('\n', failures)}")
+    {
+        Failures = failures;
+    }
+
+    // For searches that give up without separate attempts, e.g., when their budget runs out
+    public GenerationFailedException(string reason)
+        : base(reason)
+    {
+        Failures = new[] { reason };
+    }
+}
@@ -0,0 +1,49 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Options that control how a single sentence is generated from the Markov model
+/// </summary>
+public record GenerationOptions
+{
+    public static readonly GenerationOptions Default = new();
+
+    // Overrides the generator's MaxWordCount safety limit when set
+    public int? MaxWordCount { get; init; }
+
+    // Truncate back to the last clause boundary instead of throwing SentenceOverflowException when the word limit is hit
+    public bool TruncateOnOverflow { get; init; }
+
+    // Blocklist of words and patterns; banned continuations are re-sampled from the remaining transitions
+    public ContentFilter? ContentFilter { get; init; }
+
+    // Don't emit words seen only once in the corpus mid-sentence, unless they started a sentence in the corpus,
+    // which reduces the chance of regurgitating uniquely identifying corpus fragments
+    public bool ProtectRareWords { get; init; }
+
+    // Randomly capitalize, lowercase, or quote the generated sentence, as often as corpus sentences begin that way
+    public bool ReproduceOpeningStyle { get; init; }
+
+    // Polish the generated sentence, e.g., with a BalancedPunctuationPostProcessor
+    public IReadOnlyList<IPostProcessor> PostProcessors { get; init; } = Array.Empty<IPostProcessor>();
+
+    // Choose starter phrases in proportion to their frequency in the corpus, or uniformly among the distinct phrases
+    public StarterSelection StarterSelection { get; init; }
+
+    // Always pick the most frequent continuation, which generates the same canonical sentence for each starter phrase
+    public bool Greedy { get; init; }
+
+    // Only consider the k most frequent continuations of each phrase, at least 1, trading diversity for coherence
+    public int? TopK { get; init; }
+
+    // Nucleus sampling: only consider the most frequent continuations whose cumulative probability reaches p, greater than 0 and at most 1
+    public double? TopP { get; init; }
+
+    // How equally frequent continuations are ordered for Greedy, TopK, and TopP
+    public TieBreaking TieBreaking { get; init; }
+
+    // Called for every transition, can veto the chosen continuation
+    public IGenerationObserver? Observer { get; init; }
+
+    // Record every choice made during generation in GenerationResult.Choices, so the sentence can be replayed later
+    public bool RecordChoices { get; init; }
+}
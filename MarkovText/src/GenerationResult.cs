@@ -0,0 +1,20 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// A generated sentence together with metadata about how it was generated
+/// </summary>
+public readonly record struct GenerationResult
+{
+    // The generated Markov text
+    public required string Text { get; init; }
+
+    // True if the word limit was hit and the sentence was cut short at a clause boundary
+    public bool Truncated { get; init; }
+
+    // True if generation was cancelled and the text is the sentence generated so far
+    public bool Cancelled { get; init; }
+
+    // The choices made during generation, if they were recorded or replayed.
+    // A truncated sentence only keeps the choices up to its clause boundary, followed by Choice.Truncation.
+    public IReadOnlyList<Choice>? Choices { get; init; }
+}
@@ -0,0 +1,132 @@
+﻿using System.Net;
+using System.Text;
+using System.Text.Json;
+using System.Web;
+
+namespace MarkovText;
+
+/// <summary>
+/// Minimal HTTP server for backing websites and bots with a trained model:
+/// GET /generate?count=N&amp;seed=S returns { "seed": "S", "sentences": [ ... ] }.
+/// The same seed and count always return the same sentences, and a random seed is used if none is given.
+/// </summary>
+public class GenerationServer
+{
+    // Upper limit of sentences per request, so a single request can't keep the server busy
+    public const int MaxCount = 100;
+
+    private readonly IGenerator generator;
+
+    // The URL prefix to listen on, e.g., "http://localhost:8080/"
+    private readonly string prefix;
+
+    public GenerationServer(IGenerator generator, string prefix)
+    {
+        this.generator = generator;
+        this.prefix = prefix;
+    }
+
+    /// <summary>
+    /// Serves requests one at a time until cancelled.
+    /// A request that fails unexpectedly is answered with 500 and doesn't stop the server.
+    /// </summary>
+    public void Run(CancellationToken cancellationToken)
+    {
+        using var listener = new HttpListener();
+        listener.Prefixes.Add(prefix);
+        listener.Start();
+
+        using var registration = cancellationToken.Register(listener.Stop);
+
+        while (!cancellationToken.IsCancellationRequested)
+        {
+            HttpListenerContext context;
+
+            try
+            {
+                context = listener.GetContext();
+            }
+            catch (Exception e) when (e is HttpListenerException or ObjectDisposedException && cancellationToken.IsCancellationRequested)
+            {
+                break;
+            }
+
+            try
+            {
+                var (statusCode, json) = Handle(context.Request.HttpMethod, context.Request.Url!.PathAndQuery);
+                Respond(context.Response, statusCode, json);
+            }
+            catch (Exception e)
+            {
+                // A failed request (or a client that disconnected) mustn't stop the server from serving the next one
+                TryRespond(context.Response, 500, Error(e.Message));
+            }
+        }
+    }
+
+    private static void Respond(HttpListenerResponse response, int statusCode, string json)
+    {
+        var body = Encoding.UTF8.GetBytes(json);
+
+        response.StatusCode = statusCode;
+        response.ContentType = "application/json; charset=utf-8";
+        response.ContentLength64 = body.Length;
+        response.OutputStream.Write(body);
+        response.Close();
+    }
+
+    // Answers with the error if the response hasn't been sent already and the client is still connected, otherwise drops the connection
+    private static void TryRespond(HttpListenerResponse response, int statusCode, string json)
+    {
+        try
+        {
+            Respond(response, statusCode, json);
+        }
+        catch (Exception e) when (e is HttpListenerException or IOException or InvalidOperationException or ObjectDisposedException)
+        {
+            response.Abort();
+        }
+    }
+
+    /// <summary>
+    /// The status code and JSON response for a request
+    /// </summary>
+    public (int StatusCode, string Json) Handle(string method, string pathAndQuery)
+    {
+        var queryStart = pathAndQuery.IndexOf('?');
+        var path = queryStart >= 0 ? pathAndQuery[..queryStart] : pathAndQuery;
+
+        if (path != "/generate")
+        {
+            return (404, Error($"Not found: {path}"));
+        }
+
+        if (method != "GET")
+        {
+            return (405, Error($"Method not allowed: {method}"));
+        }
+
+        var query = HttpUtility.ParseQueryString(queryStart >= 0 ? pathAndQuery[(queryStart + 1)..] : "");
+        var count = 1;
+
+        if (query["count"] is { } countParameter && (!int.TryParse(countParameter, out count) || count < 1 || count > MaxCount))
+        {
+            return (400, Error($"The count must be a number from 1 to {MaxCount}."));
+        }
+
+        var seed = query["seed"] ?? Guid.NewGuid().ToString()[..8];
+        var random = new DefaultRandom(new Random(seed.GetStableHashCode()));
+
+        try
+        {
+            var sentences = Enumerable.Range(0, count).Select(_ => generator.GenerateSentence(random)).ToList();
+            return (200, JsonSerializer.Serialize(new { seed, sentences }));
+        }
+        catch (Exception e) when (e is SentenceOverflowException or ContentFilterException or GenerationFailedException)
+        {
+            return (500, Error(e.Message));
+        }
+    }
+
+    private static string Error(string message) => JsonSerializer.Serialize(new { error = message });
+}
@@ -0,0 +1,30 @@
+﻿using System.Net;
+using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Converts HTML to plain text for training on scraped web pages or exported blog archives:
+/// removes comments, scripts, and styles with their content, replaces block tags with spaces, removes inline tags, and decodes entities like "&amp;amp;"
+/// </summary>
+public partial class HtmlToTextPreprocessor : IPreprocessor
+{
+    [GeneratedRegex(@"<!--.*?-->|<(script|style|noscript|template)\b[^>]*>.*?</\1\s*>", RegexOptions.IgnoreCase | RegexOptions.Singleline)]
+    private static partial Regex HiddenContentRegex();
+
+    // Block tags are replaced with a space so words in adjacent blocks aren't glued together, e.g., "<li>one</li><li>two</li>"
+    [GeneratedRegex(@"</?(p|div|br|hr|li|ul|ol|dl|dt|dd|h[1-6]|table|tr|td|th|section|article|header|footer|nav|aside|blockquote|pre|title|body|head|html)\b[^>]*>", RegexOptions.IgnoreCase)]
+    private static partial Regex BlockTagRegex();
+
+    // Inline tags are removed, e.g., "<b>tasty</b>."
+    [GeneratedRegex(@"<[^>]*>")]
+    private static partial Regex TagRegex();
+
+    public string Process(string text)
+    {
+        text = HiddenContentRegex().Replace(text, " ");
+        text = BlockTagRegex().Replace(text, " ");
+        text = TagRegex().Replace(text, "");
+        return WebUtility.HtmlDecode(text);
+    }
+}
@@ -0,0 +1,18 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Observes each transition during generation, e.g., to log or visualize the walk, or to let a user choose the next word
+/// </summary>
+public interface IGenerationObserver
+{
+    /// <summary>
+    /// Called with the continuation chosen for the current phrase.
+    /// Return false to veto it, and another continuation is chosen among the remaining ones.
+    /// </summary>
+    public bool OnTransition(TransitionStep step);
+}
+
+/// <summary>
+/// A continuation chosen for a phrase, and the number of distinct continuations the phrase has
+/// </summary>
+public readonly record struct TransitionStep(string Phrase, string Word, int CandidateCount);
@@ -0,0 +1,11 @@
+﻿namespace MarkovText;
+
+public interface ILanguageDetector
+{
+    /// <summary>
+    /// Detects the language of a sentence
+    /// </summary>
+    /// <param name="words">The words of the sentence</param>
+    /// <returns>A language code such as "en", or null if the language could not be determined</returns>
+    public string? DetectLanguage(IReadOnlyList<string> words);
+}
@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Outgoing chat messages of a ChatBot
+/// </summary>
+public interface IMessageSink
+{
+    public void Send(string channel, string text);
+}
@@ -0,0 +1,17 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Incoming chat messages for a ChatBot, e.g., from an IRC or Discord channel
+/// </summary>
+public interface IMessageSource
+{
+    /// <summary>
+    /// Blocks until the next message arrives. Returns null when the connection is closed.
+    /// </summary>
+    public ChatMessage? Receive(CancellationToken cancellationToken);
+}
+
+/// <summary>
+/// A chat message and the channel it was sent to
+/// </summary>
+public record ChatMessage(string Channel, string Author, string Text);
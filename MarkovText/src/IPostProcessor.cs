@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+public interface IPostProcessor
+{
+    /// <summary>
+    /// Polishes a generated sentence before it is returned
+    /// </summary>
+    public string Process(string sentence);
+}
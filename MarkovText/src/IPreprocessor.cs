@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+public interface IPreprocessor
+{
+    /// <summary>
+    /// Cleans up the corpus text before it is tokenized
+    /// </summary>
+    public string Process(string text);
+}
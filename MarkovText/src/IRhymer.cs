@@ -0,0 +1,12 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Decides which words rhyme, see StringBasedMarkovTextGenerator.GenerateCouplet
+/// </summary>
+public interface IRhymer
+{
+    /// <summary>
+    /// The part of the word that rhymes, e.g., "ake" of "make", words rhyme if their keys are equal. Empty if nothing rhymes with the word.
+    /// </summary>
+    public string RhymeKey(string word);
+}
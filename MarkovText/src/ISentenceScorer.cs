@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+public interface ISentenceScorer
+{
+    /// <summary>
+    /// Scores a generated sentence, higher is better
+    /// </summary>
+    public double Score(SentenceCandidate candidate);
+}
@@ -0,0 +1,12 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Counts the syllables of words for syllable-constrained poetry, see StringBasedMarkovTextGenerator.GenerateSyllableStanza
+/// </summary>
+public interface ISyllableCounter
+{
+    /// <summary>
+    /// The number of syllables of a generated token, 0 for tokens without letters like dashes
+    /// </summary>
+    public int CountSyllables(string word);
+}
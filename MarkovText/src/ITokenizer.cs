@@ -0,0 +1,29 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Splits sanitized corpus text into tokens and joins generated tokens back into text
+/// </summary>
+public interface ITokenizer
+{
+    /// <summary>
+    /// Splits the sanitized corpus into tokens, in order
+    /// </summary>
+    public IEnumerable<string> Tokenize(string text);
+
+    /// <summary>
+    /// True if the token terminates a sentence
+    /// </summary>
+    public bool IsSentenceEnd(string token);
+
+    /// <summary>
+    /// Appends a generated token to the output, inserting whitespace as needed
+    /// </summary>
+    public void Append(StringBuilder builder, string token);
+
+    /// <summary>
+    /// Appends a generated token from a buffer, e.g., decoded from a memory-mapped model, without allocating a string for it
+    /// </summary>
+    public void Append(StringBuilder builder, ReadOnlySpan<char> token) => Append(builder, token.ToString());
+}
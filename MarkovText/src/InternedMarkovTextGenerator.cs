@@ -0,0 +1,106 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Class that generates text based on the Markov chain algorithm, with the tokens interned in a SymbolTable.
+/// It is a thin text-specific wrapper of a MarkovChain over symbol IDs: phrases of any order are fixed-size arrays of symbol IDs,
+/// so every token is stored once and phrase lookups hash integers instead of strings.
+/// </summary>
+public class InternedMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // The order of the Markov chain (how many words in the "state" of the chain)
+    private int Order;
+
+    // The tokens of the corpus
+    private readonly SymbolTable Symbols = new();
+
+    // The chain of the sentences of the corpus, as sequences of symbol IDs
+    private MarkovChain<int> Chain = new(2);
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    public override string ToString() => "Interned";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        Order = order;
+
+        Symbols.Clear();
+        Chain = new MarkovChain<int>(order);
+
+        AnalyzeCorpus(corpus);  // Analyze the corpus and build the Markov model
+
+        if (Chain.SequenceCount == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        if (Chain.SequenceCount == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+        var wordCount = 0;  // Track the current word count to prevent infinite loops
+
+        foreach (var symbol in Chain.Generate(random))
+        {
+            if (++wordCount > Order && wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+            }
+
+            if (wordCount > 1)
+            {
+                stringBuilder.Append(' ');
+            }
+
+            stringBuilder.Append(Symbols[symbol]);
+        }
+
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the model in bytes, for comparing orders and generators
+    /// </summary>
+    public long EstimateMemoryBytes() => Symbols.EstimateMemoryBytes() + Chain.EstimateMemoryBytes();
+
+    private void AnalyzeCorpus(string corpus)
+    {
+        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
+        corpus = Preprocessing.Default.Apply(corpus);
+
+        var sentence = new List<int>();
+
+        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            sentence.Add(Symbols.Intern(word));
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                Chain.Add(sentence);
+                sentence.Clear();
+            }
+        }
+
+        Chain.Add(sentence);    // The corpus may end without a sentence delimiter
+    }
+}
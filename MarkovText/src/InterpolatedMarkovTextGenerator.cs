@@ -0,0 +1,517 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Class that generates text based on Markov chains of all orders from 1 to Order trained simultaneously.
+/// The next word is sampled from a linear interpolation of the orders whose context has been seen, so a high order gives the
+/// sentences coherence while the lower orders continue past its dead-ends and keep it from copying corpus sentences verbatim.
+/// Other estimates of the next word from the orders can be chosen with Smoothing.
+/// </summary>
+public class InterpolatedMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // Relative weights of the orders 1 to Order, e.g., { 0.1, 0.3, 0.6 }. Null weighs all orders equally.
+    public double[]? Weights;
+
+    // How the orders are combined to estimate the probability of the next word, for generation and Probability
+    public Smoothing Smoothing = Smoothing.Interpolation;
+
+    // The highest order of the Markov chains (how many words in the "state" of the chain)
+    private int Order;
+
+    // The first Order words of the corpus sentences
+    private ProbabilityDistribution<string> SentenceStarterPhrases = new(Array.Empty<KeyValuePair<string, int>>());
+
+    // Transitions[k - 1] maps the last k words to the distribution of the next word, e.g., "big dog" => { "was": 0.5, "ran": 0.5 }
+    private Dictionary<string, ProbabilityDistribution<string>>[] Transitions = Array.Empty<Dictionary<string, ProbabilityDistribution<string>>>();
+
+    // The frequencies of all words of the corpus, the order 0 model that Katz back-off ends with
+    private ProbabilityDistribution<string> Words = new(Array.Empty<KeyValuePair<string, int>>());
+
+    // KatzDiscounts[k - 1][r] is the Good-Turing discount of a continuation seen r times after a context of k words
+    private double[][] KatzDiscounts = Array.Empty<double[]>();
+
+    // KneserNeyContinuations[k] maps contexts of k words to the number of distinct words preceding them and each next word,
+    // e.g., "dog" => { "was": 3 } if "dog was" follows three different words. The highest order uses the counts of Transitions.
+    private Dictionary<string, ProbabilityDistribution<string>>[] KneserNeyContinuations = Array.Empty<Dictionary<string, ProbabilityDistribution<string>>>();
+
+    // KneserNeyDiscounts[k][i] is the discount of contexts of k words for counts of i, where i = 3 stands for 3 or more
+    private double[][] KneserNeyDiscounts = Array.Empty<double[]>();
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    // Weights are sampled with this resolution, since the random number generator only draws integers
+    private const int WeightResolution = 1_000_000;
+
+    // Continuations seen more often than this are reliable and not discounted by Katz back-off
+    private const int MaxDiscountedCount = 5;
+
+    // How often a backed-off word is redrawn if the higher order has seen it, before settling for a seen continuation
+    private const int MaxBackoffAttempts = 100;
+
+    public override string ToString() => "Interpolated";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        Order = order;
+
+        AnalyzeCorpus(corpus);  // Analyze the corpus and build the Markov models
+
+        if (SentenceStarterPhrases.TotalCount == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        if (SentenceStarterPhrases.TotalCount == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+
+        var words = SentenceStarterPhrases.Sample(random).Split(' ').ToList();
+        stringBuilder.Append(string.Join(' ', words));   // Write the entire sentence starter phrase
+
+        // Continue until the sentence ends or no order has seen the context
+        while (!SentenceDelimiters.Contains(words[^1][^1]) && NextWord(words, random) is { } word)
+        {
+            if (words.Count + 1 >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {words.Count + 1} reached for sentence:\n{stringBuilder}");
+            }
+
+            words.Add(word);
+
+            stringBuilder.Append(' ');
+            stringBuilder.Append(word);
+        }
+
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    /// <summary>
+    /// The probability of the word following the context, estimated with the Smoothing. Interpolation only considers the orders
+    /// that have seen their part of the context.
+    /// </summary>
+    public double Probability(IReadOnlyList<string> context, string word) => Smoothing switch
+    {
+        Smoothing.KatzBackoff => KatzProbability(context, Math.Min(Order, context.Count), word),
+        Smoothing.KneserNey => KneserNeyProbability(context, Math.Min(Order, context.Count), word),
+        _ => InterpolatedProbability(context, word),
+    };
+
+    // A random next word, or null if the sentence can't be continued
+    private string? NextWord(IReadOnlyList<string> words, IRandomNumberGenerator random) => Smoothing switch
+    {
+        Smoothing.KatzBackoff => SampleKatz(words, Order, random),
+        Smoothing.KneserNey => SampleKneserNey(words, Order, random),
+        _ => ChooseOrder(words, random) is { } order ? Transitions[order - 1][Context(words, order)].Sample(random) : null,
+    };
+
+    /// <summary>
+    /// The perplexity of the model on held-out text, i.e., how many choices per word the model is as confused as on average,
+    /// over the words after the first of each sentence, each given the words before it. Lower is better, e.g., to compare
+    /// smoothing methods. Infinite if a word has probability 0, which Kneser-Ney smoothing avoids.
+    /// </summary>
+    public double Perplexity(string text)
+    {
+        if (SentenceStarterPhrases.TotalCount == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var logProbability = 0.0;
+        var predictions = 0;
+        var sentence = new List<string>();
+
+        foreach (var word in Preprocessing.Default.Apply(text).Trim().Split(' ').Where(word => !string.IsNullOrWhiteSpace(word)))
+        {
+            if (sentence.Count > 0)
+            {
+                logProbability += Math.Log(Probability(sentence, word));
+                predictions++;
+            }
+
+            sentence.Add(word);
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                sentence.Clear();
+            }
+        }
+
+        if (predictions == 0)
+        {
+            throw new ArgumentException("The text contains no words to predict.", nameof(text));
+        }
+
+        return Math.Exp(-logProbability / predictions);
+    }
+
+    private double InterpolatedProbability(IReadOnlyList<string> context, string word)
+    {
+        var knownOrders = KnownOrders(context).ToList();
+        var totalWeight = knownOrders.Sum(Weight);
+
+        return totalWeight == 0 ? 0 : knownOrders.Sum(order => Weight(order) * Probability(Transitions[order - 1][Context(context, order)], word)) / totalWeight;
+    }
+
+    private static double Probability(ProbabilityDistribution<string> distribution, string word)
+    {
+        for (var i = 0; i < distribution.Outcomes.Count; i++)
+        {
+            if (distribution.Outcomes[i] == word)
+            {
+                return distribution.Probabilities[i];
+            }
+        }
+
+        return 0;
+    }
+
+    // The Katz back-off probability of the word following the last words of the context of the order
+    private double KatzProbability(IReadOnlyList<string> context, int order, string word)
+    {
+        if (order == 0)
+        {
+            return Probability(Words, word);
+        }
+
+        if (!Transitions[order - 1].TryGetValue(Context(context, order), out var continuations))
+        {
+            return KatzProbability(context, order - 1, word);
+        }
+
+        var count = Count(continuations, word);
+
+        return count > 0
+            ? KatzDiscount(order, count) * count / continuations.TotalCount
+            : BackoffWeight(context, order, continuations) * KatzProbability(context, order - 1, word);
+    }
+
+    // The discounted probability mass of the seen continuations of a context of the order
+    private double SeenMass(int order, ProbabilityDistribution<string> continuations) =>
+        continuations.Counts.Sum(count => KatzDiscount(order, count) * count) / continuations.TotalCount;
+
+    // Scales the lower order probabilities of the unseen continuations to the probability mass left by the seen ones
+    private double BackoffWeight(IReadOnlyList<string> context, int order, ProbabilityDistribution<string> continuations)
+    {
+        var lowerMass = continuations.Outcomes.Sum(word => KatzProbability(context, order - 1, word));
+
+        return lowerMass >= 1 ? 0 : (1 - SeenMass(order, continuations)) / (1 - lowerMass);
+    }
+
+    private double KatzDiscount(int order, int count) => count <= MaxDiscountedCount ? KatzDiscounts[order - 1][count] : 1;
+
+    // Samples the Katz back-off distribution: a seen continuation with its discounted probability, or else a word of the lower
+    // order that the context hasn't been followed by
+    private string SampleKatz(IReadOnlyList<string> context, int order, IRandomNumberGenerator random)
+    {
+        if (order == 0)
+        {
+            return Words.Sample(random);
+        }
+
+        if (!Transitions[order - 1].TryGetValue(Context(context, order), out var continuations))
+        {
+            return SampleKatz(context, order - 1, random);
+        }
+
+        var value = NextDouble(random);
+
+        for (var i = 0; i < continuations.Outcomes.Count; i++)
+        {
+            value -= KatzDiscount(order, continuations.Counts[i]) * continuations.Counts[i] / continuations.TotalCount;
+
+            if (value < 0)
+            {
+                return continuations.Outcomes[i];
+            }
+        }
+
+        for (var attempt = 0; attempt < MaxBackoffAttempts; attempt++)
+        {
+            var word = SampleKatz(context, order - 1, random);
+
+            if (Count(continuations, word) == 0)
+            {
+                return word;
+            }
+        }
+
+        return continuations.Sample(random);
+    }
+
+    // The interpolated Kneser-Ney probability of the word following the last words of the context of the order
+    private double KneserNeyProbability(IReadOnlyList<string> context, int order, string word)
+    {
+        var continuations = KneserNeyCounts(context, order);
+
+        if (continuations == null)
+        {
+            // Below the unigrams, every word of the vocabulary is equally likely, so even unseen words have a probability
+            return order == 0 ? 1.0 / Words.Outcomes.Count : KneserNeyProbability(context, order - 1, word);
+        }
+
+        var discounts = KneserNeyDiscounts[order];
+        var count = Count(continuations, word);
+        var reserved = continuations.Counts.Sum(seen => discounts[Math.Min(seen, 3)]) / continuations.TotalCount;
+        var lower = order == 0 ? 1.0 / Words.Outcomes.Count : KneserNeyProbability(context, order - 1, word);
+
+        return Math.Max(count - discounts[Math.Min(count, 3)], 0) / continuations.TotalCount + reserved * lower;
+    }
+
+    // Samples the Kneser-Ney distribution: a seen continuation with its discounted probability, or else a word of the lower order
+    private string SampleKneserNey(IReadOnlyList<string> context, int order, IRandomNumberGenerator random)
+    {
+        var continuations = KneserNeyCounts(context, order);
+
+        if (continuations != null)
+        {
+            var discounts = KneserNeyDiscounts[order];
+            var value = NextDouble(random);
+
+            for (var i = 0; i < continuations.Outcomes.Count; i++)
+            {
+                value -= Math.Max(continuations.Counts[i] - discounts[Math.Min(continuations.Counts[i], 3)], 0) / continuations.TotalCount;
+
+                if (value < 0)
+                {
+                    return continuations.Outcomes[i];
+                }
+            }
+        }
+
+        return order == 0 ? Words.Outcomes[random.Next(Words.Outcomes.Count)] : SampleKneserNey(context, order - 1, random);
+    }
+
+    // The counts Kneser-Ney smoothing uses for the last words of the context of the order, or null if the context is unseen.
+    // Contexts only seen at the start of sentences have no preceding words and fall back to their plain counts.
+    private ProbabilityDistribution<string>? KneserNeyCounts(IReadOnlyList<string> context, int order)
+    {
+        var key = Context(context, order);
+
+        if (order < Order && KneserNeyContinuations[order].TryGetValue(key, out var continuations))
+        {
+            return continuations;
+        }
+
+        return order > 0 && Transitions[order - 1].TryGetValue(key, out var transitions) ? transitions : null;
+    }
+
+    // The modified Kneser-Ney discounts for counts of 1, 2, and 3 or more, estimated from the counts of counts
+    private static double[] KneserNeyDiscountsOf(IEnumerable<ProbabilityDistribution<string>> continuations)
+    {
+        var countsOfCounts = new double[5];
+
+        foreach (var count in continuations.SelectMany(distribution => distribution.Counts).Where(count => count <= 4))
+        {
+            countsOfCounts[count]++;
+        }
+
+        if (countsOfCounts[1] == 0 || countsOfCounts[2] == 0)
+        {
+            return new[] { 0, 0.5, 0.5, 0.5 };  // Too few counts to estimate the discounts from
+        }
+
+        var y = countsOfCounts[1] / (countsOfCounts[1] + 2 * countsOfCounts[2]);
+        var discounts = new[]
+        {
+            0,
+            1 - 2 * y * countsOfCounts[2] / countsOfCounts[1],
+            2 - 3 * y * countsOfCounts[3] / countsOfCounts[2],
+            countsOfCounts[3] > 0 ? 3 - 4 * y * countsOfCounts[4] / countsOfCounts[3] : 3 - 3 * y,
+        };
+
+        // Each discount stays between 0 and its count, so no continuation loses all of its probability
+        return discounts.Select((discount, count) => Math.Clamp(discount, 0, Math.Max(count - 0.1, 0))).ToArray();
+    }
+
+    // The Good-Turing discounts r* / r of the counts up to MaxDiscountedCount, with Katz's correction for the undiscounted counts.
+    // Counts whose estimate is out of range, e.g., when no continuation is seen r + 1 times, are discounted absolutely instead.
+    private static double[] GoodTuringDiscounts(IEnumerable<ProbabilityDistribution<string>> continuations)
+    {
+        var countsOfCounts = new double[MaxDiscountedCount + 2];
+
+        foreach (var count in continuations.SelectMany(distribution => distribution.Counts).Where(count => count <= MaxDiscountedCount + 1))
+        {
+            countsOfCounts[count]++;
+        }
+
+        var discounts = Enumerable.Repeat(1.0, MaxDiscountedCount + 1).ToArray();
+
+        if (countsOfCounts[1] == 0)
+        {
+            return discounts;   // Without singletons, there is no mass to reserve for unseen continuations
+        }
+
+        var undiscounted = (MaxDiscountedCount + 1) * countsOfCounts[MaxDiscountedCount + 1] / countsOfCounts[1];
+        var absoluteDiscount = countsOfCounts[2] > 0 ? countsOfCounts[1] / (countsOfCounts[1] + 2 * countsOfCounts[2]) : 0.5;
+
+        for (var count = 1; count <= MaxDiscountedCount; count++)
+        {
+            var goodTuring = countsOfCounts[count] > 0 && countsOfCounts[count + 1] > 0
+                ? ((count + 1) * countsOfCounts[count + 1] / (count * countsOfCounts[count]) - undiscounted) / (1 - undiscounted)
+                : double.NaN;
+
+            discounts[count] = goodTuring is > 0 and < 1 ? goodTuring : (count - absoluteDiscount) / count;
+        }
+
+        return discounts;
+    }
+
+    private static int Count(ProbabilityDistribution<string> distribution, string word)
+    {
+        for (var i = 0; i < distribution.Outcomes.Count; i++)
+        {
+            if (distribution.Outcomes[i] == word)
+            {
+                return distribution.Counts[i];
+            }
+        }
+
+        return 0;
+    }
+
+    private static double NextDouble(IRandomNumberGenerator random) => (double)random.Next(WeightResolution) / WeightResolution;
+
+    // Picks one of the orders that have seen the context in proportion to its weight, or null if none has
+    private int? ChooseOrder(IReadOnlyList<string> words, IRandomNumberGenerator random)
+    {
+        var knownOrders = KnownOrders(words).ToList();
+        var value = NextDouble(random) * knownOrders.Sum(Weight);
+
+        foreach (var order in knownOrders)
+        {
+            if (value < Weight(order))
+            {
+                return order;
+            }
+
+            value -= Weight(order);
+        }
+
+        return null;
+    }
+
+    // The orders whose context, the last words, has been followed by a word in the corpus, highest first
+    private IEnumerable<int> KnownOrders(IReadOnlyList<string> words) =>
+        Enumerable.Range(1, Math.Min(Order, words.Count)).Reverse().Where(order => Transitions[order - 1].ContainsKey(Context(words, order)));
+
+    private static string Context(IReadOnlyList<string> words, int order) => string.Join(' ', words.Skip(words.Count - order));
+
+    private double Weight(int order)
+    {
+        if (Weights == null)
+        {
+            return 1;
+        }
+
+        if (Weights.Length != Order)
+        {
+            throw new InvalidOperationException($"{Weights.Length} weights are given for the orders 1 to {Order}.");
+        }
+
+        return Weights[order - 1];
+    }
+
+    private void AnalyzeCorpus(string corpus)
+    {
+        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
+        corpus = Preprocessing.Default.Apply(corpus);
+
+        // Count the occurrences first, then freeze the counts into distributions
+        var starterCounts = new Dictionary<string, int>();
+        var wordCounts = new Dictionary<string, int>();
+        var transitionCounts = Enumerable.Range(0, Order).Select(_ => new Dictionary<string, Dictionary<string, int>>()).ToArray();
+        var sentence = new List<string>();
+
+        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            // Each word follows the contexts of all orders before it in the sentence
+            for (var order = 1; order <= Math.Min(Order, sentence.Count); order++)
+            {
+                var context = Context(sentence, order);
+
+                if (!transitionCounts[order - 1].TryGetValue(context, out var counts))
+                {
+                    counts = new Dictionary<string, int>();
+                    transitionCounts[order - 1][context] = counts;
+                }
+
+                counts.Increment(word);
+            }
+
+            sentence.Add(word);
+            wordCounts.Increment(word);
+
+            if (sentence.Count == Order)
+            {
+                starterCounts.Increment(string.Join(' ', sentence));
+            }
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                sentence.Clear();
+            }
+        }
+
+        SentenceStarterPhrases = new ProbabilityDistribution<string>(starterCounts);
+        Words = new ProbabilityDistribution<string>(wordCounts);
+        Transitions = transitionCounts
+            .Select(contexts => contexts.ToDictionary(context => context.Key, context => new ProbabilityDistribution<string>(context.Value)))
+            .ToArray();
+        KatzDiscounts = Transitions.Select(contexts => GoodTuringDiscounts(contexts.Values)).ToArray();
+        BuildKneserNeyContinuations();
+    }
+
+    // Counts the distinct words preceding each context and continuation, from the transitions of the next higher order
+    private void BuildKneserNeyContinuations()
+    {
+        var continuationCounts = Enumerable.Range(0, Order).Select(_ => new Dictionary<string, Dictionary<string, int>>()).ToArray();
+
+        for (var order = 0; order < Order; order++)
+        {
+            foreach (var (context, words) in Transitions[order])
+            {
+                // The context without its first word, e.g., "the big" => "big"
+                var shorter = order == 0 ? "" : context[(context.IndexOf(' ') + 1)..];
+
+                if (!continuationCounts[order].TryGetValue(shorter, out var counts))
+                {
+                    counts = new Dictionary<string, int>();
+                    continuationCounts[order][shorter] = counts;
+                }
+
+                foreach (var word in words.Outcomes)
+                {
+                    counts.Increment(word);
+                }
+            }
+        }
+
+        KneserNeyContinuations = continuationCounts
+            .Select(contexts => contexts.ToDictionary(context => context.Key, context => new ProbabilityDistribution<string>(context.Value)))
+            .ToArray();
+
+        KneserNeyDiscounts = Enumerable.Range(0, Order + 1)
+            .Select(order => KneserNeyDiscountsOf(order < Order ? KneserNeyContinuations[order].Values : Transitions[Order - 1].Values))
+            .ToArray();
+    }
+}
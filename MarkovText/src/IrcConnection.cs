@@ -0,0 +1,99 @@
+﻿using System.Net.Sockets;
+
+namespace MarkovText;
+
+/// <summary>
+/// Reference chat adapter for IRC: registers a nickname, joins the channels once the server welcomes it,
+/// answers pings, and receives the PRIVMSG messages of the channels
+/// </summary>
+public sealed class IrcConnection : IMessageSource, IMessageSink, IDisposable
+{
+    private readonly TextReader reader;
+    private readonly TextWriter writer;
+    private readonly string[] channels;
+    private readonly IDisposable? connection;
+
+    public IrcConnection(TextReader reader, TextWriter writer, string nick, IEnumerable<string> channels, IDisposable? connection = null)
+    {
+        this.reader = reader;
+        this.writer = writer;
+        this.channels = channels.ToArray();
+        this.connection = connection;
+
+        SendLine($"NICK {nick}");
+        SendLine($"USER {nick} 0 * :{nick}");
+    }
+
+    /// <summary>
+    /// Connects to an IRC server without TLS, e.g., Connect("irc.libera.chat", 6667, "markovbot", new[] { "#markov" })
+    /// </summary>
+    public static IrcConnection Connect(string host, int port, string nick, IEnumerable<string> channels)
+    {
+        var client = new TcpClient(host, port);
+        var stream = client.GetStream();
+        return new IrcConnection(new StreamReader(stream), new StreamWriter(stream) { NewLine = "\r\n" }, nick, channels, client);
+    }
+
+    public ChatMessage? Receive(CancellationToken cancellationToken)
+    {
+        while (!cancellationToken.IsCancellationRequested && reader.ReadLine() is { } line)
+        {
+            // Lines are "[:prefix] COMMAND params [:trailing]", e.g., ":alice!alice@host PRIVMSG #markov :Hello there"
+            if (line.StartsWith(':') && !line.Contains(' '))
+            {
+                continue;   // A prefix without a command
+            }
+
+            var prefix = line.StartsWith(':') ? line[1..line.IndexOf(' ')] : "";
+            var rest = prefix.Length > 0 ? line[(prefix.Length + 2)..] : line;
+            var trailingStart = rest.IndexOf(" :", StringComparison.Ordinal);
+            var trailing = trailingStart >= 0 ? rest[(trailingStart + 2)..] : "";
+            var parameters = (trailingStart >= 0 ? rest[..trailingStart] : rest).Split(' ', StringSplitOptions.RemoveEmptyEntries);
+
+            if (parameters.Length == 0)
+            {
+                continue;
+            }
+
+            switch (parameters[0])
+            {
+                case "PING":
+                    SendLine($"PONG :{trailing}");
+                    break;
+                case "001":     // Welcome, registration is complete
+                    foreach (var channel in channels)
+                    {
+                        SendLine($"JOIN {channel}");
+                    }
+
+                    break;
+                case "PRIVMSG" when parameters.Length > 1:
+                    var author = prefix.Split('!')[0];
+
+                    // Private messages are answered privately
+                    return new ChatMessage(parameters[1].StartsWith('#') ? parameters[1] : author, author, trailing);
+            }
+        }
+
+        return null;
+    }
+
+    public void Send(string channel, string text)
+    {
+        // Messages are single lines
+        SendLine($"PRIVMSG {channel} :{text.ReplaceLineEndings(" ")}");
+    }
+
+    public void Dispose()
+    {
+        reader.Dispose();
+        writer.Dispose();
+        connection?.Dispose();
+    }
+
+    private void SendLine(string line)
+    {
+        writer.WriteLine(line);
+        writer.Flush();
+    }
+}
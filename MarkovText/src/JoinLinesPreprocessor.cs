@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Replaces line endings (e.g., poem line breaks) with a space
+/// </summary>
+public class JoinLinesPreprocessor : IPreprocessor
+{
+    public string Process(string text) => text.Replace("\n", " ").Replace("\r", "");
+}
@@ -0,0 +1,108 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Routes the sentences of a multilingual corpus into separate per-language Markov models,
+/// so generated sentences never switch language halfway through
+/// </summary>
+public class LanguageSegregatedGenerator : IGenerator
+{
+    private readonly ILanguageDetector languageDetector;
+
+    // One string-based generator per detected language
+    private readonly Dictionary<string, StringBasedMarkovTextGenerator> generators = new();
+
+    // Number of corpus sentences per language, used to pick the language of a generated sentence
+    private readonly Dictionary<string, int> sentenceCounts = new();
+
+    public LanguageSegregatedGenerator(ILanguageDetector? languageDetector = null)
+    {
+        this.languageDetector = languageDetector ?? new StopwordLanguageDetector();
+    }
+
+    public IReadOnlyCollection<string> Languages => generators.Keys;
+
+    public override string ToString() => "Language";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        generators.Clear();
+        sentenceCounts.Clear();
+
+        var sentencesByLanguage = new Dictionary<string, List<string>>();
+        string? previousLanguage = null;
+
+        foreach (var sentence in SentenceSplitter.Split(corpus))
+        {
+            // Sentences of undetermined language (e.g., too short) are assumed to continue in the language of the previous sentence
+            var language = languageDetector.DetectLanguage(sentence.Split(' ', StringSplitOptions.RemoveEmptyEntries)) ?? previousLanguage;
+
+            if (language == null)
+            {
+                continue;
+            }
+
+            sentencesByLanguage.AddToList(language, sentence);
+            previousLanguage = language;
+        }
+
+        foreach (var (language, sentences) in sentencesByLanguage)
+        {
+            var generator = new StringBasedMarkovTextGenerator();
+
+            try
+            {
+                generator.BuildMarkovModel(string.Join("\n", sentences), order);
+            }
+            catch (ArgumentException)
+            {
+                continue;   // Too little text in this language to build a model of the given order
+            }
+
+            generators[language] = generator;
+            sentenceCounts[language] = sentences.Count;
+        }
+
+        if (generators.Count == 0)
+        {
+            throw new ArgumentException($"No phrases of order {order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    /// <summary>
+    /// Generate a random Markov text in a language chosen with probability proportional to its share of the corpus
+    /// </summary>
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        if (generators.Count == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var value = random.Next(sentenceCounts.Values.Sum());
+
+        foreach (var (language, count) in sentenceCounts)
+        {
+            if (value < count)
+            {
+                return generators[language].GenerateSentence(random);
+            }
+
+            value -= count;
+        }
+
+        throw new InvalidOperationException("Random value out of range.");
+    }
+
+    /// <summary>
+    /// Generate a random Markov text in the given language
+    /// </summary>
+    public string GenerateSentence(string language, IRandomNumberGenerator random)
+    {
+        if (!generators.TryGetValue(language, out var generator))
+        {
+            throw new ArgumentException($"There is no Markov model for language '{language}'.", nameof(language));
+        }
+
+        return generator.GenerateSentence(random);
+    }
+}
@@ -0,0 +1,16 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Prefers sentences with a word count close to the target
+/// </summary>
+public class LengthScorer : ISentenceScorer
+{
+    private readonly int targetWordCount;
+
+    public LengthScorer(int targetWordCount)
+    {
+        this.targetWordCount = targetWordCount;
+    }
+
+    public double Score(SentenceCandidate candidate) => -Math.Abs(candidate.WordCount - targetWordCount);
+}
@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Prefers the most probable sentences, normalized by length so short sentences aren't always preferred
+/// </summary>
+public class LogLikelihoodScorer : ISentenceScorer
+{
+    public double Score(SentenceCandidate candidate) => candidate.LogProbability / Math.Max(1, candidate.WordCount);
+}
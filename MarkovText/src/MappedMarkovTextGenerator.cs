@@ -0,0 +1,371 @@
+﻿using System.IO.MemoryMappedFiles;
+using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Read-only Markov text generator backed by a memory-mapped model file, for models too large to load.
+/// The model is a flat buffer of integer offsets instead of owned strings, so opening is near-instant and
+/// multiple processes share the pages of the same file. Phrases are numbered states whose transitions point directly
+/// at the next state, so generation needs no dictionary lookups.
+/// Model files are written with StringBasedMarkovTextGenerator.SaveMapped.
+/// </summary>
+public sealed class MappedMarkovTextGenerator : IGenerator, IDisposable
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // Identifies mapped model files
+    private static readonly byte[] Magic = "MKVM"u8.ToArray();
+
+    // Incremented when the layout changes incompatibly
+    public const int CurrentFormatVersion = 1;
+
+    // Magic bytes, then the version, order, symbol count, state count, starter count, edge count, and tokenizer symbol as 32-bit integers
+    private const int HeaderSize = 32;
+
+    private readonly MemoryMappedFile file;
+    private readonly MemoryMappedViewAccessor view;
+
+    // The start of the mapped file, acquired while the model is open, so symbols are decoded straight from the mapped bytes
+    private unsafe byte* pointer;
+
+    // The tokenizer the model was built with, used to join the generated tokens
+    private readonly ITokenizer tokenizer;
+
+    private readonly int order;
+    private readonly int symbolCount;
+    private readonly int starterCount;
+
+    // Offsets of the sections following the header:
+    // - Symbol offsets: the start of each UTF-8 symbol in the symbol bytes, and the end of the last symbol
+    // - State tokens: the symbols of the phrase of each state
+    // - Edge starts: the first edge of each state, and the end of the last state's edges
+    // - Starters: the state and cumulative count of each starter phrase
+    // - Edges: the next state, the appended word symbol, and the cumulative count of each transition
+    // - Symbol bytes: the UTF-8 symbols
+    private readonly long symbolOffsets;
+    private readonly long stateTokens;
+    private readonly long edgeStarts;
+    private readonly long starters;
+    private readonly long edges;
+    private readonly long symbolBytes;
+
+    // Longest symbol in bytes that is decoded on the stack, longer symbols are rare and decoded into an array
+    private const int MaxStackSymbolLength = 256;
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    private MappedMarkovTextGenerator(string path)
+    {
+        file = MemoryMappedFile.CreateFromFile(path, FileMode.Open, null, 0, MemoryMappedFileAccess.Read);
+        view = file.CreateViewAccessor(0, 0, MemoryMappedFileAccess.Read);
+
+        try
+        {
+            unsafe
+            {
+                view.SafeMemoryMappedViewHandle.AcquirePointer(ref pointer);
+                pointer += view.PointerOffset;
+            }
+
+            var magic = new byte[Magic.Length];
+
+            if (view.Capacity < HeaderSize || view.ReadArray(0, magic, 0, magic.Length) != magic.Length || !magic.SequenceEqual(Magic))
+            {
+                throw new InvalidDataException($"The file {path} is not a mapped Markov model file.");
+            }
+
+            var version = view.ReadInt32(4);
+
+            if (version != CurrentFormatVersion)
+            {
+                throw new InvalidDataException($"Mapped model format version {version} is not supported, expected version {CurrentFormatVersion}.");
+            }
+
+            order = view.ReadInt32(8);
+            symbolCount = view.ReadInt32(12);
+            var stateCount = view.ReadInt32(16);
+            starterCount = view.ReadInt32(20);
+            var edgeCount = view.ReadInt32(24);
+
+            symbolOffsets = HeaderSize;
+            stateTokens = symbolOffsets + (symbolCount + 1L) * sizeof(int);
+            edgeStarts = stateTokens + (long)stateCount * order * sizeof(int);
+            starters = edgeStarts + (stateCount + 1L) * sizeof(int);
+            edges = starters + starterCount * 2L * sizeof(int);
+            symbolBytes = edges + edgeCount * 3L * sizeof(int);
+
+            if (order <= 0 || symbolCount <= 0 || stateCount < 0 || starterCount < 0 || edgeCount < 0 || symbolBytes > view.Capacity)
+            {
+                throw new InvalidDataException($"The mapped model file {path} is truncated.");
+            }
+
+            // Symbols are decoded straight from the mapped bytes without bounds checks, so every symbol must lie inside the file
+            var previousOffset = 0;
+
+            for (var i = 0; i <= symbolCount; i++)
+            {
+                var offset = view.ReadInt32(symbolOffsets + (long)i * sizeof(int));
+
+                if (offset < previousOffset || symbolBytes + offset > view.Capacity)
+                {
+                    throw new InvalidDataException($"The mapped model file {path} has a corrupt symbol table.");
+                }
+
+                previousOffset = offset;
+            }
+
+            tokenizer = Persona.CreateTokenizer(Symbol(view.ReadInt32(28)));
+        }
+        catch
+        {
+            Dispose();
+            throw;
+        }
+    }
+
+    /// <summary>
+    /// Maps a model file written with StringBasedMarkovTextGenerator.SaveMapped, rejecting files of another format version
+    /// </summary>
+    public static MappedMarkovTextGenerator Open(string path) => new(path);
+
+    public override string ToString() => "Mapped";
+
+    public void BuildMarkovModel(string corpus, int order = 2) =>
+        throw new NotSupportedException($"Mapped models are read-only. Build a {nameof(StringBasedMarkovTextGenerator)} and save it with SaveMapped instead.");
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+        var wordCount = order;  // Track the current word count to prevent infinite loops
+
+        // Choose a random starter state, in proportion to how many sentences it starts
+        var state = view.ReadInt32(starters + Sample(random, starters, 0, starterCount, 2) * 2L * sizeof(int));
+
+        for (var i = 0; i < order; i++)
+        {
+            AppendSymbol(stringBuilder, view.ReadInt32(stateTokens + ((long)state * order + i) * sizeof(int)));
+        }
+
+        // Continuously generate words based on the Markov chain, until reaching a state without transitions
+        while (true)
+        {
+            var firstEdge = view.ReadInt32(edgeStarts + (long)state * sizeof(int));
+            var lastEdge = view.ReadInt32(edgeStarts + (state + 1L) * sizeof(int));
+
+            if (firstEdge == lastEdge)
+            {
+                return stringBuilder.ToString();  // Return the generated Markov text
+            }
+
+            if (++wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+            }
+
+            var edge = edges + Sample(random, edges, firstEdge, lastEdge - firstEdge, 3) * 3L * sizeof(int);
+
+            state = view.ReadInt32(edge);
+            AppendSymbol(stringBuilder, view.ReadInt32(edge + sizeof(int)));
+        }
+    }
+
+    public void Dispose()
+    {
+        unsafe
+        {
+            if (pointer != null)
+            {
+                view.SafeMemoryMappedViewHandle.ReleasePointer();
+                pointer = null;
+            }
+        }
+
+        view.Dispose();
+        file.Dispose();
+        threadLocalStringBuilder.Dispose();
+    }
+
+    /// <summary>
+    /// Writes the model in the mapped layout
+    /// </summary>
+    public static void Write(Stream stream, string tokenizerName, ModelPatch model)
+    {
+        var symbols = new List<string>();
+        var symbolIndices = new Dictionary<string, int>();
+
+        int Intern(string symbol)
+        {
+            if (!symbolIndices.TryGetValue(symbol, out var index))
+            {
+                index = symbols.Count;
+                symbolIndices[symbol] = index;
+                symbols.Add(symbol);
+            }
+
+            return index;
+        }
+
+        var states = new List<string>();
+        var stateIndices = new Dictionary<string, int>();
+
+        int State(string phrase)
+        {
+            if (!stateIndices.TryGetValue(phrase, out var index))
+            {
+                index = states.Count;
+                stateIndices[phrase] = index;
+                states.Add(phrase);
+            }
+
+            return index;
+        }
+
+        var tokenizerSymbol = Intern(tokenizerName);
+
+        var starterStates = model.StarterPhrases.Where(starter => starter.Delta > 0).Select(starter => (State: State(starter.Phrase), Count: starter.Delta)).ToList();
+        var stateEdges = new Dictionary<int, List<(int Next, int Word, int Count)>>();
+
+        foreach (var (phrase, suffixPhrase, word, count) in model.Transitions.Where(transition => transition.Delta > 0))
+        {
+            var from = State(phrase);
+
+            if (!stateEdges.TryGetValue(from, out var outgoing))
+            {
+                outgoing = new List<(int, int, int)>();
+                stateEdges[from] = outgoing;
+            }
+
+            outgoing.Add((State(suffixPhrase), Intern(word), count));
+        }
+
+        // Tokens never contain spaces, so phrases can be split back into their tokens
+        var tokens = states.Select(phrase => phrase.Split(' ').Select(Intern).ToArray()).ToList();
+        var encoded = symbols.Select(Encoding.UTF8.GetBytes).ToList();
+
+        using var writer = new BinaryWriter(stream, Encoding.UTF8, leaveOpen: true);
+
+        writer.Write(Magic);
+        writer.Write(CurrentFormatVersion);
+        writer.Write(model.Order);
+        writer.Write(symbols.Count);
+        writer.Write(states.Count);
+        writer.Write(starterStates.Count);
+        writer.Write(stateEdges.Values.Sum(outgoing => outgoing.Count));
+        writer.Write(tokenizerSymbol);
+
+        var symbolOffset = 0;
+
+        foreach (var bytes in encoded)
+        {
+            writer.Write(symbolOffset);
+            symbolOffset += bytes.Length;
+        }
+
+        writer.Write(symbolOffset);
+
+        foreach (var phraseTokens in tokens)
+        {
+            foreach (var token in phraseTokens)
+            {
+                writer.Write(token);
+            }
+        }
+
+        var edgeStart = 0;
+
+        for (var state = 0; state < states.Count; state++)
+        {
+            writer.Write(edgeStart);
+            edgeStart += stateEdges.TryGetValue(state, out var outgoing) ? outgoing.Count : 0;
+        }
+
+        writer.Write(edgeStart);
+
+        var cumulativeCount = 0;
+
+        foreach (var (state, count) in starterStates)
+        {
+            writer.Write(state);
+            writer.Write(cumulativeCount += count);
+        }
+
+        for (var state = 0; state < states.Count; state++)
+        {
+            cumulativeCount = 0;
+
+            foreach (var (next, word, count) in stateEdges.GetValueOrDefault(state) ?? new())
+            {
+                writer.Write(next);
+                writer.Write(word);
+                writer.Write(cumulativeCount += count);
+            }
+        }
+
+        foreach (var bytes in encoded)
+        {
+            writer.Write(bytes);
+        }
+    }
+
+    // Picks a random record in proportion to its count, by binary search for the first cumulative count exceeding a random value
+    // The cumulative count is the last integer of each record
+    private int Sample(IRandomNumberGenerator random, long section, int first, int count, int recordInts)
+    {
+        long CumulativeCount(int record) => view.ReadInt32(section + ((long)record * recordInts + recordInts - 1) * sizeof(int));
+
+        var value = random.Next((int)CumulativeCount(first + count - 1));
+        var low = first;
+        var high = first + count - 1;
+
+        while (low < high)
+        {
+            var middle = (low + high) / 2;
+
+            if (CumulativeCount(middle) > value)
+            {
+                high = middle;
+            }
+            else
+            {
+                low = middle + 1;
+            }
+        }
+
+        return low;
+    }
+
+    private string Symbol(int index)
+    {
+        var start = view.ReadInt32(symbolOffsets + (long)index * sizeof(int));
+        var end = view.ReadInt32(symbolOffsets + (index + 1L) * sizeof(int));
+
+        var bytes = new byte[end - start];
+        view.ReadArray(symbolBytes + start, bytes, 0, bytes.Length);
+
+        return Encoding.UTF8.GetString(bytes);
+    }
+
+    // Appends the symbol with the tokenizer, decoding it from the mapped bytes without allocating an array or a string per token
+    private unsafe void AppendSymbol(StringBuilder stringBuilder, int index)
+    {
+        // The offsets of valid symbols were checked when opening the model, the indices come from states and edges
+        if ((uint)index >= (uint)symbolCount)
+        {
+            throw new InvalidDataException($"Symbol {index} is outside the symbol table of the mapped model.");
+        }
+
+        var start = view.ReadInt32(symbolOffsets + (long)index * sizeof(int));
+        var end = view.ReadInt32(symbolOffsets + (index + 1L) * sizeof(int));
+        var bytes = new ReadOnlySpan<byte>(pointer + symbolBytes + start, end - start);
+
+        // UTF-8 never decodes to more characters than bytes
+        Span<char> characters = bytes.Length <= MaxStackSymbolLength ? stackalloc char[bytes.Length] : new char[bytes.Length];
+        var length = Encoding.UTF8.GetChars(bytes, characters);
+
+        tokenizer.Append(stringBuilder, characters[..length]);
+    }
+}
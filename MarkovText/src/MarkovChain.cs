@@ -0,0 +1,416 @@
+﻿using System.Diagnostics.CodeAnalysis;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+
+namespace MarkovText;
+
+/// <summary>
+/// Markov chain over sequences of any item type, e.g., words, characters, MIDI notes, or log events.
+/// The states are the last Order items of a sequence, stored as arrays, and each distinct state array is stored once and shared
+/// by all transitions leading to it, so the states the chain returns can be compared by reference. The transitions of a state
+/// keep one entry per occurrence in the order they were added, so choosing one uniformly is proportional to its frequency.
+/// The text generators wrap a chain with tokenization and formatting, see StringBasedMarkovTextGenerator and InternedMarkovTextGenerator.
+/// </summary>
+public class MarkovChain<T> where T : notnull
+{
+    // The number of items in a state of the chain
+    public int Order { get; }
+
+    // States at the start of sequences are the initial states of the chain, once per sequence
+    private readonly List<T[]> StarterStates = new();
+
+    // The distinct starter states in the order they were first seen, and the number of sequences starting with them
+    private readonly List<T[]> DistinctStarterStates = new();
+    private readonly Dictionary<T[], int> StarterStateCounts;
+
+    // Maps states to the states following them, e.g., (the, big, dog) => (big, dog, was)
+    private readonly Dictionary<T[], SmallList<T[]>> Transitions;
+
+    // The shared instance of each distinct state
+    private readonly Dictionary<T[], T[]> States;
+
+    // EstimateMemoryBytes of the states and transitions, kept up to date as they are added and removed
+    private long StateMemoryBytes;
+
+    /// <summary>
+    /// Creates an empty chain of the order, comparing the items of states with the comparer, or their default equality
+    /// </summary>
+    public MarkovChain(int order, IEqualityComparer<T>? comparer = null)
+    {
+        if (order < 1)
+        {
+            throw new ArgumentException("The order must be positive.", nameof(order));
+        }
+
+        Order = order;
+        StateComparer = new ArrayEqualityComparer<T>(comparer);
+        StarterStateCounts = new Dictionary<T[], int>(StateComparer);
+        Transitions = new Dictionary<T[], SmallList<T[]>>(StateComparer);
+        States = new Dictionary<T[], T[]>(StateComparer);
+    }
+
+    /// <summary>
+    /// Compares states by their items
+    /// </summary>
+    public IEqualityComparer<T[]> StateComparer { get; }
+
+    /// <summary>
+    /// The number of sequences with at least Order items that have been added
+    /// </summary>
+    public int SequenceCount => StarterStates.Count;
+
+    /// <summary>
+    /// The starter state of each sequence, so choosing one uniformly is proportional to its frequency
+    /// </summary>
+    public IReadOnlyList<T[]> Starters => StarterStates;
+
+    /// <summary>
+    /// The distinct starter states in the order they were first seen
+    /// </summary>
+    public IReadOnlyList<T[]> DistinctStarters => DistinctStarterStates;
+
+    /// <summary>
+    /// The number of sequences starting with each distinct starter state
+    /// </summary>
+    public IReadOnlyDictionary<T[], int> StarterCounts => StarterStateCounts;
+
+    /// <summary>
+    /// The states followed by at least one more item, in the order they were first continued
+    /// </summary>
+    public IEnumerable<T[]> ContinuedStates => Transitions.Keys;
+
+    /// <summary>
+    /// Adds the transitions of a sequence to the chain. Sequences shorter than the order have no states and are skipped.
+    /// </summary>
+    public void Add(IEnumerable<T> sequence)
+    {
+        var slidingWindow = new CyclicArray<T>(Order);
+        T[]? previousState = null;
+
+        foreach (var item in sequence)
+        {
+            slidingWindow.Push(item);
+
+            if (!slidingWindow.IsFull)
+            {
+                continue;
+            }
+
+            var state = slidingWindow.LatestWindow();
+            previousState = previousState == null ? AddStarter(state) : AddTransition(previousState, state);
+        }
+    }
+
+    /// <summary>
+    /// Adds the state as the start of count more sequences, or removes it from the last -count of the sequences starting with it,
+    /// and returns the shared instance of the state
+    /// </summary>
+    public T[] AddStarter(T[] state, int count = 1)
+    {
+        if (count > 0)
+        {
+            state = Intern(state);
+        }
+        else if (States.TryGetValue(state, out var shared))
+        {
+            state = shared;
+        }
+        else
+        {
+            return state;
+        }
+
+        for (var i = 0; i < count; i++)
+        {
+            StarterStates.Add(state);
+        }
+
+        for (var i = 0; i < -count && StarterStates.LastIndexOf(state) is var index and >= 0; i++)
+        {
+            StarterStates.RemoveAt(index);
+        }
+
+        var starterCount = Math.Max(0, StarterStateCounts.GetValueOrDefault(state) + count);
+
+        if (starterCount == 0)
+        {
+            if (StarterStateCounts.Remove(state))
+            {
+                DistinctStarterStates.Remove(state);
+            }
+        }
+        else if (StarterStateCounts.TryAdd(state, starterCount))
+        {
+            DistinctStarterStates.Add(state);
+        }
+        else
+        {
+            StarterStateCounts[state] = starterCount;
+        }
+
+        return state;
+    }
+
+    /// <summary>
+    /// Adds count more occurrences of the transition from the state to the next state, or removes its last -count occurrences,
+    /// and returns the shared instance of the next state. A state without transitions left ends its sequences.
+    /// </summary>
+    public T[] AddTransition(T[] state, T[] next, int count = 1)
+    {
+        if (count <= 0)
+        {
+            return RemoveTransition(state, next, -count);
+        }
+
+        state = Intern(state);
+        next = Intern(next);
+
+        ref var transitions = ref CollectionsMarshal.GetValueRefOrAddDefault(Transitions, state, out var exists);
+
+        if (!exists)
+        {
+            StateMemoryBytes += TransitionsEntryBytes;
+        }
+
+        var overflowCapacity = transitions.OverflowCapacity;
+
+        for (var i = 0; i < count; i++)
+        {
+            transitions.Add(next);
+        }
+
+        StateMemoryBytes += OverflowBytes(transitions.OverflowCapacity) - OverflowBytes(overflowCapacity);
+        return next;
+    }
+
+    // Removes the last occurrences of the transition, and the transitions of the state if none are left
+    private T[] RemoveTransition(T[] state, T[] next, int count)
+    {
+        next = States.GetValueOrDefault(next, next);
+        ref var transitions = ref CollectionsMarshal.GetValueRefOrNullRef(Transitions, state);
+
+        if (Unsafe.IsNullRef(ref transitions))
+        {
+            return next;
+        }
+
+        var overflowCapacity = transitions.OverflowCapacity;
+
+        for (var i = 0; i < count && LastIndexOf(transitions, next) is var index and >= 0; i++)
+        {
+            transitions.RemoveAt(index);
+        }
+
+        StateMemoryBytes += OverflowBytes(transitions.OverflowCapacity) - OverflowBytes(overflowCapacity);
+
+        if (transitions.Count == 0)
+        {
+            Transitions.Remove(state);
+            StateMemoryBytes -= TransitionsEntryBytes;
+        }
+
+        return next;
+    }
+
+    private static int LastIndexOf(SmallList<T[]> transitions, T[] next)
+    {
+        for (var i = transitions.Count - 1; i >= 0; i--)
+        {
+            if (transitions[i] == next)
+            {
+                return i;
+            }
+        }
+
+        return -1;
+    }
+
+    /// <summary>
+    /// The next states of each occurrence of a transition from the state, false if the state ends its sequences
+    /// </summary>
+    public bool TryGetTransitions(T[] state, [MaybeNullWhen(false)] out IReadOnlyList<T[]> transitions)
+    {
+        if (Transitions.TryGetValue(state, out var list))
+        {
+            transitions = list;
+            return true;
+        }
+
+        transitions = null;
+        return false;
+    }
+
+    /// <summary>
+    /// The distinct next states of the transitions and their number of occurrences, most frequent first, ties in the order they
+    /// were first added
+    /// </summary>
+    public IOrderedEnumerable<(T[] State, int Count)> CountSuccessors(IReadOnlyList<T[]> transitions) => transitions
+        .GroupBy(next => next, StateComparer)
+        .Select(group => (group.Key, group.Count()))
+        .OrderByDescending(successor => successor.Item2);
+
+    /// <summary>
+    /// Removes the transitions that occur fewer than minCount times from their state, and the states without any left, calling
+    /// removed with each removed transition and its number of occurrences, e.g., to keep counts of the transitions up to date
+    /// </summary>
+    public PruneReport RemoveRareTransitions(int minCount, Action<T[], T[], int>? removed = null)
+    {
+        var report = PruneReport.None;
+
+        foreach (var state in Transitions.Keys.ToList())
+        {
+            var rare = CountSuccessors(Transitions[state]).Where(successor => successor.Count < minCount).ToList();
+
+            if (rare.Count == 0)
+            {
+                continue;
+            }
+
+            var rareStates = rare.Select(successor => successor.State).ToHashSet();
+            ref var transitions = ref CollectionsMarshal.GetValueRefOrNullRef(Transitions, state);
+            var overflowCapacity = transitions.OverflowCapacity;
+            transitions.RemoveAll(rareStates.Contains);
+            StateMemoryBytes += OverflowBytes(transitions.OverflowCapacity) - OverflowBytes(overflowCapacity);
+            report = report.Add(new PruneReport(rare.Count, rare.Sum(successor => successor.Count), 0));
+
+            if (transitions.Count == 0)
+            {
+                Transitions.Remove(state);
+                StateMemoryBytes -= TransitionsEntryBytes;
+                report = report.Add(new PruneReport(0, 0, 1));
+            }
+
+            foreach (var (next, count) in rare)
+            {
+                removed?.Invoke(state, next, count);
+            }
+        }
+
+        RemoveUnusedStates();
+        return report;
+    }
+
+    /// <summary>
+    /// Removes the transitions of the states that no sequence reaches from a starter state
+    /// </summary>
+    public PruneReport RemoveUnreachableStates()
+    {
+        var reachable = new HashSet<T[]>(DistinctStarterStates);
+        var queue = new Queue<T[]>(DistinctStarterStates);
+
+        while (queue.TryDequeue(out var state))
+        {
+            if (Transitions.TryGetValue(state, out var transitions))
+            {
+                foreach (var next in transitions)
+                {
+                    if (reachable.Add(next))
+                    {
+                        queue.Enqueue(next);
+                    }
+                }
+            }
+        }
+
+        var report = PruneReport.None;
+
+        foreach (var state in Transitions.Keys.Where(state => !reachable.Contains(state)).ToList())
+        {
+            var transitions = Transitions[state];
+            report = report.Add(new PruneReport(CountSuccessors(transitions).Count(), transitions.Count, 1));
+            Transitions.Remove(state);
+            StateMemoryBytes -= TransitionsEntryBytes + OverflowBytes(transitions.OverflowCapacity);
+        }
+
+        RemoveUnusedStates();
+        return report;
+    }
+
+    // The shared instance of the state, which becomes the shared instance if it is new
+    private T[] Intern(T[] state)
+    {
+        if (States.TryGetValue(state, out var shared))
+        {
+            return shared;
+        }
+
+        States.Add(state, state);
+        StateMemoryBytes += StateBytes(state);
+        return state;
+    }
+
+    // Forgets the shared instances of states that are neither a starter state nor part of a transition anymore
+    private void RemoveUnusedStates()
+    {
+        var used = new HashSet<T[]>(DistinctStarterStates);
+
+        foreach (var (state, transitions) in Transitions)
+        {
+            used.Add(state);
+            used.UnionWith(transitions);
+        }
+
+        foreach (var state in States.Keys.Where(state => !used.Contains(state)).ToList())
+        {
+            States.Remove(state);
+            StateMemoryBytes -= StateBytes(state);
+        }
+    }
+
+    /// <summary>
+    /// Generates a random sequence, starting with the items of a random starter state and continuing with the last item of each
+    /// following state, until a state without transitions. The sequence is generated lazily, so callers can stop it early.
+    /// </summary>
+    public IEnumerable<T> Generate(IRandomNumberGenerator random)
+    {
+        if (StarterStates.Count == 0)
+        {
+            throw new InvalidOperationException($"The chain has no states. You need to call {nameof(Add)} first.");
+        }
+
+        var state = StarterStates.Random(random);
+
+        foreach (var item in state)
+        {
+            yield return item;
+        }
+
+        while (Transitions.TryGetValue(state, out var possibleTransitions))
+        {
+            state = possibleTransitions[random.Next(possibleTransitions.Count)];
+            yield return state[^1];
+        }
+    }
+
+    public void Clear()
+    {
+        StarterStates.Clear();
+        DistinctStarterStates.Clear();
+        StarterStateCounts.Clear();
+        Transitions.Clear();
+        States.Clear();
+        StateMemoryBytes = 0;
+    }
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the chain in bytes, not counting what the items reference.
+    /// Kept up to date as the chain changes, so it is cheap to check while adding sequences.
+    /// </summary>
+    public long EstimateMemoryBytes() =>
+        StateMemoryBytes
+        + (long)(StarterStates.Capacity + DistinctStarterStates.Capacity) * MemorySize.Reference
+        + (long)StarterStateCounts.Count * (MemorySize.DictionaryEntryOverhead + MemorySize.Reference + sizeof(int));
+
+    // Estimated size of a shared state: its entry in States and its array
+    private static long StateBytes(T[] state)
+    {
+        var itemSize = RuntimeHelpers.IsReferenceOrContainsReferences<T>() ? MemorySize.Reference : Unsafe.SizeOf<T>();
+        return MemorySize.DictionaryEntryOverhead + 2 * MemorySize.Reference + MemorySize.Array(state.Length, itemSize);
+    }
+
+    // The transitions are stored in the entry, and only their overflow items in an array of their own
+    private static int TransitionsEntryBytes => MemorySize.DictionaryEntryOverhead + MemorySize.Reference + Unsafe.SizeOf<SmallList<T[]>>();
+
+    private static long OverflowBytes(int overflowCapacity) =>
+        overflowCapacity > 0 ? MemorySize.Array(overflowCapacity, MemorySize.Reference) : 0;
+}
@@ -0,0 +1,19 @@
+﻿using System.Runtime.CompilerServices;
+
+namespace MarkovText;
+
+/// <summary>
+/// Rough sizes of heap objects for estimating the memory used by models, assuming a 64-bit runtime
+/// </summary>
+internal static class MemorySize
+{
+    public const int Reference = 8;
+    public const int ObjectOverhead = 24;
+    public const int DictionaryEntryOverhead = 24;
+
+    public static long String(string value) => ObjectOverhead + 2L * value.Length;
+
+    public static long Array<T>(T[] array) where T : unmanaged => Array(array.Length, Unsafe.SizeOf<T>());
+
+    public static long Array(int length, int elementSize) => ObjectOverhead + (long)length * elementSize;
+}
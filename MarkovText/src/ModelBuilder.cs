@@ -0,0 +1,44 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Separates training from generation: the builder holds the mutable training structures, and Build finalizes them into
+/// an immutable FrozenMarkovTextGenerator with precomputed sampling tables. The frozen model is never modified, so a single
+/// instance can be shared by any number of threads generating concurrently, while the builder keeps training on new text.
+/// </summary>
+public class ModelBuilder
+{
+    private readonly StringBasedMarkovTextGenerator generator = new();
+
+    private readonly BuildOptions options;
+
+    // True once the first corpus has been added
+    private bool hasModel;
+
+    public ModelBuilder(BuildOptions? options = null)
+    {
+        this.options = options ?? new BuildOptions();
+    }
+
+    /// <summary>
+    /// Trains on the corpus with the build options, adding to the corpora added before like StringBasedMarkovTextGenerator.Learn
+    /// </summary>
+    public ModelBuilder Add(string corpus)
+    {
+        if (hasModel)
+        {
+            generator.Learn(corpus);
+        }
+        else
+        {
+            generator.BuildMarkovModel(corpus, options);
+            hasModel = true;
+        }
+
+        return this;
+    }
+
+    /// <summary>
+    /// The model trained so far, which isn't affected by adding more corpora afterwards
+    /// </summary>
+    public FrozenMarkovTextGenerator Build() => generator.Freeze();
+}
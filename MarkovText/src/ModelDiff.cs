@@ -0,0 +1,27 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// How two Markov models differ, e.g., to compare the corpora of two authors quantitatively.
+/// Created with StringBasedMarkovTextGenerator.Diff.
+/// </summary>
+/// <param name="PhrasesOnlyInThis">The phrases of this model that the other model has never seen</param>
+/// <param name="PhrasesOnlyInOther">The phrases of the other model that this model has never seen</param>
+/// <param name="TransitionsOnlyInThis">The transitions from a phrase to a word that only this model has</param>
+/// <param name="TransitionsOnlyInOther">The transitions from a phrase to a word that only the other model has</param>
+/// <param name="Shifts">The transitions of both models whose probabilities differ the most, largest shift first</param>
+public record ModelDiff(
+    IReadOnlyList<string> PhrasesOnlyInThis,
+    IReadOnlyList<string> PhrasesOnlyInOther,
+    IReadOnlyList<(string Phrase, string Word)> TransitionsOnlyInThis,
+    IReadOnlyList<(string Phrase, string Word)> TransitionsOnlyInOther,
+    IReadOnlyList<TransitionShift> Shifts);
+
+/// <summary>
+/// A transition whose probability differs between two models
+/// </summary>
+/// <param name="Probability">The probability of the word following the phrase in this model</param>
+/// <param name="OtherProbability">The probability of the word following the phrase in the other model</param>
+public record TransitionShift(string Phrase, string Word, double Probability, double OtherProbability)
+{
+    public double Shift => Probability - OtherProbability;
+}
@@ -0,0 +1,216 @@
+﻿using System.Globalization;
+
+namespace MarkovText;
+
+/// <summary>
+/// Exports the Markov model of a StringBasedMarkovTextGenerator: as patches, model files, personas, and the DOT, ARPA, CSV,
+/// and JSON formats, or frozen for generation throughput
+/// </summary>
+public static class ModelExport
+{
+    /// <summary>
+    /// Creates a patch that turns the old model into this model when applied with ApplyPatch
+    /// </summary>
+    public static ModelPatch CreatePatch(this StringBasedMarkovTextGenerator generator, StringBasedMarkovTextGenerator old)
+    {
+        if (old.Order != generator.Order)
+        {
+            throw new ArgumentException($"Cannot create a patch between models of order {old.Order} and {generator.Order}.", nameof(old));
+        }
+
+        var patch = new ModelPatch { Order = generator.Order };
+
+        var starterCounts = old.CountStarterPhrases();
+
+        foreach (var (phrase, count) in generator.CountStarterPhrases())
+        {
+            if (count != starterCounts.GetValueOrDefault(phrase))
+            {
+                patch.StarterPhrases.Add(new StarterPhraseDelta(phrase, count - starterCounts.GetValueOrDefault(phrase)));
+            }
+
+            starterCounts.Remove(phrase);
+        }
+
+        // Phrases that only start sentences in the old model
+        patch.StarterPhrases.AddRange(starterCounts.Select(starter => new StarterPhraseDelta(starter.Key, -starter.Value)));
+
+        var transitionCounts = old.CountTransitions();
+
+        foreach (var (transition, count) in generator.CountTransitions())
+        {
+            if (count != transitionCounts.GetValueOrDefault(transition))
+            {
+                var (phrase, suffixPhrase, word) = transition;
+                patch.Transitions.Add(new TransitionDelta(phrase, suffixPhrase, word, count - transitionCounts.GetValueOrDefault(transition)));
+            }
+
+            transitionCounts.Remove(transition);
+        }
+
+        // Transitions that only exist in the old model
+        patch.Transitions.AddRange(transitionCounts.Select(transition =>
+            new TransitionDelta(transition.Key.Phrase, transition.Key.SuffixPhrase, transition.Key.Word, -transition.Value)));
+
+        return patch;
+    }
+
+    // The patch that turns an empty model into this model
+    private static ModelPatch ToPatch(StringBasedMarkovTextGenerator generator) => new()
+    {
+        Order = generator.Order,
+        StarterPhrases = generator.CountStarterPhrases().Select(starter => new StarterPhraseDelta(starter.Key, starter.Value)).ToList(),
+        Transitions = generator.CountTransitions()
+            .Select(transition => new TransitionDelta(transition.Key.Phrase, transition.Key.SuffixPhrase, transition.Key.Word, transition.Value))
+            .ToList(),
+    };
+
+    /// <summary>
+    /// Bundles the model, its tokenizer and casing, and DefaultOptions into a persona
+    /// </summary>
+    public static Persona ToPersona(this StringBasedMarkovTextGenerator generator, string name, string description = "") => new()
+    {
+        Name = name,
+        Description = description,
+        Tokenizer = Persona.GetTokenizerName(generator.Tokenizer),
+        CaseNormalized = generator.CaseNormalized,
+        PreferredCasing = new Dictionary<string, string>(generator.Statistics.PreferredCasing),
+        Stopwords = generator.Stopwords.ToList(),
+        StopwordWeight = generator.StopwordWeight,
+        OpeningStyles = new Dictionary<OpeningStyle, int>(generator.Statistics.OpeningStyleCounts),
+        OpeningQuotes = generator.Statistics.OpeningQuoteCounts.ToDictionary(quote => quote.Key.ToString(), quote => quote.Value),
+        MaxWordCount = generator.DefaultOptions.MaxWordCount,
+        TruncateOnOverflow = generator.DefaultOptions.TruncateOnOverflow,
+        ProtectRareWords = generator.DefaultOptions.ProtectRareWords,
+        ReproduceOpeningStyle = generator.DefaultOptions.ReproduceOpeningStyle,
+        PostProcessors = generator.DefaultOptions.PostProcessors.Select(Persona.GetPostProcessorName).ToList(),
+        Model = ToPatch(generator),
+    };
+
+    /// <summary>
+    /// Writes the phrase transition graph in the Graphviz DOT language, with starter phrases as boxes and edges labeled with their counts
+    /// </summary>
+    public static void ExportDot(this StringBasedMarkovTextGenerator generator, TextWriter writer, DotExportOptions? options = null)
+    {
+        generator.ThrowIfNoModel();
+        options ??= new DotExportOptions();
+
+        // Quotes and backslashes are escaped in DOT strings
+        static string Quote(string text) => $"\"{text.Replace("\\", "\\\\").Replace("\"", "\\\"")}\"";
+
+        var states = generator.Chain.ContinuedStates.OrderByDescending(state => generator.GetTransitions(state).Count).AsEnumerable();
+        var starters = generator.Chain.DistinctStarters.AsEnumerable();
+
+        if (options.MaxStates != null)
+        {
+            states = states.Take(options.MaxStates.Value).ToList();
+
+            // Only the kept states are drawn
+            var keptStates = states.ToHashSet();
+            starters = starters.Where(keptStates.Contains);
+        }
+
+        writer.WriteLine("digraph markov {");
+
+        foreach (var starter in starters)
+        {
+            writer.WriteLine($"    {Quote(generator.Phrase(starter))} [shape=box];");
+        }
+
+        foreach (var state in states)
+        {
+            var successors = generator.CountSuccessors(generator.GetTransitions(state));
+
+            if (options.MaxEdgesPerState != null)
+            {
+                successors = successors.Take(options.MaxEdgesPerState.Value);
+            }
+
+            foreach (var (next, count) in successors)
+            {
+                writer.WriteLine($"    {Quote(generator.Phrase(state))} -> {Quote(generator.Phrase(next))} [label={count}];");
+            }
+        }
+
+        writer.WriteLine("}");
+    }
+
+    /// <summary>
+    /// Exports the Markov model as an n-gram language model in the ARPA format, see ArpaFormat
+    /// </summary>
+    public static void ExportArpa(this StringBasedMarkovTextGenerator generator, TextWriter writer)
+    {
+        generator.ThrowIfNoModel();
+        ArpaFormat.Write(writer, ToPatch(generator));
+    }
+
+    /// <summary>
+    /// Writes one from_phrase,to_word,count,probability row per distinct transition, for analyzing the model in spreadsheets
+    /// </summary>
+    public static void ExportCsv(this StringBasedMarkovTextGenerator generator, TextWriter writer)
+    {
+        generator.ThrowIfNoModel();
+
+        // Fields with commas, quotes, or line breaks are quoted, with quotes doubled
+        static string Escape(string field) =>
+            field.IndexOfAny(new[] { ',', '"', '\n', '\r' }) >= 0 ? $"\"{field.Replace("\"", "\"\"")}\"" : field;
+
+        writer.WriteLine("from_phrase,to_word,count,probability");
+
+        foreach (var state in generator.Chain.ContinuedStates)
+        {
+            var transitions = generator.GetTransitions(state);
+
+            foreach (var (next, count) in generator.CountSuccessors(transitions))
+            {
+                var probability = ((double)count / transitions.Count).ToString(CultureInfo.InvariantCulture);
+                writer.WriteLine($"{Escape(generator.Phrase(state))},{Escape(generator.Word(next))},{count},{probability}");
+            }
+        }
+    }
+
+    /// <summary>
+    /// Saves the Markov model in the binary ModelFile format, optionally gzip-compressed for book-length corpora
+    /// </summary>
+    public static void Save(this StringBasedMarkovTextGenerator generator, string path, bool compress = false)
+    {
+        using var stream = File.Create(path);
+        generator.Save(stream, compress);
+    }
+
+    public static void Save(this StringBasedMarkovTextGenerator generator, Stream stream, bool compress = false)
+    {
+        generator.ThrowIfNoModel();
+        ModelFile.Write(stream, Persona.GetTokenizerName(generator.Tokenizer), ToPatch(generator), compress);
+    }
+
+    /// <summary>
+    /// Saves the Markov model in the flat layout of MappedMarkovTextGenerator, to be memory-mapped read-only
+    /// </summary>
+    public static void SaveMapped(this StringBasedMarkovTextGenerator generator, string path)
+    {
+        generator.ThrowIfNoModel();
+
+        using var stream = File.Create(path);
+        MappedMarkovTextGenerator.Write(stream, Persona.GetTokenizerName(generator.Tokenizer), ToPatch(generator));
+    }
+
+    /// <summary>
+    /// Finalizes the Markov model into a read-only FrozenMarkovTextGenerator of flat arrays, optimized for generation throughput.
+    /// Later changes to this generator don't affect the frozen model.
+    /// </summary>
+    public static FrozenMarkovTextGenerator Freeze(this StringBasedMarkovTextGenerator generator)
+    {
+        generator.ThrowIfNoModel();
+        return new FrozenMarkovTextGenerator(ToPatch(generator), generator.Tokenizer) { MaxWordCount = generator.MaxWordCount };
+    }
+
+    /// <summary>
+    /// Exports the Markov model as a JSON transition table
+    /// </summary>
+    public static string ToJson(this StringBasedMarkovTextGenerator generator)
+    {
+        generator.ThrowIfNoModel();
+        return TransitionTable.FromPatch(ToPatch(generator), Persona.GetTokenizerName(generator.Tokenizer)).ToJson();
+    }
+}
@@ -0,0 +1,186 @@
+﻿using System.IO.Compression;
+using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Compact binary on-disk format of a Markov model:
+/// - Magic bytes "MKVT" and the format version
+/// - The order of the model and the tokenizer it was built with
+/// - The symbol table of distinct tokens, so each token is stored once and phrases are stored as token indices
+/// - The starter phrases and the transition table with their counts
+/// Model files can be gzip-compressed, which is detected when reading.
+/// </summary>
+public static class ModelFile
+{
+    // Identifies model files
+    private static readonly byte[] Magic = "MKVT"u8.ToArray();
+
+    // Incremented when the format changes incompatibly
+    public const ushort CurrentFormatVersion = 1;
+
+    // The first bytes of gzip streams
+    private static readonly byte[] GZipMagic = { 0x1F, 0x8B };
+
+    public static void Write(Stream stream, string tokenizer, ModelPatch model, bool compress = false)
+    {
+        if (compress)
+        {
+            using var compressed = new GZipStream(stream, CompressionLevel.SmallestSize, leaveOpen: true);
+            Write(compressed, tokenizer, model);
+            return;
+        }
+
+        using var writer = new BinaryWriter(stream, Encoding.UTF8, leaveOpen: true);
+
+        writer.Write(Magic);
+        writer.Write(CurrentFormatVersion);
+        writer.Write(model.Order);
+        writer.Write(tokenizer);
+
+        var symbols = new List<string>();
+        var symbolIndices = new Dictionary<string, int>();
+
+        // Tokens never contain spaces, so phrases can be split back into their tokens
+        int[] Intern(string phrase) => phrase.Split(' ').Select(token =>
+        {
+            if (!symbolIndices.TryGetValue(token, out var index))
+            {
+                index = symbols.Count;
+                symbolIndices[token] = index;
+                symbols.Add(token);
+            }
+
+            return index;
+        }).ToArray();
+
+        var starters = model.StarterPhrases.Select(starter => (Tokens: Intern(starter.Phrase), starter.Delta)).ToList();
+        var transitions = model.Transitions.Select(transition =>
+            (Phrase: Intern(transition.Phrase), SuffixPhrase: Intern(transition.SuffixPhrase), Word: Intern(transition.Word)[0], transition.Delta)).ToList();
+
+        writer.Write(symbols.Count);
+
+        foreach (var symbol in symbols)
+        {
+            writer.Write(symbol);
+        }
+
+        writer.Write(starters.Count);
+
+        foreach (var (tokens, count) in starters)
+        {
+            WritePhrase(writer, tokens);
+            writer.Write(count);
+        }
+
+        writer.Write(transitions.Count);
+
+        foreach (var (phrase, suffixPhrase, word, count) in transitions)
+        {
+            WritePhrase(writer, phrase);
+            WritePhrase(writer, suffixPhrase);
+            writer.Write(word);
+            writer.Write(count);
+        }
+    }
+
+    public static (string Tokenizer, ModelPatch Model) Read(Stream stream)
+    {
+        if (IsCompressed(ref stream))
+        {
+            using var decompressed = new GZipStream(stream, CompressionMode.Decompress, leaveOpen: true);
+            return Read(decompressed);
+        }
+
+        using var reader = new BinaryReader(stream, Encoding.UTF8, leaveOpen: true);
+
+        try
+        {
+            if (!reader.ReadBytes(Magic.Length).SequenceEqual(Magic))
+            {
+                throw new InvalidDataException("The file is not a Markov model file.");
+            }
+
+            var version = reader.ReadUInt16();
+
+            if (version != CurrentFormatVersion)
+            {
+                throw new InvalidDataException($"Model format version {version} is not supported, expected version {CurrentFormatVersion}.");
+            }
+
+            var order = reader.ReadInt32();
+            var tokenizer = reader.ReadString();
+
+            var symbols = new string[reader.ReadInt32()];
+
+            for (var i = 0; i < symbols.Length; i++)
+            {
+                symbols[i] = reader.ReadString();
+            }
+
+            var model = new ModelPatch { Order = order };
+            var starterCount = reader.ReadInt32();
+
+            for (var i = 0; i < starterCount; i++)
+            {
+                model.StarterPhrases.Add(new StarterPhraseDelta(ReadPhrase(reader, symbols), reader.ReadInt32()));
+            }
+
+            var transitionCount = reader.ReadInt32();
+
+            for (var i = 0; i < transitionCount; i++)
+            {
+                var phrase = ReadPhrase(reader, symbols);
+                var suffixPhrase = ReadPhrase(reader, symbols);
+                model.Transitions.Add(new TransitionDelta(phrase, suffixPhrase, symbols[reader.ReadInt32()], reader.ReadInt32()));
+            }
+
+            return (tokenizer, model);
+        }
+        catch (Exception e) when (e is EndOfStreamException or IndexOutOfRangeException)
+        {
+            throw new InvalidDataException("The model file is truncated or corrupt.", e);
+        }
+    }
+
+    // Peeks at the first bytes, copying the stream to memory first if it cannot seek back
+    private static bool IsCompressed(ref Stream stream)
+    {
+        if (!stream.CanSeek)
+        {
+            var buffered = new MemoryStream();
+            stream.CopyTo(buffered);
+            buffered.Position = 0;
+            stream = buffered;
+        }
+
+        var start = stream.Position;
+        var header = new byte[GZipMagic.Length];
+        var length = stream.ReadAtLeast(header, header.Length, throwOnEndOfStream: false);
+        stream.Position = start;
+
+        return length == header.Length && header.SequenceEqual(GZipMagic);
+    }
+
+    private static void WritePhrase(BinaryWriter writer, int[] tokens)
+    {
+        writer.Write((byte)tokens.Length);
+
+        foreach (var token in tokens)
+        {
+            writer.Write(token);
+        }
+    }
+
+    private static string ReadPhrase(BinaryReader reader, string[] symbols)
+    {
+        var tokens = new string[reader.ReadByte()];
+
+        for (var i = 0; i < tokens.Length; i++)
+        {
+            tokens[i] = symbols[reader.ReadInt32()];
+        }
+
+        return string.Join(' ', tokens);
+    }
+}
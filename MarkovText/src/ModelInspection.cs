@@ -0,0 +1,157 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Checks the invariants of the Markov model of a StringBasedMarkovTextGenerator and compares it with other models
+/// </summary>
+public static class ModelInspection
+{
+    /// <summary>
+    /// Compares this model with another model of the same order, e.g., built from the works of another author: the phrases
+    /// and transitions only one of them has, and the shared transitions whose probabilities differ by at least minShift.
+    /// </summary>
+    public static ModelDiff Diff(this StringBasedMarkovTextGenerator generator, StringBasedMarkovTextGenerator other, double minShift = 0.25)
+    {
+        if (other.Order != generator.Order)
+        {
+            throw new ArgumentException($"Cannot compare models of order {generator.Order} and {other.Order}.", nameof(other));
+        }
+
+        var phrases = Phrases(generator);
+        var otherPhrases = Phrases(other);
+        var transitions = CountWordTransitions(generator);
+        var otherTransitions = CountWordTransitions(other);
+
+        var shifts = transitions.Keys
+            .Where(otherTransitions.ContainsKey)
+            .Select(transition => new TransitionShift(transition.Phrase, transition.Word,
+                (double)transitions[transition] / generator.GetTransitions(generator.State(transition.Phrase)).Count,
+                (double)otherTransitions[transition] / other.GetTransitions(other.State(transition.Phrase)).Count))
+            .Where(shift => Math.Abs(shift.Shift) >= minShift)
+            .OrderByDescending(shift => Math.Abs(shift.Shift))
+            .ToList();
+
+        return new ModelDiff(
+            phrases.Where(phrase => !otherPhrases.Contains(phrase)).ToList(),
+            otherPhrases.Where(phrase => !phrases.Contains(phrase)).ToList(),
+            transitions.Keys.Where(transition => !otherTransitions.ContainsKey(transition)).ToList(),
+            otherTransitions.Keys.Where(transition => !transitions.ContainsKey(transition)).ToList(),
+            shifts);
+    }
+
+    // The starter phrases and the phrases before and after each transition
+    private static HashSet<string> Phrases(StringBasedMarkovTextGenerator generator)
+    {
+        var phrases = new HashSet<string>(generator.Chain.DistinctStarters.Select(generator.Phrase));
+
+        foreach (var state in generator.Chain.ContinuedStates)
+        {
+            phrases.Add(generator.Phrase(state));
+            phrases.UnionWith(generator.GetTransitions(state).Select(generator.Phrase));
+        }
+
+        return phrases;
+    }
+
+    // The number of occurrences of each word after each phrase
+    private static Dictionary<(string Phrase, string Word), int> CountWordTransitions(StringBasedMarkovTextGenerator generator)
+    {
+        var counts = new Dictionary<(string Phrase, string Word), int>();
+
+        foreach (var ((phrase, _, word), count) in generator.CountTransitions())
+        {
+            counts.Increment((phrase, word), count);
+        }
+
+        return counts;
+    }
+
+    /// <summary>
+    /// Checks the invariants of the model, e.g., after loading a model file or persona from an untrusted source: every phrase
+    /// has Order non-empty tokens, the counts are positive, and each transition leads to the phrase ending with its word,
+    /// or to the sentence ending of that phrase in models imported from ARPA.
+    /// Phrases without transitions that don't end with a sentence delimiter are reported as warnings, since they also occur
+    /// at the end of a corpus without a final delimiter or after pruning.
+    /// </summary>
+    public static ValidationReport Validate(this StringBasedMarkovTextGenerator generator)
+    {
+        var chain = generator.Chain;
+        var errors = new List<string>();
+        var warnings = new List<string>();
+
+        if (chain.SequenceCount == 0)
+        {
+            errors.Add("There are no starter phrases.");
+        }
+
+        foreach (var starter in chain.DistinctStarters)
+        {
+            var phrase = generator.Phrase(starter);
+            ValidatePhrase(generator, phrase, "Starter phrase", errors);
+
+            if (chain.StarterCounts.GetValueOrDefault(starter) <= 0)
+            {
+                errors.Add($"Starter phrase '{phrase}' has count {chain.StarterCounts.GetValueOrDefault(starter)}.");
+            }
+
+            if (!chain.TryGetTransitions(starter, out _) && !EndsSentence(generator, starter))
+            {
+                warnings.Add($"Starter phrase '{phrase}' has no transitions and does not end a sentence.");
+            }
+        }
+
+        if (chain.StarterCounts.Values.Sum() != chain.SequenceCount)
+        {
+            errors.Add($"The starter phrase counts add up to {chain.StarterCounts.Values.Sum()}, not the {chain.SequenceCount} sentences.");
+        }
+
+        var deadEnds = new HashSet<string>();
+
+        foreach (var state in chain.ContinuedStates)
+        {
+            var phrase = generator.Phrase(state);
+            var transitions = generator.GetTransitions(state);
+            ValidatePhrase(generator, phrase, "Phrase", errors);
+
+            if (transitions.Count == 0)
+            {
+                errors.Add($"Phrase '{phrase}' has an empty transition list.");
+            }
+
+            foreach (var next in transitions.Distinct())
+            {
+                var word = generator.Word(next);
+                var suffixPhrase = generator.Phrase(next);
+
+                if (string.IsNullOrWhiteSpace(word))
+                {
+                    errors.Add($"Phrase '{phrase}' has a transition to the malformed token '{word}'.");
+                }
+                else if (string.Join(' ', phrase.Split(' ').Skip(1).Append(word)) is var expected && suffixPhrase != expected && suffixPhrase != ArpaFormat.Ending(expected))
+                {
+                    errors.Add($"Phrase '{phrase}' has a transition with word '{word}' to the unrelated phrase '{suffixPhrase}'.");
+                }
+                else if (!chain.TryGetTransitions(next, out _) && !EndsSentence(generator, next) && deadEnds.Add(suffixPhrase))
+                {
+                    warnings.Add($"Phrase '{suffixPhrase}' has no transitions and does not end a sentence.");
+                }
+            }
+        }
+
+        return new ValidationReport(errors, warnings);
+    }
+
+    // Whether the state ends with a sentence delimiter, or is the sentence ending of a phrase of an ARPA model
+    private static bool EndsSentence(StringBasedMarkovTextGenerator generator, string[] state) =>
+        generator.IsEnding(state) || generator.Tokenizer.IsSentenceEnd(state[^1]);
+
+    // Reports the phrase if it doesn't consist of Order non-empty tokens
+    private static void ValidatePhrase(StringBasedMarkovTextGenerator generator, string phrase, string kind, List<string> errors)
+    {
+        var tokens = phrase.Split(' ');
+
+        if (tokens.Length != generator.Order || tokens.Any(string.IsNullOrWhiteSpace))
+        {
+            errors.Add($"{kind} '{phrase}' does not consist of {generator.Order} non-empty tokens.");
+        }
+    }
+}
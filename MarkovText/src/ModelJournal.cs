@@ -0,0 +1,44 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Append-only journal of training updates as one model patch per line, replayed on top of a base snapshot like a persona,
+/// so a continuously learning model is durable without rewriting the full model file on every update
+/// </summary>
+public class ModelJournal
+{
+    private readonly string path;
+
+    public ModelJournal(string path)
+    {
+        this.path = path;
+    }
+
+    /// <summary>
+    /// Appends a patch, e.g., the result of StringBasedMarkovTextGenerator.Learn
+    /// </summary>
+    public void Append(ModelPatch patch) => File.AppendAllText(path, patch.ToJson() + "\n");
+
+    /// <summary>
+    /// Applies the journaled patches to the generator in the order they were appended. A missing journal has no patches.
+    /// </summary>
+    public void Replay(StringBasedMarkovTextGenerator generator)
+    {
+        if (!File.Exists(path))
+        {
+            return;
+        }
+
+        foreach (var line in File.ReadLines(path))
+        {
+            if (!string.IsNullOrWhiteSpace(line))
+            {
+                generator.ApplyPatch(ModelPatch.FromJson(line));
+            }
+        }
+    }
+
+    /// <summary>
+    /// Starts a new journal, e.g., after the journaled patches have been saved to a new base snapshot
+    /// </summary>
+    public void Clear() => File.Delete(path);
+}
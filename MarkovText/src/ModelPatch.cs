@@ -0,0 +1,32 @@
+﻿using System.Text.Json;
+
+namespace MarkovText;
+
+/// <summary>
+/// The difference between two Markov models as starter phrase and transition count deltas,
+/// so a model can be synchronized by shipping a small patch instead of the full corpus
+/// </summary>
+public class ModelPatch
+{
+    // The order of both Markov models
+    public required int Order { get; init; }
+
+    public List<StarterPhraseDelta> StarterPhrases { get; init; } = new();
+
+    public List<TransitionDelta> Transitions { get; init; } = new();
+
+    public string ToJson() => JsonSerializer.Serialize(this);
+
+    public static ModelPatch FromJson(string json) =>
+        JsonSerializer.Deserialize<ModelPatch>(json) ?? throw new ArgumentException("The JSON does not contain a model patch.", nameof(json));
+}
+
+/// <summary>
+/// Change in the number of sentences starting with a phrase
+/// </summary>
+public record StarterPhraseDelta(string Phrase, int Delta);
+
+/// <summary>
+/// Change in the number of transitions from a phrase to a suffix phrase ending with the given word
+/// </summary>
+public record TransitionDelta(string Phrase, string SuffixPhrase, string Word, int Delta);
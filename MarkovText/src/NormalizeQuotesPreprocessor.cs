@@ -0,0 +1,29 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Removes quotes and apostrophes, or replaces typographic quotes with straight quotes if they should be kept
+/// </summary>
+public partial class NormalizeQuotesPreprocessor : IPreprocessor
+{
+    private readonly bool strip;
+
+    public NormalizeQuotesPreprocessor(bool strip = true)
+    {
+        this.strip = strip;
+    }
+
+    [GeneratedRegex(@"\""|\'|“|”|’")]
+    private static partial Regex QuotesRegex();
+
+    public string Process(string text)
+    {
+        if (strip)
+        {
+            return QuotesRegex().Replace(text, "");
+        }
+
+        return text.Replace('“', '"').Replace('”', '"').Replace('‘', '\'').Replace('’', '\'');
+    }
+}
@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Prefers sentences that branch often instead of copying long passages of the corpus
+/// </summary>
+public class NoveltyScorer : ISentenceScorer
+{
+    public double Score(SentenceCandidate candidate) => candidate.Novelty;
+}
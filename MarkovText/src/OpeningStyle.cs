@@ -0,0 +1,13 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// How a sentence begins, e.g., with a capitalized word or with quoted dialogue
+/// </summary>
+public enum OpeningStyle
+{
+    Capitalized,
+    Lowercase,
+    Digit,
+    Quoted,
+    Other,
+}
@@ -0,0 +1,178 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Class that generates text based on the Markov chain algorithm, specialized for the common order 2 (and order 1) case.
+/// Words are interned as integer IDs and phrases of up to two words are packed into a single 64-bit key,
+/// which avoids hashing and storing phrase strings. Higher orders fall back to the string-based model.
+/// </summary>
+public class PackedMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // The order of the Markov chain (how many words in the "state" of the chain)
+    private int Order;
+
+    // Interned words
+    private readonly SymbolTable Words = new();
+
+    // Phrases at the start of sentences are the initial states of the Markov chain, packed into 64-bit keys
+    private readonly List<ulong> SentenceStarterPhrases = new();
+
+    // Maps packed phrases to the IDs of the next word, e.g., "big dog" => "was"
+    // The suffix phrase is packed from the last word of the phrase and the next word
+    private readonly Dictionary<ulong, List<int>> PhraseTransitions = new();
+
+    // Model for orders higher than 2, whose phrases don't fit in a 64-bit key
+    private StringBasedMarkovTextGenerator? FallbackGenerator;
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    public override string ToString() => "Packed";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        Order = order;
+
+        Words.Clear();
+        SentenceStarterPhrases.Clear();
+        PhraseTransitions.Clear();
+        FallbackGenerator = null;
+
+        if (order > 2)
+        {
+            FallbackGenerator = new StringBasedMarkovTextGenerator();
+            FallbackGenerator.BuildMarkovModel(corpus, order);
+            return;
+        }
+
+        AnalyzeCorpus(corpus);  // Analyze the corpus and build the Markov model
+
+        if (SentenceStarterPhrases.Count == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        if (FallbackGenerator != null)
+        {
+            FallbackGenerator.MaxWordCount = MaxWordCount;
+            return FallbackGenerator.GenerateSentence(random);
+        }
+
+        if (SentenceStarterPhrases.Count == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+        var wordCount = Order;  // Track the current word count to prevent infinite loops
+        var phrase = SentenceStarterPhrases.Random(random); // Choose a random starter key from the available starter keys
+
+        // Write the entire sentence starter phrase
+        if (Order == 2)
+        {
+            stringBuilder.Append(Words[FirstWord(phrase)]);
+            stringBuilder.Append(' ');
+        }
+
+        stringBuilder.Append(Words[LastWord(phrase)]);
+
+        // Continuously generate words based on the Markov chain
+        while (PhraseTransitions.TryGetValue(phrase, out var possibleWords))
+        {
+            if (++wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+            }
+
+            var word = possibleWords.Random(random);
+            phrase = Pack(LastWord(phrase), word);
+
+            stringBuilder.Append(' ');
+            stringBuilder.Append(Words[word]);   // Write the last word of the phrase to the generated text
+        }
+
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the model in bytes, for comparing orders and generators
+    /// </summary>
+    public long EstimateMemoryBytes()
+    {
+        if (FallbackGenerator != null)
+        {
+            return FallbackGenerator.EstimateMemoryBytes();
+        }
+
+        var bytes = Words.EstimateMemoryBytes() + (long)SentenceStarterPhrases.Capacity * sizeof(ulong);
+
+        foreach (var words in PhraseTransitions.Values)
+        {
+            bytes += MemorySize.DictionaryEntryOverhead + sizeof(ulong) + MemorySize.Reference + MemorySize.ObjectOverhead + MemorySize.Array(words.Capacity, sizeof(int));
+        }
+
+        return bytes;
+    }
+
+    // Packs the last Order words into a key; for order 1 the key is the ID of the word
+    private ulong Pack(int previousWord, int word) => Order == 2 ? (ulong)(uint)previousWord << 32 | (uint)word : (uint)word;
+
+    private static int FirstWord(ulong phrase) => (int)(phrase >> 32);
+
+    private static int LastWord(ulong phrase) => (int)(uint)phrase;
+
+    private void AnalyzeCorpus(string corpus)
+    {
+        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
+        corpus = Preprocessing.Default.Apply(corpus);
+
+        var wordCount = 0;
+        var previousWord = -1;
+        ulong? previousPhrase = null;
+
+        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            var id = Words.Intern(word);
+
+            if (++wordCount >= Order)
+            {
+                var phrase = Pack(previousWord, id);
+
+                if (previousPhrase == null)
+                {
+                    SentenceStarterPhrases.Add(phrase);
+                }
+                else
+                {
+                    PhraseTransitions.AddToList(previousPhrase.Value, id);
+                }
+
+                previousPhrase = phrase;
+            }
+
+            previousWord = id;
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                previousPhrase = null;
+                wordCount = 0;
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+﻿using System.Text.Json;
+
+namespace MarkovText;
+
+/// <summary>
+/// A trained character voice as a single portable .persona file: the Markov model together with the tokenizer and
+/// casing it was built with, the default generation options, and descriptive metadata
+/// </summary>
+public class Persona
+{
+    // Incremented when the bundle format changes incompatibly
+    public const int CurrentFormatVersion = 1;
+
+    public int FormatVersion { get; init; } = CurrentFormatVersion;
+
+    // Metadata describing the voice, e.g., "Gabriel Conroy" and "Trained on Dubliners by James Joyce"
+    public string Name { get; init; } = "";
+    public string Description { get; init; } = "";
+
+    // The tokenizer the model was built with: "whitespace", "punctuation", or "unicode"
+    public string Tokenizer { get; init; } = "whitespace";
+
+    // True if the model was built with BuildOptions.NormalizeCase, and the preferred casing of lowercased tokens
+    public bool CaseNormalized { get; init; }
+    public Dictionary<string, string> PreferredCasing { get; init; } = new();
+
+    // See BuildOptions.Stopwords and BuildOptions.StopwordWeight
+    public List<string> Stopwords { get; init; } = new();
+    public double StopwordWeight { get; init; } = 1;
+
+    // How corpus sentences begin, see GenerationOptions.ReproduceOpeningStyle
+    public Dictionary<OpeningStyle, int> OpeningStyles { get; init; } = new();
+    public Dictionary<string, int> OpeningQuotes { get; init; } = new();
+
+    // Sampling defaults, see GenerationOptions
+    public int? MaxWordCount { get; init; }
+    public bool TruncateOnOverflow { get; init; }
+    public bool ProtectRareWords { get; init; }
+    public bool ReproduceOpeningStyle { get; init; }
+
+    // Post-processors in the order they run: "balance-punctuation" or "strip-unbalanced-punctuation"
+    public List<string> PostProcessors { get; init; } = new();
+
+    // The Markov model as a patch from an empty model
+    public required ModelPatch Model { get; init; }
+
+    public string ToJson() => JsonSerializer.Serialize(this);
+
+    public static Persona FromJson(string json)
+    {
+        var persona = JsonSerializer.Deserialize<Persona>(json) ?? throw new ArgumentException("The JSON does not contain a persona.", nameof(json));
+
+        if (persona.FormatVersion > CurrentFormatVersion)
+        {
+            throw new ArgumentException($"Persona format version {persona.FormatVersion} is newer than the supported version {CurrentFormatVersion}.", nameof(json));
+        }
+
+        return persona;
+    }
+
+    public void Save(string path) => File.WriteAllText(path, ToJson());
+
+    public static Persona Load(string path) => FromJson(File.ReadAllText(path));
+
+    public static string GetTokenizerName(ITokenizer tokenizer) => tokenizer switch
+    {
+        WhitespaceTokenizer => "whitespace",
+        PunctuationTokenizer => "punctuation",
+        UnicodeTokenizer => "unicode",
+        _ => throw new ArgumentException($"The tokenizer {tokenizer.GetType().Name} cannot be stored in a persona.", nameof(tokenizer))
+    };
+
+    public static ITokenizer CreateTokenizer(string name) => name switch
+    {
+        "whitespace" => new WhitespaceTokenizer(),
+        "punctuation" => new PunctuationTokenizer(),
+        "unicode" => new UnicodeTokenizer(),
+        _ => throw new ArgumentException($"Unknown persona tokenizer: {name}", nameof(name))
+    };
+
+    public static string GetPostProcessorName(IPostProcessor postProcessor) => postProcessor switch
+    {
+        BalancedPunctuationPostProcessor { Strip: false } => "balance-punctuation",
+        BalancedPunctuationPostProcessor { Strip: true } => "strip-unbalanced-punctuation",
+        _ => throw new ArgumentException($"The post-processor {postProcessor.GetType().Name} cannot be stored in a persona.", nameof(postProcessor))
+    };
+
+    public static IPostProcessor CreatePostProcessor(string name) => name switch
+    {
+        "balance-punctuation" => new BalancedPunctuationPostProcessor(),
+        "strip-unbalanced-punctuation" => new BalancedPunctuationPostProcessor(strip: true),
+        _ => throw new ArgumentException($"Unknown persona post-processor: {name}", nameof(name))
+    };
+
+    /// <summary>
+    /// The sampling defaults of the persona as generation options
+    /// </summary>
+    public GenerationOptions CreateGenerationOptions() => new()
+    {
+        MaxWordCount = MaxWordCount,
+        TruncateOnOverflow = TruncateOnOverflow,
+        ProtectRareWords = ProtectRareWords,
+        ReproduceOpeningStyle = ReproduceOpeningStyle,
+        PostProcessors = PostProcessors.Select(CreatePostProcessor).ToArray(),
+    };
+}
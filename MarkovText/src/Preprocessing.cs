@@ -0,0 +1,31 @@
+﻿namespace MarkovText;
+
+public static class Preprocessing
+{
+    /// <summary>
+    /// The default sanitization pipeline: removes the Project Gutenberg license boilerplate, joins lines, removes page numbers,
+    /// parentheses, quotes, and underscores used for emphasis, and collapses whitespace
+    /// </summary>
+    public static readonly IReadOnlyList<IPreprocessor> Default = new IPreprocessor[]
+    {
+        new StripGutenbergBoilerplatePreprocessor(),
+        new JoinLinesPreprocessor(),
+        new StripBracketsPreprocessor(),
+        new NormalizeQuotesPreprocessor(),
+        new StripEmphasisPreprocessor(),
+        new CollapseWhitespacePreprocessor(),
+    };
+
+    /// <summary>
+    /// Runs the text through each preprocessor of the pipeline in turn
+    /// </summary>
+    public static string Apply(this IEnumerable<IPreprocessor> pipeline, string text)
+    {
+        foreach (var preprocessor in pipeline)
+        {
+            text = preprocessor.Process(text);
+        }
+
+        return text;
+    }
+}
@@ -0,0 +1,109 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Distinct outcomes with their counts, explicit probabilities, and a cumulative distribution,
+/// so an outcome is sampled in proportion to its count with a binary search.
+/// Optionally builds an alias table (Vose's alias method) to sample in constant time instead, at the cost of two random numbers per sample.
+/// </summary>
+public class ProbabilityDistribution<T>
+{
+    private readonly T[] outcomes;
+    private readonly int[] counts;
+    private readonly double[] probabilities;
+
+    // The running total of the counts, e.g., counts { 2, 1, 3 } => { 2, 3, 6 }
+    private readonly int[] cumulativeCounts;
+
+    // Alias table: outcome i is picked with probability aliasThresholds[i] / TotalCount, and otherwise aliases[i]
+    private readonly int[]? aliasThresholds;
+    private readonly int[]? aliases;
+
+    public ProbabilityDistribution(IEnumerable<KeyValuePair<T, int>> outcomeCounts, bool aliasTable = false)
+    {
+        var pairs = outcomeCounts.ToArray();
+
+        outcomes = pairs.Select(pair => pair.Key).ToArray();
+        counts = pairs.Select(pair => pair.Value).ToArray();
+        cumulativeCounts = new int[counts.Length];
+
+        for (var i = 0; i < counts.Length; i++)
+        {
+            cumulativeCounts[i] = (i > 0 ? cumulativeCounts[i - 1] : 0) + counts[i];
+        }
+
+        TotalCount = counts.Length > 0 ? cumulativeCounts[^1] : 0;
+        probabilities = counts.Select(count => (double)count / TotalCount).ToArray();
+
+        if (aliasTable)
+        {
+            (aliasThresholds, aliases) = CreateAliasTable();
+        }
+    }
+
+    // The distinct outcomes, in the order they were first seen
+    public IReadOnlyList<T> Outcomes => outcomes;
+
+    public IReadOnlyList<int> Counts => counts;
+
+    public IReadOnlyList<double> Probabilities => probabilities;
+
+    public int TotalCount { get; }
+
+    /// <summary>
+    /// Picks the index of a random outcome with probability proportional to its count
+    /// </summary>
+    public int SampleIndex(IRandomNumberGenerator random)
+    {
+        if (aliasThresholds != null)
+        {
+            var column = random.Next(counts.Length);
+            return random.Next(TotalCount) < aliasThresholds[column] ? column : aliases![column];
+        }
+
+        var value = random.Next(TotalCount);
+
+        // The first outcome whose cumulative count exceeds the value, the cumulative counts are strictly increasing
+        var index = Array.BinarySearch(cumulativeCounts, value + 1);
+        return index >= 0 ? index : ~index;
+    }
+
+    public T Sample(IRandomNumberGenerator random) => outcomes[SampleIndex(random)];
+
+    // Scales the counts so each of the columns holds TotalCount, then fills the columns of the less frequent outcomes up with the more frequent ones.
+    // Integer arithmetic keeps the probabilities exact.
+    private (int[] Thresholds, int[] Aliases) CreateAliasTable()
+    {
+        var thresholds = new int[counts.Length];
+        var columnAliases = new int[counts.Length];
+        var scaled = counts.Select(count => (long)count * counts.Length).ToArray();
+
+        var small = new Stack<int>();
+        var large = new Stack<int>();
+
+        for (var i = 0; i < scaled.Length; i++)
+        {
+            (scaled[i] < TotalCount ? small : large).Push(i);
+        }
+
+        while (small.Count > 0 && large.Count > 0)
+        {
+            var less = small.Pop();
+            var more = large.Pop();
+
+            thresholds[less] = (int)scaled[less];
+            columnAliases[less] = more;
+
+            scaled[more] -= TotalCount - scaled[less];
+            (scaled[more] < TotalCount ? small : large).Push(more);
+        }
+
+        // The remaining columns are full
+        foreach (var full in large.Concat(small))
+        {
+            thresholds[full] = TotalCount;
+            columnAliases[full] = full;
+        }
+
+        return (thresholds, columnAliases);
+    }
+}
@@ -1,20 +1,184 @@
 ﻿using CommandLine;
 using MarkovText;
 
-Parser.Default.ParseArguments<CommandLineOptions>(args).WithParsed(options =>
+Parser.Default.ParseArguments<CommandLineOptions, TrainCommandLineOptions, GenerateCommandLineOptions, ServeCommandLineOptions>(args)
+    .WithParsed<CommandLineOptions>(Run)
+    .WithParsed<TrainCommandLineOptions>(Train)
+    .WithParsed<GenerateCommandLineOptions>(Generate)
+    .WithParsed<ServeCommandLineOptions>(Serve);
+
+static void Run(CommandLineOptions options)
+{
+    options.Seed = SeedOrRandom(options.Seed);
+
+    var corpusFiles = FindCorpusFiles(options);
+
+    if (options.Teach)
+    {
+        var corpusText = string.Join("\n", corpusFiles.Select(path => CorpusFiles.Read(path, options.CsvColumn, options.JsonField)));
+        new ChainExplainer(Console.Out).Explain(corpusText, options.Order, new DefaultRandom(new Random(options.Seed.GetStableHashCode())));
+        return;
+    }
+
+    IGenerator generator;
+
+    if (options.Persona != null)
+    {
+        generator = StringBasedMarkovTextGenerator.FromPersona(options.Persona);
+    }
+    else if (options.Interned)
+    {
+        generator = BuildInterned(options, corpusFiles);
+    }
+    else
+    {
+        var stringGenerator = BuildFromCorpus(options, corpusFiles);
+
+        if (options.SavePersona != null)
+        {
+            stringGenerator.ToPersona(options.Name, $"Trained on {string.Join(", ", corpusFiles)}").Save(options.SavePersona);
+        }
+
+        generator = stringGenerator;
+    }
+
+    if (options.Explore && generator is StringBasedMarkovTextGenerator exploredGenerator)
+    {
+        new ChainExplorer(exploredGenerator, new DefaultRandom(new Random(options.Seed.GetStableHashCode())), Console.In, Console.Out).Run();
+        return;
+    }
+
+    if (options.Stdio)
+    {
+        new StdioServer(generator, Console.In, Console.Out).Run();
+        return;
+    }
+
+    if (options.Perplexity != null && generator is StringBasedMarkovTextGenerator evaluatedGenerator)
+    {
+        Console.WriteLine($"Perplexity: {evaluatedGenerator.Perplexity(File.ReadAllText(options.Perplexity)):F2}");
+    }
+
+    WriteSentences(generator, options.Seed, options.Count, options.Separator);
+}
+
+// Analyzes the corpus once and saves the model, so the generate verb doesn't analyze it on every run
+static void Train(TrainCommandLineOptions options)
+{
+    options.Seed = SeedOrRandom(options.Seed);
+
+    var generator = BuildFromCorpus(options, FindCorpusFiles(options));
+    generator.Save(options.Output, compress: true);
+
+    Console.Error.WriteLine($"Saved the model to {options.Output}");
+}
+
+static void Generate(GenerateCommandLineOptions options)
+{
+    WriteSentences(StringBasedMarkovTextGenerator.Load(options.Model), SeedOrRandom(options.Seed), options.Count, options.Separator);
+}
+
+static void Serve(ServeCommandLineOptions options)
+{
+    var generator = options.Model.EndsWith(".persona", StringComparison.OrdinalIgnoreCase)
+        ? StringBasedMarkovTextGenerator.FromPersona(options.Model)
+        : StringBasedMarkovTextGenerator.Load(options.Model);
+
+    using var cancellation = new CancellationTokenSource();
+    Console.CancelKeyPress += (_, e) =>
+    {
+        e.Cancel = true;
+        cancellation.Cancel();
+    };
+
+    Console.WriteLine($"Serving GET /generate on port {options.Port}, press Ctrl+C to stop.");
+    new GenerationServer(generator, $"http://localhost:{options.Port}/").Run(cancellation.Token);
+}
+
+static string SeedOrRandom(string? seed) => string.IsNullOrEmpty(seed) ? Guid.NewGuid().ToString()[..8] : seed;
+
+// The corpus files matching the patterns, remote texts, and directories, which replace the default corpus
+static List<string> FindCorpusFiles(CorpusCommandLineOptions options)
+{
+    var recursiveFiles = options.Recursive != null ? CorpusFiles.FindRecursive(options.Recursive, options.Extensions) : Array.Empty<string>();
+    var replacesDefault = options.Url.Any() || options.Recursive != null;
+    var corpusPatterns = replacesDefault && options.Corpus.SequenceEqual(new[] { ArrayBasedMarkovTextGenerator.DefaultCorpusPath }) ? Array.Empty<string>() : options.Corpus;
+    return CorpusFiles.Expand(corpusPatterns).Concat(recursiveFiles).Concat(options.Url).ToList();
+}
+
+static StringBasedMarkovTextGenerator BuildFromCorpus(CorpusCommandLineOptions options, List<string> corpusFiles)
+{
+    if (corpusFiles.Count == 0)
+    {
+        throw new FileNotFoundException($"No corpus files match {string.Join(", ", options.Corpus)}");
+    }
+
+    var generator = new StringBasedMarkovTextGenerator();
+    var built = false;
+
+    // The model is built from the first file with phrases of the order and the other files are learned incrementally
+    for (var i = 0; i < corpusFiles.Count; i++)
+    {
+        var path = corpusFiles[i];
+        var text = ReadCorpusFile(options, path);
+
+        // Building starts from an empty model, also after a file without phrases
+        var tokenCount = built ? generator.TokenCount : 0;
+
+        if (built)
+        {
+            generator.Learn(text);
+        }
+        else
+        {
+            try
+            {
+                generator.BuildMarkovModel(text, options.Order);
+                built = true;
+            }
+            catch (ArgumentException) when (i < corpusFiles.Count - 1)
+            {
+                // The file is too short for the order, so the model is built from the next one
+            }
+        }
+
+        if (corpusFiles.Count > 1)
+        {
+            Console.Error.WriteLine($"[{i + 1}/{corpusFiles.Count}] {path}: {(built ? generator.TokenCount - tokenCount : 0)} tokens");
+        }
+    }
+
+    return generator;
+}
+
+// The interned models can't learn incrementally, so they are built from all corpus files at once.
+// The packed model is the interned model specialized for the common order 2.
+static IGenerator BuildInterned(CorpusCommandLineOptions options, List<string> corpusFiles)
 {
-    if (string.IsNullOrEmpty(options.Seed))
+    if (corpusFiles.Count == 0)
     {
-        options.Seed = Guid.NewGuid().ToString()[..8];
+        throw new FileNotFoundException($"No corpus files match {string.Join(", ", options.Corpus)}");
     }
 
-    var corpus = string.Join("\n", options.Corpus.Select(File.ReadAllText));
-    IGenerator generator = new StringBasedMarkovTextGenerator();
-    generator.BuildMarkovModel(corpus, options.Order);
+    IGenerator generator = options.Order == 2 ? new PackedMarkovTextGenerator() : new InternedMarkovTextGenerator();
+    generator.BuildMarkovModel(string.Join("\n", corpusFiles.Select(path => ReadCorpusFile(options, path))), options.Order);
+    return generator;
+}
 
+static string ReadCorpusFile(CorpusCommandLineOptions options, string path)
+{
+    var text = CorpusFiles.Read(path, options.CsvColumn, options.JsonField);
+    return options.Sample < 1 ? CorpusSampler.Sample(text, options.Sample, options.Seed) : text;
+}
+
+static void WriteSentences(IGenerator generator, string seed, int count, string separator)
+{
     Console.WriteLine();
-    Console.WriteLine(generator.GenerateSentence(options.Seed));
+    // The first sentence is the same as when generating a single sentence with the seed
+    var random = new Random(seed.GetStableHashCode());
+    var sentences = Enumerable.Range(0, Math.Max(count, 1)).Select(_ => generator.GenerateSentence(random));
+    Console.WriteLine(string.Join(separator.Replace("\\n", "\n").Replace("\\t", "\t"), sentences));
     Console.WriteLine();
     Console.WriteLine();
-    Console.WriteLine($"Seed: {options.Seed}");
-});
+    Console.WriteLine($"Seed: {seed}");
+}
@@ -0,0 +1,15 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// What was removed from a model by pruning
+/// </summary>
+/// <param name="TransitionsRemoved">The distinct transitions removed</param>
+/// <param name="OccurrencesRemoved">The corpus occurrences of the removed transitions</param>
+/// <param name="PhrasesRemoved">The phrases removed, which have no transitions left or can no longer be reached</param>
+public record PruneReport(int TransitionsRemoved, int OccurrencesRemoved, int PhrasesRemoved)
+{
+    public static readonly PruneReport None = new(0, 0, 0);
+
+    public PruneReport Add(PruneReport other) =>
+        new(TransitionsRemoved + other.TransitionsRemoved, OccurrencesRemoved + other.OccurrencesRemoved, PhrasesRemoved + other.PhrasesRemoved);
+}
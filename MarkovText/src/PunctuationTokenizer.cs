@@ -0,0 +1,87 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Splits leading and trailing punctuation into separate tokens, e.g., "dog." becomes "dog" and ".",
+/// so the model doesn't fragment into separate states for "dog" and "dog."
+/// The punctuation is re-attached to the preceding or following word when the output is joined.
+/// </summary>
+public class PunctuationTokenizer : ITokenizer
+{
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    // Punctuation that attaches to the preceding word, e.g., "dog ." => "dog."
+    private static readonly char[] ClosingPunctuation = { '.', ',', ';', ':', '?', '!', ')', ']', '}', '”', '’', '…' };
+
+    // Punctuation that attaches to the following word, e.g., "( dog" => "(dog"
+    private static readonly char[] OpeningPunctuation = { '(', '[', '{', '“', '‘', '¿', '¡' };
+
+    // Closing quotes and brackets that can follow the sentence delimiter in the same punctuation run, e.g., ." or ?)
+    private static readonly char[] ClosingQuotesAndBrackets = { '"', '\'', ')', ']', '}', '”', '’', '»' };
+
+    public IEnumerable<string> Tokenize(string text)
+    {
+        foreach (var word in text.Trim().Split(' '))
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            var start = 0;
+            var end = word.Length;
+
+            while (start < end && char.IsPunctuation(word[start]))
+            {
+                start++;
+            }
+
+            while (end > start && char.IsPunctuation(word[end - 1]))
+            {
+                end--;
+            }
+
+            // Runs of punctuation like "?!" or "..." are kept together as a single token
+            if (start > 0)
+            {
+                yield return word[..start];
+            }
+
+            if (end > start)
+            {
+                yield return word[start..end];
+            }
+
+            if (end < word.Length)
+            {
+                yield return word[end..];
+            }
+        }
+    }
+
+    public bool IsSentenceEnd(string token)
+    {
+        var end = token.Length;
+
+        while (end > 1 && ClosingQuotesAndBrackets.Contains(token[end - 1]))
+        {
+            end--;
+        }
+
+        return SentenceDelimiters.Contains(token[end - 1]);
+    }
+
+    public void Append(StringBuilder builder, string token) => Append(builder, token.AsSpan());
+
+    public void Append(StringBuilder builder, ReadOnlySpan<char> token)
+    {
+        if (builder.Length > 0 && !ClosingPunctuation.Contains(token[0]) && !OpeningPunctuation.Contains(builder[^1]))
+        {
+            builder.Append(' ');
+        }
+
+        builder.Append(token);
+    }
+}
@@ -0,0 +1,22 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Replaces all matches of a custom regular expression
+/// </summary>
+public class RegexPreprocessor : IPreprocessor
+{
+    private readonly Regex regex;
+    private readonly string replacement;
+
+    public RegexPreprocessor(Regex regex, string replacement = "")
+    {
+        this.regex = regex;
+        this.replacement = replacement;
+    }
+
+    public RegexPreprocessor(string pattern, string replacement = "") : this(new Regex(pattern), replacement) { }
+
+    public string Process(string text) => regex.Replace(text, replacement);
+}
@@ -0,0 +1,19 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Constraints a generated sentence must satisfy, see StringBasedMarkovTextGenerator.GenerateWithRetries
+/// </summary>
+public class RetryOptions
+{
+    // How many sentences are generated before giving up
+    public int MaxAttempts { get; init; } = 10;
+
+    // Sentences with fewer words are rejected
+    public int MinWordCount { get; init; }
+
+    // Sentences without this word are rejected, ignoring case and punctuation
+    public string? RequiredWord { get; init; }
+
+    // Sentences that reproduce a corpus sentence word for word are rejected
+    public bool RejectVerbatimCopies { get; init; } = true;
+}
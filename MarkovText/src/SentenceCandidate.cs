@@ -0,0 +1,10 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// A generated sentence with the statistics needed to rank it, see StringBasedMarkovTextGenerator.GenerateBest
+/// </summary>
+/// <param name="Result">The generated sentence</param>
+/// <param name="WordCount">The number of words in the sentence</param>
+/// <param name="LogProbability">The natural logarithm of the probability of generating the sentence</param>
+/// <param name="Novelty">The fraction of steps where the chain could have chosen a different word, 0 for a verbatim corpus sentence</param>
+public record SentenceCandidate(GenerationResult Result, int WordCount, double LogProbability, double Novelty);
@@ -0,0 +1,49 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Formatting of the generated sentences of StringBasedMarkovTextGenerator
+/// </summary>
+internal static class SentenceFormatting
+{
+    // Clause delimiters where an overlong sentence can be cut short, see GenerationOptions.TruncateOnOverflow
+    public static readonly char[] SoftBoundaries = { ',', ';' };
+
+    public static void CapitalizeFirstLetter(StringBuilder stringBuilder)
+    {
+        for (var i = 0; i < stringBuilder.Length; i++)
+        {
+            if (char.IsLetter(stringBuilder[i]))
+            {
+                stringBuilder[i] = char.ToUpperInvariant(stringBuilder[i]);
+                break;
+            }
+        }
+    }
+
+    // Cuts the text back to the last comma or semicolon (if any) after the start and terminates it with a full stop.
+    // Returns the length the text was cut back to, before terminating it.
+    public static int TruncateAtClauseBoundary(StringBuilder stringBuilder, int start = 0)
+    {
+        for (var i = stringBuilder.Length - 1; i > start; i--)
+        {
+            if (SoftBoundaries.Contains(stringBuilder[i]))
+            {
+                stringBuilder.Length = i;
+                break;
+            }
+        }
+
+        var cut = stringBuilder.Length;
+
+        // Drop any trailing punctuation before adding the final sentence delimiter
+        while (stringBuilder.Length > 0 && char.IsPunctuation(stringBuilder[^1]))
+        {
+            stringBuilder.Length--;
+        }
+
+        stringBuilder.Append('.');
+        return cut;
+    }
+}
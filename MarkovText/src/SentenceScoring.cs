@@ -0,0 +1,360 @@
+﻿using System.Runtime.ExceptionServices;
+
+namespace MarkovText;
+
+/// <summary>
+/// Scores sentences with the probabilities of a StringBasedMarkovTextGenerator, and generates the best or most probable sentences
+/// </summary>
+public static class SentenceScoring
+{
+    /// <summary>
+    /// The log-probability of generating the words of the given sentence from the model, ignoring stopword weights.
+    /// Negative infinity if the sentence doesn't start with a starter phrase or contains a transition the model has never seen,
+    /// unless the model was built with BuildOptions.AdditiveSmoothing.
+    /// </summary>
+    public static double ScoreSentence(this StringBasedMarkovTextGenerator generator, string sentence)
+    {
+        generator.ThrowIfNoModel();
+        return LogProbability(generator, generator.NormalizeTokens(generator.Tokenizer.Tokenize(sentence).ToArray()), out _);
+    }
+
+    /// <summary>
+    /// The perplexity of the model on held-out text, i.e., how many choices per word the model is as confused as on average.
+    /// Lower is better, e.g., to compare orders or preprocessing options. The text is sanitized with the given preprocessors,
+    /// or the default pipeline. Infinite if the text contains a transition the model has never seen, unless the model was built
+    /// with BuildOptions.AdditiveSmoothing.
+    /// </summary>
+    public static double Perplexity(this StringBasedMarkovTextGenerator generator, string corpus, IEnumerable<IPreprocessor>? preprocessors = null)
+    {
+        generator.ThrowIfNoModel();
+
+        var logProbability = 0.0;
+        var predictions = 0;
+
+        foreach (var sentence in CorpusSplitting.SplitSentences((preprocessors ?? Preprocessing.Default).Apply(corpus), generator.Tokenizer))
+        {
+            // Sentences shorter than a phrase are skipped, like when the model is built
+            if (sentence.Length < generator.Order)
+            {
+                continue;
+            }
+
+            logProbability += LogProbability(generator, generator.NormalizeTokens(sentence), out var sentencePredictions);
+            predictions += sentencePredictions;
+        }
+
+        if (predictions == 0)
+        {
+            throw new ArgumentException($"The text contains no phrases of order {generator.Order}.", nameof(corpus));
+        }
+
+        return Math.Exp(-logProbability / predictions);
+    }
+
+    // Trains a model on the first sentences of the corpus and evaluates it on the rest, see StringBasedMarkovTextGenerator.EvaluateSplit
+    internal static SplitEvaluation EvaluateSplit(string corpus, double trainFraction, BuildOptions options)
+    {
+        if (trainFraction is <= 0 or >= 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(trainFraction), trainFraction, "The training fraction must be between 0 and 1.");
+        }
+
+        var sentences = CorpusSplitting.SplitCorpus(corpus, options).ToList();
+        var trainingCount = (int)Math.Round(sentences.Count * trainFraction);
+        var heldOut = sentences.Skip(trainingCount).ToList();
+
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.Reset(options);
+        generator.AnalyzeSentences(sentences.Take(trainingCount), options, CancellationToken.None);
+        generator.Statistics.UpdatePreferredCasing();
+
+        if (generator.Chain.SequenceCount == 0)
+        {
+            throw new ArgumentException($"No phrases of order {options.Order} could be generated from the training sentences.", nameof(corpus));
+        }
+
+        var logProbability = 0.0;
+        var predictions = 0;
+        var seen = 0;
+
+        // Sentences shorter than a phrase are skipped, like when the model is built
+        foreach (var sentence in heldOut.Where(sentence => sentence.Length >= options.Order).Select(generator.NormalizeTokens))
+        {
+            logProbability += LogProbability(generator, sentence, out _);
+            predictions += sentence.Length - options.Order + 1;
+            seen += CountSeenPredictions(generator, sentence);
+        }
+
+        if (predictions == 0)
+        {
+            throw new ArgumentException($"The held-out sentences contain no phrases of order {options.Order}.", nameof(corpus));
+        }
+
+        return new SplitEvaluation(options.Order, trainingCount, heldOut.Count, Math.Exp(-logProbability / predictions), (double)seen / predictions);
+    }
+
+    // The number of the starter phrase and following words of the tokens that the model has seen
+    private static int CountSeenPredictions(StringBasedMarkovTextGenerator generator, string[] tokens)
+    {
+        var order = generator.Order;
+        var state = tokens[..order];
+        var seen = generator.Chain.StarterCounts.ContainsKey(state) ? 1 : 0;
+
+        for (var i = order; i < tokens.Length; i++)
+        {
+            var next = tokens[(i - order + 1)..(i + 1)];
+
+            if (generator.GetTransitions(state).Contains(next, generator.Chain.StateComparer))
+            {
+                seen++;
+            }
+
+            state = next;
+        }
+
+        return seen;
+    }
+
+    // The log-probability of the starter phrase and each following word, predictions is the number of words predicted
+    private static double LogProbability(StringBasedMarkovTextGenerator generator, string[] tokens, out int predictions)
+    {
+        var order = generator.Order;
+        predictions = 0;
+
+        if (tokens.Length < order)
+        {
+            return double.NegativeInfinity;
+        }
+
+        var state = tokens[..order];
+        var logProbability = Math.Log(StarterProbability(generator, state));
+        predictions++;
+
+        for (var i = order; i < tokens.Length; i++)
+        {
+            var next = tokens[(i - order + 1)..(i + 1)];
+
+            if (generator.AdditiveSmoothing > 0)
+            {
+                logProbability += Math.Log(SmoothedProbability(generator, state, next));
+                predictions++;
+                state = next;
+                continue;
+            }
+
+            if (!generator.Chain.TryGetTransitions(state, out var transitions))
+            {
+                return double.NegativeInfinity;    // The model ends the sentence here
+            }
+
+            logProbability += Math.Log(Probability(generator, transitions, next));
+            predictions++;
+            state = next;
+        }
+
+        return logProbability;
+    }
+
+    /// <summary>
+    /// Generates a number of candidate sentences and returns the one with the highest score.
+    /// Candidates that exceed the word limit or are rejected by the generation options are skipped.
+    /// With a time budget, e.g., 50 ms for a game frame, stops early and returns the best candidate found so far.
+    /// If the budget runs out during the first candidate, its partial sentence is returned with GenerationResult.Cancelled set.
+    /// </summary>
+    public static GenerationResult GenerateBest(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, int candidates, ISentenceScorer scorer,
+        GenerationOptions? options = null, TimeSpan? timeBudget = null)
+    {
+        if (candidates < 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(candidates), candidates, "At least one candidate must be generated.");
+        }
+
+        // The choices are needed to compute the probability and novelty of the candidates
+        var recordingOptions = (options ?? generator.DefaultOptions) with { RecordChoices = true };
+
+        using var deadline = timeBudget is { } budget ? new CancellationTokenSource(budget) : null;
+        var cancellationToken = deadline?.Token ?? CancellationToken.None;
+
+        SentenceCandidate? best = null;
+        var bestScore = double.NegativeInfinity;
+        Exception? lastError = null;
+
+        for (var i = 0; i < candidates; i++)
+        {
+            if (best != null && cancellationToken.IsCancellationRequested)
+            {
+                break;  // The time budget is spent
+            }
+
+            GenerationResult result;
+
+            try
+            {
+                result = generator.Generate(random, recordingOptions, cancellationToken);
+            }
+            catch (Exception e) when (e is SentenceOverflowException or ContentFilterException)
+            {
+                lastError = e;
+                continue;
+            }
+
+            // Partial sentences are only returned if no candidate could be completed in time
+            if (result.Cancelled && best != null)
+            {
+                break;
+            }
+
+            var candidate = CreateCandidate(generator, result, recordingOptions.StarterSelection);
+            var score = scorer.Score(candidate);
+
+            if (best == null || score > bestScore)
+            {
+                best = candidate;
+                bestScore = score;
+            }
+        }
+
+        if (best == null)
+        {
+            // Every candidate failed, since at least one is generated and the time budget only ends the search after a candidate.
+            // The last failure is rethrown with its original stack trace.
+            ExceptionDispatchInfo.Capture(lastError!).Throw();
+        }
+
+        return best.Result;
+    }
+
+    // Replays the choices of the result from its starter phrase to compute its probability and how often it could branch
+    private static SentenceCandidate CreateCandidate(StringBasedMarkovTextGenerator generator, GenerationResult result, StarterSelection starterSelection)
+    {
+        var starters = starterSelection == StarterSelection.Uniform ? generator.Chain.DistinctStarters : generator.Chain.Starters;
+
+        var state = Array.Empty<string>();
+        var logProbability = 0.0;
+        var steps = 0;
+        var branchingSteps = 0;
+
+        foreach (var (phrase, index) in result.Choices!.Where(choice => choice != Choice.Truncation))
+        {
+            if (phrase == null)
+            {
+                state = starters[index];
+                logProbability += Math.Log(StarterProbability(generator, state));
+                continue;
+            }
+
+            var transitions = generator.GetTransitions(state);
+            state = transitions[index];
+            logProbability += Math.Log(Probability(generator, transitions, state));
+
+            steps++;
+
+            if (transitions.Distinct().Skip(1).Any())
+            {
+                branchingSteps++;
+            }
+        }
+
+        var wordCount = result.Text.Split(' ', StringSplitOptions.RemoveEmptyEntries).Length;
+
+        return new SentenceCandidate(result, wordCount, logProbability, steps == 0 ? 0 : (double)branchingSteps / steps);
+    }
+
+    // The probability of starting a sentence with the phrase under the model. With additive smoothing, the unseen starter
+    // phrases share the smoothed count of one extra starter phrase.
+    private static double StarterProbability(StringBasedMarkovTextGenerator generator, string[] state)
+    {
+        var chain = generator.Chain;
+        var smoothing = generator.AdditiveSmoothing;
+
+        return (chain.StarterCounts.GetValueOrDefault(state) + smoothing) / (chain.Starters.Count + smoothing * (chain.DistinctStarters.Count + 1));
+    }
+
+    // The add-k probability of the transition from the phrase, over the vocabulary and one extra slot for unseen words
+    private static double SmoothedProbability(StringBasedMarkovTextGenerator generator, string[] state, string[] next)
+    {
+        var transitions = generator.GetTransitions(state);
+        var count = transitions.Count(candidate => generator.Chain.StateComparer.Equals(candidate, next));
+
+        return (count + generator.AdditiveSmoothing) / (transitions.Count + generator.AdditiveSmoothing * (generator.Statistics.WordCount + 1));
+    }
+
+    // The probability of choosing the next state uniformly from the transitions, where it may occur several times
+    private static double Probability(StringBasedMarkovTextGenerator generator, IReadOnlyList<string[]> transitions, string[] next)
+    {
+        return (double)transitions.Count(candidate => generator.Chain.StateComparer.Equals(candidate, next)) / transitions.Count;
+    }
+
+    /// <summary>
+    /// Beam search for the most probable sentences instead of a random walk, returning up to count sentences, most probable first.
+    /// Keeps the beamWidth most probable partial sentences at each step, scored by their log-probability per word so longer
+    /// sentences aren't penalized for their length. Equally probable sentences are ordered by the tie-breaking.
+    /// </summary>
+    public static IReadOnlyList<string> GenerateMostProbable(this StringBasedMarkovTextGenerator generator, int beamWidth, int count = 1,
+        TieBreaking tieBreaking = TieBreaking.CorpusOrder)
+    {
+        generator.ThrowIfNoModel();
+
+        if (beamWidth < 1)
+        {
+            throw new ArgumentOutOfRangeException(nameof(beamWidth), beamWidth, "The beam must be at least one sentence wide.");
+        }
+
+        static double Score(Beam beam) => beam.LogProbability / beam.Tokens.Length;
+
+        // Ties are in the order they were seen in the corpus, since ordering is stable, unless ordered lexicographically
+        IEnumerable<Beam> MostProbable(IEnumerable<Beam> candidates)
+        {
+            var ordered = candidates.OrderByDescending(Score);
+
+            return tieBreaking == TieBreaking.Lexicographic
+                ? ordered.ThenBy(beam => string.Join(' ', beam.Tokens), StringComparer.Ordinal)
+                : ordered;
+        }
+
+        var beams = MostProbable(generator.Chain.StarterCounts.Keys
+                .Select(starter => new Beam(starter, starter, Math.Log(StarterProbability(generator, starter)))))
+            .Take(beamWidth)
+            .ToList();
+
+        var finished = new List<Beam>();
+
+        while (beams.Count > 0)
+        {
+            var expanded = new List<Beam>();
+
+            foreach (var beam in beams)
+            {
+                if (!generator.Chain.TryGetTransitions(beam.State, out var transitions))
+                {
+                    finished.Add(beam);
+                    continue;
+                }
+
+                // Sentences that hit the word limit are dropped, like the overflowing sentences of a random walk
+                if (beam.Tokens.Length >= generator.MaxWordCount)
+                {
+                    continue;
+                }
+
+                foreach (var (next, occurrences) in generator.CountSuccessors(transitions, tieBreaking))
+                {
+                    var logProbability = beam.LogProbability + Math.Log((double)occurrences / transitions.Count);
+                    expanded.Add(new Beam(next, beam.Tokens.Append(generator.Word(next)).ToArray(), logProbability));
+                }
+            }
+
+            beams = MostProbable(expanded).Take(beamWidth).ToList();
+        }
+
+        if (finished.Count == 0)
+        {
+            throw new SentenceOverflowException($"Beam search found no sentence within the word limit {generator.MaxWordCount}.");
+        }
+
+        return MostProbable(finished).Take(count).Select(beam => generator.Render(beam.Tokens)).ToList();
+    }
+
+    // A partial sentence of beam search, ending with the state
+    private record Beam(string[] State, string[] Tokens, double LogProbability);
+}
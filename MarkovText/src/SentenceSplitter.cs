@@ -0,0 +1,17 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Splits raw (unsanitized) text into sentences at the same delimiters the generators use
+/// </summary>
+public static partial class SentenceSplitter
+{
+    [GeneratedRegex(@"(?<=[.?!])\s+")]
+    private static partial Regex SentenceBoundaryRegex();
+
+    public static IEnumerable<string> Split(string text)
+    {
+        return SentenceBoundaryRegex().Split(text.Trim()).Where(sentence => !string.IsNullOrWhiteSpace(sentence));
+    }
+}
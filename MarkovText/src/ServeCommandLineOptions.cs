@@ -0,0 +1,16 @@
+﻿using CommandLine;
+
+namespace MarkovText;
+
+/// <summary>
+/// Options of the serve verb, which serves a saved model over HTTP, see GenerationServer
+/// </summary>
+[Verb("serve", HelpText = "Serve GET /generate?count=N&seed=S requests from a model file written by train, or a .persona file.")]
+public class ServeCommandLineOptions
+{
+    [Value(0, MetaName = "model", Required = true, HelpText = "Path to the model file, e.g., model.mkv, or a .persona file.")]
+    public required string Model { get; set; }
+
+    [Option('p', "port", Required = false, HelpText = "The port to listen on.", Default = 8080)]
+    public required int Port { get; set; }
+}
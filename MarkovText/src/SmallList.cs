@@ -0,0 +1,108 @@
+﻿using System.Collections;
+using System.Runtime.CompilerServices;
+
+namespace MarkovText;
+
+/// <summary>
+/// List that stores its first items inline, for the many states of a Markov model with only a handful of continuations.
+/// As a struct stored directly in a dictionary, a list of up to InlineCapacity items needs no heap allocation of its own,
+/// and only longer lists allocate an array for the remaining items.
+/// Mutate the list stored in a dictionary by reference, e.g., with CollectionsMarshal.GetValueRefOrAddDefault.
+/// Copies share the array of overflow items, but removing items never changes what a copy contains.
+/// </summary>
+public struct SmallList<T> : IReadOnlyList<T>
+{
+    public const int InlineCapacity = 4;
+
+    [InlineArray(InlineCapacity)]
+    private struct InlineItems
+    {
+        private T item;
+    }
+
+    private InlineItems inline;
+
+    // The items after the inline ones
+    private T[]? overflow;
+
+    public int Count { get; private set; }
+
+    public readonly T this[int index]
+    {
+        get
+        {
+            ArgumentOutOfRangeException.ThrowIfNegative(index);
+            ArgumentOutOfRangeException.ThrowIfGreaterThanOrEqual(index, Count);
+
+            return index < InlineCapacity ? inline[index] : overflow![index - InlineCapacity];
+        }
+    }
+
+    // The length of the array allocated for the items after the inline ones
+    public readonly int OverflowCapacity => overflow?.Length ?? 0;
+
+    public void Add(T item)
+    {
+        if (Count < InlineCapacity)
+        {
+            inline[Count++] = item;
+            return;
+        }
+
+        var overflowIndex = Count - InlineCapacity;
+
+        if (overflow == null || overflowIndex == overflow.Length)
+        {
+            Array.Resize(ref overflow, Math.Max(InlineCapacity, overflowIndex * 2));
+        }
+
+        overflow[overflowIndex] = item;
+        Count++;
+    }
+
+    /// <summary>
+    /// Removes the item at the index, moving the following items forward
+    /// </summary>
+    public void RemoveAt(int index)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegative(index);
+        ArgumentOutOfRangeException.ThrowIfGreaterThanOrEqual(index, Count);
+
+        var removed = index;
+        RemoveAll(_ => removed-- == 0);
+    }
+
+    /// <summary>
+    /// Removes the items that match, keeping the order of the others, and returns how many were removed.
+    /// The remaining items are copied to a new list, so the overflow array shrinks with them.
+    /// </summary>
+    public int RemoveAll(Predicate<T> match)
+    {
+        var kept = new SmallList<T>();
+
+        for (var i = 0; i < Count; i++)
+        {
+            if (!match(this[i]))
+            {
+                kept.Add(this[i]);
+            }
+        }
+
+        var removed = Count - kept.Count;
+        this = kept;
+        return removed;
+    }
+
+    public readonly IEnumerator<T> GetEnumerator() => Enumerate(this).GetEnumerator();
+
+    readonly IEnumerator IEnumerable.GetEnumerator() => GetEnumerator();
+
+    // Enumerates a copy of the list, since iterators can't refer to the struct itself
+    private static IEnumerable<T> Enumerate(SmallList<T> list)
+    {
+        for (var i = 0; i < list.Count; i++)
+        {
+            yield return list[i];
+        }
+    }
+}
@@ -0,0 +1,18 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// How the multi-order model of InterpolatedMarkovTextGenerator estimates the probability of the next word from its orders
+/// </summary>
+public enum Smoothing
+{
+    // Linear interpolation of the orders that have seen their part of the context, see InterpolatedMarkovTextGenerator.Weights
+    Interpolation,
+
+    // Katz back-off: seen continuations are discounted with Good-Turing estimates, and the discounted probability mass is given to
+    // the unseen words in proportion to the next lower order, down to the word frequencies
+    KatzBackoff,
+
+    // Modified Kneser-Ney: each order subtracts one of three discounts from the counts and interpolates with the next lower order,
+    // whose counts are the numbers of distinct words preceding the continuation. The usual choice for scoring and perplexity.
+    KneserNey,
+}
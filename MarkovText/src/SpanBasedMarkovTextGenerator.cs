@@ -102,9 +102,9 @@ public partial class SpanBasedMarkovTextGenerator : IGenerator
         Corpus = corpus.AsMemory();
 
         var corpusSpan = Corpus.Span;
-        var firstOccurrenceLookup = new Dictionary<string, Range>(); // Tracks the first occurrence of a phrase in the corpus
+        var firstOccurrences = new Dictionary<string, Range>(); // Tracks the first occurrence of a phrase in the corpus
+        var firstOccurrenceLookup = firstOccurrences.GetAlternateLookup<ReadOnlySpan<char>>();  // Looks up phrases by their span without allocating strings
         var slidingWindow = new CyclicArray<Range>(Order);
-        var wordCount = 0;
         Range? previousRange = null;
 
         foreach (var word in corpusSpan.Split(' ')) // Split the corpus into words
@@ -114,27 +114,26 @@ public partial class SpanBasedMarkovTextGenerator : IGenerator
                 continue;
             }
 
-            slidingWindow[wordCount] = word;
+            slidingWindow.Push(word);
 
-            if (++wordCount < Order)
+            if (!slidingWindow.IsFull)
             {
                 if (SentenceDelimiters.Contains(corpusSpan[word.End.Value-1]))
                 {
                     previousRange = default;
-                    wordCount = 0;
+                    slidingWindow.Clear();
                 }
 
                 continue;
             }
 
-            var firstWord = slidingWindow[wordCount];
-            var lastWord = slidingWindow[wordCount - 1];
+            var firstWord = slidingWindow.Oldest;
+            var lastWord = slidingWindow.Latest;
             var phrase = new Range(firstWord.Start, lastWord.End);
-            var phraseString = corpusSpan[phrase].ToString();
 
-            if (!firstOccurrenceLookup.TryGetValue(phraseString, out var firstPhrase))
+            if (!firstOccurrenceLookup.TryGetValue(corpusSpan[phrase], out var firstPhrase))
             {
-                firstOccurrenceLookup.Add(phraseString, phrase);
+                firstOccurrences.Add(corpusSpan[phrase].ToString(), phrase);  // Only the first occurrence allocates a string
             }
             else
             {
@@ -155,7 +154,7 @@ public partial class SpanBasedMarkovTextGenerator : IGenerator
             if (SentenceDelimiters.Contains(corpusSpan[phrase.End.Value-1]))
             {
                 previousRange = default;
-                wordCount = 0;
+                slidingWindow.Clear();
             }
         }
     }
@@ -0,0 +1,11 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// How well a model trained on part of a corpus predicts the rest, see StringBasedMarkovTextGenerator.EvaluateSplit
+/// </summary>
+/// <param name="Order">The order of the evaluated model</param>
+/// <param name="TrainingSentences">The number of corpus sentences the model was trained on</param>
+/// <param name="HeldOutSentences">The number of corpus sentences held out for the evaluation</param>
+/// <param name="Perplexity">The perplexity of the model on the held-out sentences, infinite if they contain an unseen transition without smoothing</param>
+/// <param name="Coverage">The fraction of the starter phrases and transitions of the held-out sentences that the model has seen</param>
+public record SplitEvaluation(int Order, int TrainingSentences, int HeldOutSentences, double Perplexity, double Coverage);
@@ -0,0 +1,14 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Ordinal string comparer hashing with the unseeded GetStableHashCode, which is cheaper than the randomized hashing
+/// .NET otherwise falls back to when many keys collide. Only suitable for trusted input, since the hash can be attacked.
+/// </summary>
+public sealed class StableStringComparer : IEqualityComparer<string>
+{
+    public static readonly StableStringComparer Instance = new();
+
+    public bool Equals(string? first, string? second) => string.Equals(first, second, StringComparison.Ordinal);
+
+    public int GetHashCode(string text) => text.GetStableHashCode();
+}
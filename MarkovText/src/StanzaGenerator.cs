@@ -0,0 +1,233 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Generates poetry with a StringBasedMarkovTextGenerator: stanzas, lines of given syllable counts, and rhyming couplets
+/// grown backwards with the reverse model, see BuildOptions.BuildReverseModel
+/// </summary>
+public static class StanzaGenerator
+{
+    /// <summary>
+    /// Generates a stanza of the given number of lines, for a model built with BuildOptions.LineMode
+    /// </summary>
+    public static string GenerateStanza(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, int lineCount, GenerationOptions? options = null)
+    {
+        var lines = new List<string>();
+
+        for (var i = 0; i < lineCount; i++)
+        {
+            lines.Add(generator.Generate(random, options ?? generator.DefaultOptions).Text);
+        }
+
+        return string.Join('\n', lines);
+    }
+
+    /// <summary>
+    /// Generates a stanza whose lines are sentences (or lines, see BuildOptions.LineMode) with exactly the given syllable counts,
+    /// e.g., 5, 7, 5 for a haiku. Each line is searched by backtracking over the continuations in random order, until a sentence
+    /// ends with the syllables of the line. Throws GenerationFailedException if the budget of visited phrases runs out first.
+    /// </summary>
+    public static string GenerateSyllableStanza(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, IReadOnlyList<int> syllablesPerLine,
+        ISyllableCounter? syllableCounter = null, int budget = 10_000)
+    {
+        generator.ThrowIfNoModel();
+
+        var counter = syllableCounter ?? new EnglishSyllableCounter();
+        var lines = new List<string>();
+
+        foreach (var syllables in syllablesPerLine)
+        {
+            var tokens = new List<string>();
+            var found = false;
+
+            foreach (var starter in generator.Chain.DistinctStarters.Shuffled(random))
+            {
+                var remaining = syllables - starter.Sum(counter.CountSyllables);
+
+                tokens.Clear();
+                tokens.AddRange(starter);
+
+                if (remaining >= 0 && TryCompleteSyllables(generator, starter, remaining, tokens, counter, random, ref budget))
+                {
+                    found = true;
+                    break;
+                }
+
+                if (budget <= 0)
+                {
+                    break;
+                }
+            }
+
+            if (!found)
+            {
+                throw new GenerationFailedException($"No line of {syllables} syllables was found, {lines.Count} of {syllablesPerLine.Count} lines were generated.");
+            }
+
+            lines.Add(generator.Render(tokens));
+        }
+
+        return string.Join('\n', lines);
+    }
+
+    // Depth-first search for continuations of the state that end the sentence with exactly the remaining syllables
+    private static bool TryCompleteSyllables(StringBasedMarkovTextGenerator generator, string[] state, int remaining, List<string> tokens,
+        ISyllableCounter counter, IRandomNumberGenerator random, ref int budget)
+    {
+        if (--budget < 0 || tokens.Count >= generator.MaxWordCount)
+        {
+            return false;
+        }
+
+        if (!generator.Chain.TryGetTransitions(state, out var transitions))
+        {
+            return remaining == 0;
+        }
+
+        foreach (var next in transitions.Distinct().Shuffled(random))
+        {
+            var word = generator.Word(next);
+            var syllables = counter.CountSyllables(word);
+
+            if (syllables > remaining)
+            {
+                continue;
+            }
+
+            tokens.Add(word);
+
+            if (TryCompleteSyllables(generator, next, remaining - syllables, tokens, counter, random, ref budget))
+            {
+                return true;
+            }
+
+            tokens.RemoveAt(tokens.Count - 1);
+
+            if (budget < 0)
+            {
+                return false;
+            }
+        }
+
+        return false;
+    }
+
+    /// <summary>
+    /// Grows a random sentence backwards from one of the corpus sentences ending with the word, ignoring case and punctuation.
+    /// Needs the reverse model, see BuildOptions.BuildReverseModel.
+    /// </summary>
+    public static string GenerateEndingWith(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, string word)
+    {
+        var target = word.TrimPunctuation();
+
+        return GrowFromEnding(generator, random, lastWord => string.Equals(lastWord, target, StringComparison.OrdinalIgnoreCase))
+            ?? throw new ArgumentException($"No corpus sentence ends with {word}.", nameof(word));
+    }
+
+    /// <summary>
+    /// Generates two sentences whose last words rhyme, the second grown backwards from a rhyme of the first, see GenerateEndingWith.
+    /// Words rhyme by their spelling, or by the rhymer. Throws GenerationFailedException after maxAttempts first lines without rhymes.
+    /// </summary>
+    public static string GenerateCouplet(this StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, IRhymer? rhymer = null, int maxAttempts = 10)
+    {
+        generator.ThrowIfNoReverseModel();
+
+        rhymer ??= new SuffixRhymer();
+        var failures = new List<string>();
+
+        for (var attempt = 0; attempt < maxAttempts; attempt++)
+        {
+            string firstLine;
+
+            try
+            {
+                firstLine = generator.Generate(random, generator.DefaultOptions).Text;
+            }
+            catch (Exception e) when (e is SentenceOverflowException or ContentFilterException)
+            {
+                failures.Add(e.Message);
+                continue;
+            }
+
+            var word = FirstWord(generator.Tokenizer.Tokenize(firstLine).Reverse());
+            var rhymeKey = rhymer.RhymeKey(word);
+            var secondLine = rhymeKey.Length == 0 ? null : GrowFromEnding(generator, random, lastWord =>
+                !string.Equals(lastWord, word, StringComparison.OrdinalIgnoreCase) && rhymer.RhymeKey(lastWord) == rhymeKey);
+
+            if (secondLine != null)
+            {
+                return $"{firstLine}\n{secondLine}";
+            }
+
+            failures.Add($"No corpus sentence ends with a rhyme of {word}: {firstLine}");
+        }
+
+        throw new GenerationFailedException(failures);
+    }
+
+    // The first token with a letter or digit without its punctuation, skipping punctuation tokens like "." of PunctuationTokenizer,
+    // or "" if there is none
+    private static string FirstWord(IEnumerable<string> tokens) => tokens.FirstOrDefault(token => token.Any(char.IsLetterOrDigit))?.TrimPunctuation() ?? "";
+
+    // Grows a sentence backwards from a random corpus sentence ending whose last word, without punctuation, is accepted, or null if none is
+    private static string? GrowFromEnding(StringBasedMarkovTextGenerator generator, IRandomNumberGenerator random, Func<string, bool> isLastWord)
+    {
+        var reverseModel = generator.ThrowIfNoReverseModel();
+        var endings = reverseModel.Chain.Starters
+            .Where(ending => isLastWord(FirstWord(ending)))
+            .ToList();
+
+        if (endings.Count == 0)
+        {
+            return null;
+        }
+
+        var tokens = generator.GrowBackwards(endings.Random(random), random);
+
+        tokens.Reverse();
+        return generator.Render(tokens);
+    }
+
+    // The reversed tokens of the state of the reverse model, followed by the preceding tokens up to the start of a sentence, chosen with
+    // the generation options like Generate does. If truncating on overflow, the sentence starts after the clause boundary closest to the word limit.
+    internal static List<string> GrowBackwards(this StringBasedMarkovTextGenerator generator, string[] reversedState, IRandomNumberGenerator random,
+        GenerationOptions? options = null, Func<string[], bool>? isAllowedTransition = null, Func<string[], double>? transitionWeight = null)
+    {
+        options ??= GenerationOptions.Default;
+
+        var reverseModel = generator.ReverseModel!;
+        var maxWordCount = options.MaxWordCount ?? generator.MaxWordCount;
+        var tokens = new List<string>(reversedState);
+        var phraseLength = tokens.Count;
+        var state = reversedState;
+
+        while (reverseModel.Chain.TryGetTransitions(state, out var transitions))
+        {
+            if (tokens.Count >= maxWordCount)    // Safety check to prevent infinite loops
+            {
+                if (!options.TruncateOnOverflow)
+                {
+                    throw new SentenceOverflowException($"Word limit {tokens.Count} reached for sentence:\n{string.Join(' ', Enumerable.Reverse(tokens))}");
+                }
+
+                var boundary = tokens.FindLastIndex(tokens.Count - 1, tokens.Count - phraseLength, token => token.Length > 0 && SentenceFormatting.SoftBoundaries.Contains(token[^1]));
+
+                if (boundary >= 0)
+                {
+                    tokens.RemoveRange(boundary, tokens.Count - boundary);
+                }
+
+                break;
+            }
+
+            if (!reverseModel.TryChooseTransition(state, transitions, random, null, options, isAllowedTransition, transitionWeight, out var index))
+            {
+                throw new ContentFilterException($"All transitions from '{reverseModel.Phrase(state)}' of the reverse model are excluded by the generation options for sentence:\n{string.Join(' ', Enumerable.Reverse(tokens))}");
+            }
+
+            state = transitions[index];
+            tokens.Add(state[^1]);
+        }
+
+        return tokens;
+    }
+}
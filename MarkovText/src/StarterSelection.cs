@@ -0,0 +1,13 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// How the starter phrase of a generated sentence is chosen
+/// </summary>
+public enum StarterSelection
+{
+    // In proportion to the number of corpus sentences starting with the phrase
+    Proportional,
+
+    // Every distinct starter phrase is equally likely, which favors rare sentence openings
+    Uniform,
+}
@@ -0,0 +1,119 @@
+﻿using System.Text.Json;
+using System.Text.Json.Nodes;
+
+namespace MarkovText;
+
+/// <summary>
+/// Newline-delimited JSON requests and responses over standard input and output, so the generator can be embedded
+/// as a subprocess, e.g., from Node, Python, or a game engine:
+/// {"cmd":"generate","count":3,"seed":"S"} => {"seed":"S","sentences":[...]}
+/// An "id" in the request is echoed in the response, and invalid requests get {"error":"..."} responses.
+/// </summary>
+public class StdioServer
+{
+    private readonly IGenerator generator;
+    private readonly TextReader input;
+    private readonly TextWriter output;
+
+    public StdioServer(IGenerator generator, TextReader input, TextWriter output)
+    {
+        this.generator = generator;
+        this.input = input;
+        this.output = output;
+    }
+
+    /// <summary>
+    /// Answers requests until the input ends
+    /// </summary>
+    public void Run()
+    {
+        while (input.ReadLine() is { } line)
+        {
+            if (string.IsNullOrWhiteSpace(line))
+            {
+                continue;
+            }
+
+            output.WriteLine(Handle(line));
+            output.Flush();
+        }
+    }
+
+    /// <summary>
+    /// The JSON response to a JSON request
+    /// </summary>
+    public string Handle(string request)
+    {
+        JsonObject? command;
+
+        try
+        {
+            command = JsonNode.Parse(request) as JsonObject;
+        }
+        catch (JsonException e)
+        {
+            return Error(null, $"Invalid JSON: {e.Message}");
+        }
+
+        if (command == null)
+        {
+            return Error(null, "The request must be a JSON object.");
+        }
+
+        var id = command["id"]?.DeepClone();
+
+        try
+        {
+            return command["cmd"]?.GetValue<string>() switch
+            {
+                "generate" => Generate(id, command),
+                null => Error(id, "The request has no cmd."),
+                var name => Error(id, $"Unknown cmd: {name}")
+            };
+        }
+        catch (Exception e) when (e is InvalidOperationException or FormatException)
+        {
+            return Error(id, $"Invalid request: {e.Message}");
+        }
+    }
+
+    private string Generate(JsonNode? id, JsonObject command)
+    {
+        var count = command["count"]?.GetValue<int>() ?? 1;
+
+        if (count < 1 || count > GenerationServer.MaxCount)
+        {
+            return Error(id, $"The count must be a number from 1 to {GenerationServer.MaxCount}.");
+        }
+
+        var seed = command["seed"]?.GetValue<string>() ?? Guid.NewGuid().ToString()[..8];
+        var random = new DefaultRandom(new Random(seed.GetStableHashCode()));
+        var sentences = new JsonArray();
+
+        try
+        {
+            for (var i = 0; i < count; i++)
+            {
+                sentences.Add(generator.GenerateSentence(random));
+            }
+        }
+        catch (Exception e) when (e is SentenceOverflowException or ContentFilterException)
+        {
+            return Error(id, e.Message);
+        }
+
+        return Response(id, new JsonObject { ["seed"] = seed, ["sentences"] = sentences });
+    }
+
+    private static string Error(JsonNode? id, string message) => Response(id, new JsonObject { ["error"] = message });
+
+    private static string Response(JsonNode? id, JsonObject response)
+    {
+        if (id != null)
+        {
+            response["id"] = id;
+        }
+
+        return response.ToJsonString();
+    }
+}
@@ -0,0 +1,68 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Detects the language of a sentence by counting common function words ("stopwords") of each language.
+/// Crude, but good enough to tell apart the sentences of a bilingual corpus.
+/// </summary>
+public class StopwordLanguageDetector : ILanguageDetector
+{
+    private static readonly Dictionary<string, string[]> DefaultStopwords = new()
+    {
+        ["en"] = new[] { "the", "and", "of", "to", "is", "was", "in", "that", "it", "with", "for", "as", "his", "her", "she", "he", "but", "not" },
+        ["de"] = new[] { "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "mit", "sich", "auf", "den", "dem", "ich", "war" },
+        ["fr"] = new[] { "le", "la", "les", "et", "est", "une", "des", "du", "que", "qui", "dans", "pas", "pour", "sur", "elle", "il" },
+        ["es"] = new[] { "el", "los", "las", "y", "es", "una", "del", "que", "en", "por", "con", "para", "no", "se", "su", "lo" },
+        ["it"] = new[] { "il", "gli", "e", "è", "una", "della", "che", "di", "non", "per", "con", "sono", "lo", "nel", "ma", "si" },
+        ["nl"] = new[] { "de", "het", "een", "en", "van", "is", "niet", "dat", "op", "te", "met", "zijn", "voor", "maar", "ik", "hij" },
+        ["sv"] = new[] { "och", "att", "det", "som", "en", "är", "av", "för", "med", "inte", "den", "till", "jag", "har", "på", "var" },
+    };
+
+    // Maps each stopword to the languages it belongs to
+    private readonly Dictionary<string, List<string>> languagesByStopword = new(StringComparer.OrdinalIgnoreCase);
+
+    public StopwordLanguageDetector() : this(DefaultStopwords) { }
+
+    public StopwordLanguageDetector(IReadOnlyDictionary<string, string[]> stopwordsByLanguage)
+    {
+        foreach (var (language, stopwords) in stopwordsByLanguage)
+        {
+            foreach (var stopword in stopwords)
+            {
+                languagesByStopword.AddToList(stopword, language);
+            }
+        }
+    }
+
+    public string? DetectLanguage(IReadOnlyList<string> words)
+    {
+        var scores = new Dictionary<string, int>();
+
+        foreach (var word in words)
+        {
+            if (languagesByStopword.TryGetValue(word.Trim(TrimCharacters), out var languages))
+            {
+                foreach (var language in languages)
+                {
+                    scores[language] = scores.GetValueOrDefault(language) + 1;
+                }
+            }
+        }
+
+        if (scores.Count == 0)
+        {
+            return null;
+        }
+
+        var ranked = scores.OrderByDescending(score => score.Value).ToList();
+
+        // A tie between the two best languages means the sentence is ambiguous
+        if (ranked.Count > 1 && ranked[0].Value == ranked[1].Value)
+        {
+            return null;
+        }
+
+        return ranked[0].Key;
+    }
+
+    private static readonly char[] TrimCharacters = { '.', ',', ';', ':', '?', '!', '-' };
+}
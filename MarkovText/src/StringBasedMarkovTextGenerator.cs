@@ -1,146 +1,790 @@
 ﻿using System.Text;
-using System.Text.RegularExpressions;
 
 namespace MarkovText;
 
 /// <summary>
-/// Class that generates text based on the Markov chain algorithm
+/// Class that generates text based on the Markov chain algorithm, with a MarkovChain over the tokens of the corpus sentences.
+/// Exporting, inspecting, scoring, and the constrained and poetic generation modes are extension methods, see ModelExport,
+/// ModelInspection, SentenceScoring, ConstrainedGeneration, TextContinuation, and StanzaGenerator.
 /// </summary>
-public partial class StringBasedMarkovTextGenerator : IGenerator
+public class StringBasedMarkovTextGenerator : IGenerator
 {
     // Safety limit for longest sentence that can be generated, to prevent infinite loops
     public int MaxWordCount = 1000;
 
-    // The order of the Markov chain (how many words in the "state" of the chain)
-    private int Order;
-
     // Default file path for the corpus text
     public const string DefaultCorpusPath = "Resources/thecorsetandthecrinoline.txt";
 
-    // Phrases at the start of sentences are the initial states of the Markov chain
-    private readonly List<string> SentenceStarterPhrases = new();
+    // The chain whose states are the phrases of Order tokens, e.g., (the, big, dog) => (big, dog, was).
+    // In models imported from ARPA, the sentence ending of a phrase is a state of one more token, see IsEnding.
+    // The comparer of the tokens can be replaced, see BuildOptions.PhraseComparer.
+    internal MarkovChain<string> Chain { get; private set; } = new(2);
 
-    // Maps prefix word phrases to suffix phrases, e.g., "the big dog" => "big dog was"
-    // Tuple also holds the last word of the suffix phrase, e.g., "was"
-    private readonly Dictionary<string, List<(string,string)>> PhraseTransitions = new();
+    // The order of the Markov chain (how many words in the "state" of the chain)
+    internal int Order => Chain.Order;
 
-    // Thread-local StringBuilder to avoid memory overhead from multiple threads
-    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+    // The options the model was built with, which Learn keeps applying. Null for models loaded from files, which don't store them.
+    private BuildOptions? Options;
 
-    // Sentence delimiters used to detect sentence boundaries
-    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+    // The tokenizer the model was built with, also used to join the generated tokens
+    internal ITokenizer Tokenizer { get; private set; } = new WhitespaceTokenizer();
 
-    // Regex patterns for sentence splitting, sanitization, and whitespace normalization
-    [GeneratedRegex(@"\[.+?\]|\""|\)|\(|\'|\n|\r|“|”|’|_")]
-    private static partial Regex SanitizerRegex();
+    // The distinct states containing each word without punctuation, ignoring case, for GenerateContaining.
+    // Indexed on first use and cleared whenever the transitions change.
+    internal Dictionary<string, List<string[]>>? KeywordPhrases;
 
-    [GeneratedRegex("\n")]
-    private static partial Regex FixLineEndingsRegex();
+    // Model of the reversed corpus sentences, if built, see BuildOptions.BuildReverseModel
+    internal StringBasedMarkovTextGenerator? ReverseModel { get; private set; }
 
-    [GeneratedRegex(@"\s+")]
-    private static partial Regex MultipleWhitespaceRegex();
+    // Repeated words share one string instead of allocating a string per occurrence, across all chunks of the corpus,
+    // the reverse model, and learned text
+    private StringPool Pool = new();
 
-    public override string ToString() => "String";
+    // The word frequencies, sentence hashes, opening styles, and casing of the corpus
+    internal CorpusStatistics Statistics { get; private set; } = new();
 
-    public void BuildMarkovModel(string corpus, int order = 2)
-    {
-        Order = order;
+    // True if the tokens were lowercased, see BuildOptions.NormalizeCase
+    internal bool CaseNormalized { get; private set; }
+
+    // Transitions to these words are down-weighted by StopwordWeight, see BuildOptions.Stopwords
+    internal HashSet<string> Stopwords { get; private set; } = new();
+    internal double StopwordWeight { get; private set; } = 1;
+
+    // The k of add-k smoothing when scoring, see BuildOptions.AdditiveSmoothing
+    internal double AdditiveSmoothing { get; private set; }
 
-        SentenceStarterPhrases.Clear();
-        PhraseTransitions.Clear();
+    // The net changes of the starter phrase and transition counts while learning, returned as a patch by Learn
+    private Dictionary<string, int>? LearnedStarterPhrases;
+    private Dictionary<(string Phrase, string SuffixPhrase, string Word), int>? LearnedTransitions;
 
-        AnalyzeCorpus(corpus);  // Analyze the corpus and build the Markov model
+    // The successor counts kept while training with BuildOptions.MaxMemoryBytes or MaxSuccessorsPerPhrase, so the least frequent
+    // transitions are found without scanning the model. Null if not needed, or stale after editing the model, e.g., with ApplyPatch.
+    private SuccessorCounts<string>? Successors;
 
-        if (SentenceStarterPhrases.Count == 0)
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // How many sentences are analyzed between memory usage checks, see BuildOptions.MaxMemoryBytes
+    private const int MemoryCheckInterval = 1000;
+
+    // Options used by GenerateSentence, e.g., the sampling defaults of a persona
+    public GenerationOptions DefaultOptions = GenerationOptions.Default;
+
+    public override string ToString() => "String";
+
+    /// <summary>
+    /// What was pruned to stay within BuildOptions.MaxMemoryBytes while building the model
+    /// </summary>
+    public PruneReport MemoryPruning { get; private set; } = PruneReport.None;
+
+    /// <summary>
+    /// How many corpus sentences begin with each opening style
+    /// </summary>
+    public IReadOnlyDictionary<OpeningStyle, int> OpeningStyles => Statistics.OpeningStyleCounts;
+
+    /// <summary>
+    /// The tokens the model has learned and how often each occurs in the corpus, most frequent first, e.g., to inspect
+    /// the model or build frequency-based filters. Tokens are lowercased if the model was built with BuildOptions.NormalizeCase.
+    /// </summary>
+    public IEnumerable<(string Word, int Count)> Vocabulary() => Statistics.Vocabulary();
+
+    /// <summary>
+    /// The number of corpus tokens the model has learned, e.g., to report how much each file of a corpus added with Learn
+    /// </summary>
+    public long TokenCount => Statistics.TokenCount;
+
+    public void BuildMarkovModel(string corpus, int order = 2) => BuildMarkovModel(corpus, new BuildOptions { Order = order });
+
+    /// <summary>
+    /// Indexes the given corpus text with custom build options.
+    /// Clears any previously indexed corpus.
+    /// If cancelled, throws OperationCanceledException and keeps the sentences indexed so far.
+    /// </summary>
+    public void BuildMarkovModel(string corpus, BuildOptions options, CancellationToken cancellationToken = default)
+    {
+        Reset(options);
+        AnalyzeCorpus(corpus, options, cancellationToken);  // Analyze the corpus and build the Markov model
+        Statistics.UpdatePreferredCasing();
+
+        if (Chain.SequenceCount == 0)
         {
             throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
         }
     }
 
-    public string GenerateSentence(IRandomNumberGenerator random)
+    /// <summary>
+    /// Indexes a corpus too large to fit in memory, e.g., a multi-gigabyte file, reading it in chunks that end at sentence
+    /// boundaries (see CorpusFiles.ReadSentenceChunks) and discarding each chunk once analyzed. The preprocessors of the options
+    /// are applied to each chunk, and reading stops at the end of a Project Gutenberg text if they strip its boilerplate, since
+    /// only the license follows. Clears any previously indexed corpus.
+    /// </summary>
+    public void BuildMarkovModel(TextReader corpus, BuildOptions options, int chunkSize = CorpusFiles.DefaultChunkSize, CancellationToken cancellationToken = default)
     {
-        if (SentenceStarterPhrases.Count == 0)
+        Reset(options);
+
+        var stripsBoilerplate = options.Preprocessors.OfType<StripGutenbergBoilerplatePreprocessor>().Any();
+
+        foreach (var chunk in CorpusFiles.ReadSentenceChunks(corpus, chunkSize))
         {
-            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+            AnalyzeCorpus(chunk, options, cancellationToken);
+
+            if (stripsBoilerplate && StripGutenbergBoilerplatePreprocessor.ContainsEndMarker(chunk))
+            {
+                break;
+            }
+        }
+
+        Statistics.UpdatePreferredCasing();
+
+        if (Chain.SequenceCount == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus.", nameof(corpus));
         }
+    }
+
+    // Clears the indexed corpus and takes the settings of the build options
+    internal void Reset(BuildOptions options)
+    {
+        Options = options;
+        Chain = new MarkovChain<string>(options.Order, options.PhraseComparer);
+        Tokenizer = options.Tokenizer;
+        CaseNormalized = options.NormalizeCase;
+        Stopwords = new HashSet<string>(options.Stopwords.Select(word => word.TrimPunctuation()), StringComparer.OrdinalIgnoreCase);
+        StopwordWeight = options.StopwordWeight;
+        AdditiveSmoothing = options.AdditiveSmoothing;
+
+        KeywordPhrases = null;
+        Statistics = new CorpusStatistics();
+        MemoryPruning = PruneReport.None;
+        Pool = new StringPool();
+        StopTrackingMemory();
+
+        ReverseModel = options.BuildReverseModel ? new StringBasedMarkovTextGenerator { Chain = new MarkovChain<string>(Order), Pool = Pool } : null;
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random) => Generate(random, DefaultOptions).Text;
+
+    /// <summary>
+    /// Generate a random Markov text with custom generation options.
+    /// If cancelled, returns the sentence generated so far with GenerationResult.Cancelled set.
+    /// </summary>
+    public GenerationResult Generate(IRandomNumberGenerator random, GenerationOptions options, CancellationToken cancellationToken = default)
+        => Generate(random, options, null, cancellationToken);
+
+    /// <summary>
+    /// Replay a recorded choice log (see GenerationOptions.RecordChoices) against the current, possibly edited, model.
+    /// Recorded indices wrap around if a phrase now has fewer transitions, and the random number generator takes over if the log runs out.
+    /// A log ending in Choice.Truncation is cut short at the same clause boundary as the recorded sentence.
+    /// </summary>
+    public GenerationResult Replay(IReadOnlyList<Choice> choices, IRandomNumberGenerator fallback, GenerationOptions? options = null)
+        => Generate(fallback, options ?? DefaultOptions, choices, CancellationToken.None);
+
+    private GenerationResult Generate(IRandomNumberGenerator random, GenerationOptions options, IReadOnlyList<Choice>? replay, CancellationToken cancellationToken)
+    {
+        ThrowIfNoModel();
+        TransitionSampling.ThrowIfInvalidSampling(options);
+
+        var maxWordCount = options.MaxWordCount ?? MaxWordCount;
+        var truncated = false;
+        var cancelled = false;
+        var choices = options.RecordChoices || replay != null ? new List<Choice>() : null;
+
+        // Predicates rejecting banned starter phrases and transitions, null when there is nothing to filter
+        Func<string[], bool>? isAllowedStarter = options.ContentFilter is { } filter ? starter => filter.IsAllowedPhrase(Phrase(starter)) : null;
+        var isAllowedTransition = this.IsAllowedTransition(options);
+        var transitionWeight = this.TransitionWeight();
 
         var stringBuilder = threadLocalStringBuilder.Value;
         stringBuilder!.Clear();  // Clear the StringBuilder for reuse
         var wordCount = Order;  // Track the current word count to prevent infinite loops
-        var phrase = SentenceStarterPhrases.Random(random); // Choose a random starter key from the available starter keys
-        stringBuilder.Append(phrase);  // Write the entire sentence starter phrase
+
+        // Choose a random starter state from the available starter states
+        var starters = options.StarterSelection == StarterSelection.Uniform ? Chain.DistinctStarters : Chain.Starters;
+
+        if (!TransitionSampling.TryChoose(starters, random, ReplayedIndex(replay, 0), isAllowedStarter, null, out var index))
+        {
+            throw new ContentFilterException("All sentence starter phrases are banned by the content filter.");
+        }
+
+        choices?.Add(new Choice(null, index));
+        var state = starters[index];
+
+        // The length of the text after the words of each recorded choice, to trim the choices if the sentence is truncated
+        var choiceEnds = choices != null ? new List<int>() : null;
+
+        // Write the entire sentence starter phrase
+        foreach (var token in state)
+        {
+            Tokenizer.Append(stringBuilder, Recase(token));
+        }
+
+        choiceEnds?.Add(stringBuilder.Length);
 
         // Continuously generate words based on the Markov chain
-        while (PhraseTransitions.TryGetValue(phrase, out var possibleTransitions))
+        while (Chain.TryGetTransitions(state, out var possibleTransitions))
         {
-            if (++wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            // A replayed truncation cuts the sentence short where the recorded sentence was
+            var replayedTruncation = IsReplayedTruncation(replay, ++wordCount - Order);
+
+            if (wordCount >= maxWordCount || replayedTruncation)    // Safety check to prevent infinite loops
             {
-                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+                if (!options.TruncateOnOverflow && !replayedTruncation)
+                {
+                    throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+                }
+
+                var cut = SentenceFormatting.TruncateAtClauseBoundary(stringBuilder);
+                truncated = true;
+
+                // Only the choices up to the word with the clause boundary are kept, so replaying them reproduces the text
+                if (choices != null)
+                {
+                    var kept = Math.Min(choiceEnds!.Count(end => end <= cut) + 1, choices.Count);
+                    choices.RemoveRange(kept, choices.Count - kept);
+                    choices.Add(Choice.Truncation);
+                }
+
+                break;
+            }
+
+            if (cancellationToken.IsCancellationRequested)
+            {
+                cancelled = true;
+                break;
+            }
+
+            if (!this.TryChooseTransition(state, possibleTransitions, random, ReplayedIndex(replay, wordCount - Order), options, isAllowedTransition, transitionWeight, out index))
+            {
+                throw new ContentFilterException($"All transitions from '{Phrase(state)}' are excluded by the generation options for sentence:\n{stringBuilder}");
             }
 
-            (phrase, var lastWordInPhrase) = possibleTransitions.Random(random);
+            choices?.Add(new Choice(Phrase(state), index));
+            state = possibleTransitions[index];
+            Tokenizer.Append(stringBuilder, Recase(Word(state)));   // Write the last word of the phrase to the generated text
+            choiceEnds?.Add(stringBuilder.Length);
+        }
+
+        if (CaseNormalized)
+        {
+            SentenceFormatting.CapitalizeFirstLetter(stringBuilder);
+        }
+
+        if (options.ReproduceOpeningStyle && !cancelled)
+        {
+            Statistics.ApplyRandomOpeningStyle(stringBuilder, random);
+        }
+
+        var text = stringBuilder.ToString();
+
+        foreach (var postProcessor in options.PostProcessors)
+        {
+            text = postProcessor.Process(text);
+        }
+
+        return new GenerationResult { Text = text, Truncated = truncated, Cancelled = cancelled, Choices = choices };  // Return the generated Markov text
+    }
+
+    private static bool IsReplayedTruncation(IReadOnlyList<Choice>? replay, int step) => replay != null && step < replay.Count && replay[step] == Choice.Truncation;
+
+    private static int? ReplayedIndex(IReadOnlyList<Choice>? replay, int step) => replay != null && step < replay.Count ? (int?)replay[step].Index : null;
+
+    /// <summary>
+    /// The distinct sentence starter phrases and the number of corpus sentences starting with them
+    /// </summary>
+    public IReadOnlyList<(string Phrase, int Count)> GetStarterPhrases() =>
+        Chain.StarterCounts.Select(starter => (Phrase(starter.Key), starter.Value)).ToList();
+
+    /// <summary>
+    /// The phrases that are followed by at least one more word
+    /// </summary>
+    public IEnumerable<string> GetPhrases() => Chain.ContinuedStates.Select(Phrase);
+
+    /// <summary>
+    /// The distinct continuations of a phrase and how often they follow it in the corpus, most frequent first.
+    /// Empty if the phrase ends the sentence.
+    /// </summary>
+    public IReadOnlyList<(string SuffixPhrase, string Word, int Count)> GetSuccessors(string phrase) =>
+        CountSuccessors(GetTransitions(State(phrase))).Select(successor => (Phrase(successor.State), Word(successor.State), successor.Count)).ToList();
+
+    // The next state of each occurrence of a transition from the state, empty if the state ends the sentence
+    internal IReadOnlyList<string[]> GetTransitions(string[] state) =>
+        Chain.TryGetTransitions(state, out var transitions) ? transitions : Array.Empty<string[]>();
+
+    // The distinct transitions and their number of occurrences, most frequent first
+    internal IEnumerable<(string[] State, int Count)> CountSuccessors(IReadOnlyList<string[]> transitions, TieBreaking tieBreaking = TieBreaking.CorpusOrder)
+    {
+        // Ties are in the order they were seen in the corpus, since ordering is stable
+        var successors = Chain.CountSuccessors(transitions);
+
+        return tieBreaking == TieBreaking.Lexicographic
+            ? successors.ThenBy(successor => Word(successor.State), StringComparer.Ordinal)
+            : successors;
+    }
+
+    // Whether the state is the sentence ending of a phrase, which models imported from ARPA have separate transitions to
+    internal bool IsEnding(string[] state) => state.Length == Order + 1 && state[^1] == ArpaFormat.SentenceEnd;
+
+    // The last word of the phrase of the state, which its transitions append to the sentence
+    internal string Word(string[] state) => IsEnding(state) ? state[^2] : state[^1];
+
+    // The tokens of the state joined by spaces, e.g., "big dog was", and the sentence endings glued to their ArpaFormat marker
+    internal string Phrase(string[] state) =>
+        IsEnding(state) ? ArpaFormat.Ending(string.Join(' ', state, 0, Order)) : string.Join(' ', state);
+
+    // The state of a phrase, tokens never contain spaces, so the phrase can be split back into its tokens
+    internal string[] State(string phrase) =>
+        ArpaFormat.IsEnding(phrase) && phrase.Length > ArpaFormat.SentenceEnd.Length && phrase[^(ArpaFormat.SentenceEnd.Length + 1)] != ' '
+            ? [.. phrase[..^ArpaFormat.SentenceEnd.Length].Split(' '), ArpaFormat.SentenceEnd]
+            : phrase.Split(' ');
+
+    // The state of a phrase of a patch, with its tokens shared with the rest of the model
+    private string[] PooledState(string phrase)
+    {
+        var state = State(phrase);
+
+        for (var i = 0; i < state.Length; i++)
+        {
+            state[i] = Pool.Get(state[i]);
+        }
+
+        return state;
+    }
+
+    public static SplitEvaluation EvaluateSplit(string corpus, double trainFraction = 0.9, int order = 2) =>
+        EvaluateSplit(corpus, trainFraction, new BuildOptions { Order = order });
+
+    /// <summary>
+    /// Trains a model on the first sentences of the corpus and evaluates it on the rest, e.g., to pick the order for a corpus:
+    /// higher orders copy the training sentences more closely, so they cover fewer of the held-out transitions. The perplexity
+    /// is infinite once a held-out transition is unseen, unless the options set BuildOptions.AdditiveSmoothing.
+    /// </summary>
+    public static SplitEvaluation EvaluateSplit(string corpus, double trainFraction, BuildOptions options) =>
+        SentenceScoring.EvaluateSplit(corpus, trainFraction, options);
 
-            stringBuilder.Append(' ');
-            stringBuilder.Append(lastWordInPhrase);   // Write the last word of the phrase to the generated text
+    internal void ThrowIfNoModel()
+    {
+        if (Chain.SequenceCount == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
         }
+    }
 
-        return stringBuilder.ToString();  // Return the generated Markov text
+    internal StringBasedMarkovTextGenerator ThrowIfNoReverseModel()
+    {
+        ThrowIfNoModel();
+        return ReverseModel ?? throw new InvalidOperationException($"There is no reverse model. Build the model with {nameof(BuildOptions)}.{nameof(BuildOptions.BuildReverseModel)}.");
     }
 
-    private void AnalyzeCorpus(string corpus)
+    internal string[] NormalizeTokens(string[] tokens) => CaseNormalized ? tokens.Select(token => token.ToLowerInvariant()).ToArray() : tokens;
+
+    internal bool IsStopword(string word) => Stopwords.Contains(word.TrimPunctuation());
+
+    internal string Recase(string token) => CaseNormalized ? Statistics.PreferredCasing.GetValueOrDefault(token, token) : token;
+
+    internal string Render(IEnumerable<string> tokens)
     {
-        // Replace line endings (e.g., poem line breaks) with a space
-        corpus = FixLineEndingsRegex().Replace(corpus, " ");
+        var stringBuilder = JoinTokens(tokens.Select(Recase));
 
-        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
-        corpus = SanitizerRegex().Replace(corpus, "");
+        if (CaseNormalized)
+        {
+            SentenceFormatting.CapitalizeFirstLetter(stringBuilder);
+        }
 
-        // Normalize multiple consecutive spaces into a single space
-        corpus = MultipleWhitespaceRegex().Replace(corpus, " ");
+        return stringBuilder.ToString();
+    }
 
-        var wordCount = 0;
-        var slidingWindow = new CyclicArray<string>(Order);
-        string? previousPhraseString = null;
+    internal StringBuilder JoinTokens(IEnumerable<string> tokens)
+    {
+        var stringBuilder = new StringBuilder();
 
-        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        foreach (var token in tokens)
         {
-            if (string.IsNullOrWhiteSpace(word))
+            Tokenizer.Append(stringBuilder, token);
+        }
+
+        return stringBuilder;
+    }
+
+    /// <summary>
+    /// Quote mode: picks an actual sentence of the corpus instead of chaining, e.g., as a baseline to compare generated
+    /// sentences with, or as a fallback when a generated sentence isn't good enough. Requires BuildOptions.StoreSentences.
+    /// </summary>
+    public string Quote(IRandomNumberGenerator random) => Statistics.RandomSentence(random)
+        ?? throw new InvalidOperationException($"No corpus sentences were stored. Build the model with {nameof(BuildOptions.StoreSentences)} to quote sentences.");
+
+    /// <summary>
+    /// Loads a generator from a .persona file, with the sampling defaults of the persona as DefaultOptions
+    /// </summary>
+    public static StringBasedMarkovTextGenerator FromPersona(string path) => FromPersona(Persona.Load(path));
+
+    public static StringBasedMarkovTextGenerator FromPersona(Persona persona)
+    {
+        var generator = new StringBasedMarkovTextGenerator
+        {
+            Tokenizer = Persona.CreateTokenizer(persona.Tokenizer),
+            CaseNormalized = persona.CaseNormalized,
+            Stopwords = new HashSet<string>(persona.Stopwords, StringComparer.OrdinalIgnoreCase),
+            StopwordWeight = persona.StopwordWeight,
+            DefaultOptions = persona.CreateGenerationOptions(),
+        };
+
+        generator.ApplyPatch(persona.Model);
+
+        foreach (var (lowercase, preferred) in persona.PreferredCasing)
+        {
+            generator.Statistics.PreferredCasing[lowercase] = preferred;
+        }
+
+        foreach (var (style, count) in persona.OpeningStyles)
+        {
+            generator.Statistics.OpeningStyleCounts[style] = count;
+        }
+
+        foreach (var (quote, count) in persona.OpeningQuotes)
+        {
+            generator.Statistics.OpeningQuoteCounts[quote[0]] = count;
+        }
+
+        if (generator.Chain.SequenceCount == 0)
+        {
+            throw new ArgumentException($"The persona {persona.Name} does not contain a Markov model.", nameof(persona));
+        }
+
+        return generator;
+    }
+
+    /// <summary>
+    /// Loads a generator from a file saved with Save, compressed or not, rejecting files of another format version
+    /// </summary>
+    public static StringBasedMarkovTextGenerator Load(string path)
+    {
+        using var stream = File.OpenRead(path);
+        return Load(stream);
+    }
+
+    public static StringBasedMarkovTextGenerator Load(Stream stream)
+    {
+        var (tokenizer, model) = ModelFile.Read(stream);
+        var generator = new StringBasedMarkovTextGenerator { Tokenizer = Persona.CreateTokenizer(tokenizer) };
+        generator.ApplyPatch(model);
+        return generator;
+    }
+
+    /// <summary>
+    /// Imports a Markov model exported with ToJson
+    /// </summary>
+    public static StringBasedMarkovTextGenerator FromJson(string json)
+    {
+        var table = TransitionTable.FromJson(json);
+        var generator = new StringBasedMarkovTextGenerator { Tokenizer = Persona.CreateTokenizer(table.Tokenizer) };
+        generator.ApplyPatch(table.ToPatch());
+        return generator;
+    }
+
+    /// <summary>
+    /// Imports an n-gram language model in the ARPA format, see ArpaFormat
+    /// </summary>
+    public static StringBasedMarkovTextGenerator FromArpa(string path)
+    {
+        using var reader = File.OpenText(path);
+        return FromArpa(reader);
+    }
+
+    public static StringBasedMarkovTextGenerator FromArpa(TextReader reader)
+    {
+        var generator = new StringBasedMarkovTextGenerator();
+        generator.ApplyPatch(ArpaFormat.Read(reader));
+        return generator;
+    }
+
+    /// <summary>
+    /// Adds the sentences of the text to the model without clearing it, e.g., for a bot that keeps learning from messages.
+    /// The text is analyzed like the corpus with the build options of the model, e.g., its preprocessors, line mode, stopwords,
+    /// and reverse model. Models loaded from files use the default preprocessing pipeline and the settings stored in the file.
+    /// Returns the changes as a patch, including anything pruned to stay within the memory budget, so they can be appended to a ModelJournal.
+    /// </summary>
+    public ModelPatch Learn(string text)
+    {
+        ThrowIfNoModel();
+
+        var options = Options ?? new BuildOptions
+        {
+            Order = Order,
+            Tokenizer = Tokenizer,
+            NormalizeCase = CaseNormalized,
+            Stopwords = Stopwords,
+            StopwordWeight = StopwordWeight,
+            AdditiveSmoothing = AdditiveSmoothing,
+        };
+
+        LearnedStarterPhrases = new Dictionary<string, int>();
+        LearnedTransitions = new Dictionary<(string Phrase, string SuffixPhrase, string Word), int>();
+
+        try
+        {
+            AnalyzeCorpus(text, options, CancellationToken.None);
+            Statistics.UpdatePreferredCasing();
+
+            return new ModelPatch
             {
-                continue;
+                Order = Order,
+                StarterPhrases = LearnedStarterPhrases
+                    .Where(starter => starter.Value != 0)
+                    .Select(starter => new StarterPhraseDelta(starter.Key, starter.Value))
+                    .ToList(),
+                Transitions = LearnedTransitions
+                    .Where(transition => transition.Value != 0)
+                    .Select(transition => new TransitionDelta(transition.Key.Phrase, transition.Key.SuffixPhrase, transition.Key.Word, transition.Value))
+                    .ToList(),
+            };
+        }
+        finally
+        {
+            LearnedStarterPhrases = null;
+            LearnedTransitions = null;
+        }
+    }
+
+    // Adds the change of a starter phrase to the patch Learn returns, if learning
+    private void RecordStarterPhrase(string[] state, int delta)
+    {
+        LearnedStarterPhrases?.Increment(Phrase(state), delta);
+    }
+
+    // Adds the change of a transition to the patch Learn returns, if learning
+    private void RecordTransition(string[] state, string[] next, int delta)
+    {
+        LearnedTransitions?.Increment((Phrase(state), Phrase(next), Word(next)), delta);
+    }
+
+    /// <summary>
+    /// Adds and removes the starter phrases and transitions of a patch created with CreatePatch
+    /// </summary>
+    public void ApplyPatch(ModelPatch patch)
+    {
+        if (Chain.SequenceCount > 0 && patch.Order != Order)
+        {
+            throw new ArgumentException($"Cannot apply a patch of order {patch.Order} to a model of order {Order}.", nameof(patch));
+        }
+
+        if (patch.Order != Order)
+        {
+            Chain = new MarkovChain<string>(patch.Order, Options?.PhraseComparer);
+        }
+
+        StopTrackingMemory();
+
+        foreach (var (phrase, delta) in patch.StarterPhrases)
+        {
+            var state = Chain.AddStarter(PooledState(phrase), delta);
+            Statistics.AddSentenceInitialWord(state[0]);
+
+            foreach (var token in state)
+            {
+                Statistics.AddWordFrequency(token, delta);
             }
+        }
+
+        KeywordPhrases = null;
 
-            slidingWindow[wordCount] = word;
+        foreach (var (phrase, suffixPhrase, word, delta) in patch.Transitions)
+        {
+            var next = PooledState(suffixPhrase);
 
-            if (++wordCount < Order)
+            // The chain only stores the phrase a transition leads to, whose last word is the word of the transition
+            if (Word(next) != word)
             {
-                if (SentenceDelimiters.Contains(word[^1]))
-                {
-                    previousPhraseString = null;
-                    wordCount = 0;
-                }
+                throw new ArgumentException($"The transition from '{phrase}' to '{suffixPhrase}' has the word '{word}' instead of '{Word(next)}'.", nameof(patch));
+            }
+
+            Chain.AddTransition(PooledState(phrase), next, delta);  // A phrase without transitions left now ends the sentence
+            Statistics.AddWordFrequency(word, delta);
+        }
+    }
+
+    // The number of corpus sentences starting with each starter phrase
+    internal Dictionary<string, int> CountStarterPhrases() => Chain.StarterCounts.ToDictionary(starter => Phrase(starter.Key), starter => starter.Value);
+
+    // The number of occurrences of each transition, in the order the phrases and transitions were first seen
+    internal Dictionary<(string Phrase, string SuffixPhrase, string Word), int> CountTransitions()
+    {
+        var counts = new Dictionary<(string Phrase, string SuffixPhrase, string Word), int>();
+
+        foreach (var state in Chain.ContinuedStates)
+        {
+            var phrase = Phrase(state);
+
+            foreach (var next in GetTransitions(state))
+            {
+                counts.Increment((phrase, Phrase(next), Word(next)));
+            }
+        }
+
+        return counts;
+    }
 
+    private void AnalyzeCorpus(string corpus, BuildOptions options, CancellationToken cancellationToken) =>
+        AnalyzeSentences(CorpusSplitting.SplitCorpus(corpus, options), options, cancellationToken);
+
+    internal void AnalyzeSentences(IEnumerable<string[]> sentences, BuildOptions options, CancellationToken cancellationToken)
+    {
+        var sentenceCount = 0;
+
+        if (options.MaxMemoryBytes != null || options.MaxSuccessorsPerPhrase != null)
+        {
+            StartTrackingMemory();
+        }
+
+        foreach (var sentence in sentences)
+        {
+            cancellationToken.ThrowIfCancellationRequested();
+
+            if (++sentenceCount % MemoryCheckInterval == 0)
+            {
+                EnforceMemoryLimit(options);
+            }
+
+            // Drop sentences detected as a different language, undetermined sentences are kept
+            if (options is { LanguageDetector: { } detector, Language: { } language } &&
+                detector.DetectLanguage(sentence) is { } detected && detected != language)
+            {
                 continue;
             }
 
-            var phrase = slidingWindow.CreateOffsetArray(wordCount);
-            var phraseString = string.Join(' ', phrase);
+            Statistics.CountOpeningStyle(sentence[0]);
 
-            if (previousPhraseString == null)
+            if (options.StoreSentences)
             {
-                SentenceStarterPhrases.Add(phraseString);
+                Statistics.StoreSentence(JoinTokens(sentence).ToString());
             }
-            else
+
+            if (options.NormalizeCase)
             {
-                PhraseTransitions.AddToList(previousPhraseString, (phraseString, word));
+                Statistics.NormalizeCase(sentence);
             }
 
-            previousPhraseString = phraseString;
+            AnalyzeSentence(sentence, !(options.ExcludeStopwordStarters && IsStopword(sentence[0])), options.MaxSuccessorsPerPhrase);
+            ReverseModel?.AnalyzeSentence(Enumerable.Reverse(sentence).ToArray());
+        }
+
+        EnforceMemoryLimit(options);
+    }
 
-            if (SentenceDelimiters.Contains(word[^1]))
+    private void AnalyzeSentence(string[] words, bool isStarter = true, int? maxSuccessors = null)
+    {
+        string[]? previousState = null;
+
+        for (var i = 0; i < words.Length; i++)
+        {
+            words[i] = Pool.Get(words[i]);
+        }
+
+        Statistics.AddSentence(words);
+
+        // Slide a window of Order words over the sentence, sentences with fewer words are skipped
+        for (var i = 0; i + Order <= words.Length; i++)
+        {
+            var state = words[i..(i + Order)];
+
+            if (previousState == null)
             {
-                previousPhraseString = null;
-                wordCount = 0;
+                if (isStarter)
+                {
+                    state = Chain.AddStarter(state);
+                    RecordStarterPhrase(state, 1);
+                }
+            }
+            else
+            {
+                state = AddTransition(previousState, state, maxSuccessors);
             }
+
+            previousState = state;
         }
     }
+
+    // Adds an occurrence of the transition and returns the shared next state, keeping the successor counts up to date if kept.
+    // A phrase keeps at most maxSuccessors distinct successors with the Space-Saving algorithm: a new successor replaces the one
+    // with the lowest count, including what that one inherited itself, and inherits its count, so a successor that follows
+    // the phrase more than 1 / maxSuccessors of the time is never replaced, however late it first occurs.
+    private string[] AddTransition(string[] state, string[] next, int? maxSuccessors = null)
+    {
+        KeywordPhrases = null;
+        next = Chain.AddTransition(state, next);
+        RecordTransition(state, next, 1);
+
+        if (Successors == null)
+        {
+            return next;
+        }
+
+        var inherited = 0;
+
+        while (Successors.IsFull(state, next, maxSuccessors))
+        {
+            var (replaced, replacedCount, replacedTotal) = Successors.LeastFrequent(state);
+            inherited = replacedTotal;
+
+            Chain.AddTransition(state, replaced, -replacedCount);
+            RecordTransition(state, replaced, -replacedCount);
+            Successors.Remove(state, replaced);
+        }
+
+        Successors.Add(state, next, inherited);
+        return next;
+    }
+
+    private void EnforceMemoryLimit(BuildOptions options)
+    {
+        // Each check over the limit prunes the least frequent transitions, so training degrades gracefully instead of running out of memory
+        if (options.MaxMemoryBytes is { } maxMemoryBytes && EstimateMemoryBytes() > maxMemoryBytes && Successors?.LeastCount is { } leastCount)
+        {
+            MemoryPruning = MemoryPruning.Add(PruneTransitions(leastCount + 1));
+        }
+    }
+
+    // Counts the successors once, to keep the counts up to date from then on
+    private void StartTrackingMemory()
+    {
+        Successors ??= new SuccessorCounts<string>(Chain);
+    }
+
+    // Drops the successor counts, e.g., when the model is edited other than by training
+    private void StopTrackingMemory()
+    {
+        Successors = null;
+    }
+
+    /// <summary>
+    /// Shrinks the trained model by removing the transitions seen fewer than minTransitionCount times, and the phrases
+    /// that can no longer be reached from a sentence starter phrase. Returns how much was removed.
+    /// </summary>
+    public PruneReport Prune(int minTransitionCount)
+    {
+        ThrowIfNoModel();
+
+        var report = PruneTransitions(minTransitionCount).Add(Chain.RemoveUnreachableStates());
+        StopTrackingMemory();
+
+        return report;
+    }
+
+    // Removes the transitions seen fewer than minCount times, phrases without any remaining transitions end the sentence
+    private PruneReport PruneTransitions(int minCount)
+    {
+        KeywordPhrases = null;
+
+        return Chain.RemoveRareTransitions(minCount, (state, next, count) =>
+        {
+            RecordTransition(state, next, -count);
+            Successors?.Remove(state, next);
+        });
+    }
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the model in bytes, from the states of the chain, the word strings, and the
+    /// collections holding them, for comparing orders and generators. Used to enforce BuildOptions.MaxMemoryBytes, which also
+    /// counts the successor counts kept to find the least frequent transitions.
+    /// </summary>
+    public long EstimateMemoryBytes() => Chain.EstimateMemoryBytes() + Statistics.MemoryBytes + (Successors?.MemoryBytes ?? 0);
 }
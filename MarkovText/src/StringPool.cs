@@ -0,0 +1,83 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Deduplicates the strings built while analyzing a corpus: phrases are joined into a reused buffer and looked up as spans,
+/// so a string is only allocated the first time a phrase is seen, and all occurrences share that instance
+/// </summary>
+public class StringPool
+{
+    private readonly Dictionary<string, string> Strings = new();
+
+    // Looks up the pooled strings by the characters of the buffer without allocating
+    private readonly Dictionary<string, string>.AlternateLookup<ReadOnlySpan<char>> SpanLookup;
+
+    // Reused buffer for joining phrases
+    private char[] Buffer = new char[256];
+
+    public StringPool()
+    {
+        SpanLookup = Strings.GetAlternateLookup<ReadOnlySpan<char>>();
+    }
+
+    public int Count => Strings.Count;
+
+    /// <summary>
+    /// The pooled string with the characters, allocated only if it is new
+    /// </summary>
+    public string Get(ReadOnlySpan<char> text)
+    {
+        if (!SpanLookup.TryGetValue(text, out var pooled))
+        {
+            pooled = text.ToString();
+            Strings.Add(pooled, pooled);
+        }
+
+        return pooled;
+    }
+
+    /// <summary>
+    /// The pooled instance of the string, which becomes the pooled instance if it is new
+    /// </summary>
+    public string Get(string text)
+    {
+        if (!Strings.TryAdd(text, text))
+        {
+            text = Strings[text];
+        }
+
+        return text;
+    }
+
+    /// <summary>
+    /// The pooled string of the words joined with spaces, like string.Join(' ', words, start, count)
+    /// </summary>
+    public string Join(string[] words, int start, int count)
+    {
+        var length = count - 1;
+
+        for (var i = start; i < start + count; i++)
+        {
+            length += words[i].Length;
+        }
+
+        if (Buffer.Length < length)
+        {
+            Buffer = new char[Math.Max(length, Buffer.Length * 2)];
+        }
+
+        var position = 0;
+
+        for (var i = start; i < start + count; i++)
+        {
+            if (i > start)
+            {
+                Buffer[position++] = ' ';
+            }
+
+            words[i].CopyTo(Buffer.AsSpan(position));
+            position += words[i].Length;
+        }
+
+        return Get(Buffer.AsSpan(0, position));
+    }
+}
@@ -0,0 +1,14 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Removes bracketed editorial notes like page numbers, e.g., "[12]", and parentheses (but not the text within them)
+/// </summary>
+public partial class StripBracketsPreprocessor : IPreprocessor
+{
+    [GeneratedRegex(@"\[.+?\]|\)|\(")]
+    private static partial Regex BracketsRegex();
+
+    public string Process(string text) => BracketsRegex().Replace(text, "");
+}
@@ -0,0 +1,9 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Removes underscores used to mark emphasis in plain text, e.g., "_very_" in Project Gutenberg texts
+/// </summary>
+public class StripEmphasisPreprocessor : IPreprocessor
+{
+    public string Process(string text) => text.Replace("_", "");
+}
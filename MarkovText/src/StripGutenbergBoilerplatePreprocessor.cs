@@ -0,0 +1,37 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Removes the Project Gutenberg license header and footer, keeping the text between the "*** START OF THE PROJECT GUTENBERG EBOOK ... ***"
+/// and "*** END OF THE PROJECT GUTENBERG EBOOK ... ***" lines. Texts without the markers are kept as they are.
+/// </summary>
+public partial class StripGutenbergBoilerplatePreprocessor : IPreprocessor
+{
+    [GeneratedRegex(@"^\*{3}\s*START OF (THE |THIS )?PROJECT GUTENBERG E-?BOOK.*$", RegexOptions.IgnoreCase | RegexOptions.Multiline)]
+    private static partial Regex StartMarkerRegex();
+
+    // Older texts end with "End of the Project Gutenberg EBook of ..." or "End of Project Gutenberg's ..." instead
+    [GeneratedRegex(@"^(\*{3}\s*END OF (THE |THIS )?PROJECT GUTENBERG E-?BOOK|End of (the )?Project Gutenberg).*$", RegexOptions.IgnoreCase | RegexOptions.Multiline)]
+    private static partial Regex EndMarkerRegex();
+
+    public string Process(string text)
+    {
+        if (StartMarkerRegex().Match(text) is { Success: true } start)
+        {
+            text = text[(start.Index + start.Length)..];
+        }
+
+        if (EndMarkerRegex().Match(text) is { Success: true } end)
+        {
+            text = text[..end.Index];
+        }
+
+        return text;
+    }
+
+    /// <summary>
+    /// Whether the text contains the end marker, e.g., to stop reading a text in chunks once only the license follows
+    /// </summary>
+    public static bool ContainsEndMarker(string text) => EndMarkerRegex().IsMatch(text);
+}
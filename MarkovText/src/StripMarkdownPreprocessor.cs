@@ -0,0 +1,55 @@
+﻿using System.Text.RegularExpressions;
+
+namespace MarkovText;
+
+/// <summary>
+/// Strips Markdown syntax while keeping the prose, so README and documentation corpora don't produce chains full of "##" and "](":
+/// removes code blocks, images, link targets, table rules, and horizontal rules, and the markers of headings, quotes, lists, emphasis, and inline code
+/// </summary>
+public partial class StripMarkdownPreprocessor : IPreprocessor
+{
+    [GeneratedRegex(@"^\s*(```|~~~).*?^\s*\1[^\n]*$", RegexOptions.Multiline | RegexOptions.Singleline)]
+    private static partial Regex CodeBlockRegex();
+
+    [GeneratedRegex(@"!\[[^\]]*\]\([^)]*\)")]
+    private static partial Regex ImageRegex();
+
+    // Inline links "[text](url)" and reference links "[text][id]" keep their text
+    [GeneratedRegex(@"\[([^\]]*)\](\([^)]*\)|\[[^\]]*\])")]
+    private static partial Regex LinkRegex();
+
+    [GeneratedRegex(@"^\s*\[[^\]]+\]:.*$|<(https?|mailto):[^>]*>", RegexOptions.Multiline)]
+    private static partial Regex LinkTargetRegex();
+
+    // Horizontal rules, and the rules between the header and the rows of tables
+    [GeneratedRegex(@"^\s*([-*_]\s*){3,}$|^\s*\|?(\s*:?-+:?\s*\|)+\s*(:?-+:?\s*)?$", RegexOptions.Multiline)]
+    private static partial Regex RuleRegex();
+
+    // Heading, quote, and list markers at the start of a line
+    [GeneratedRegex(@"^\s*(#{1,6}\s+|>+\s?|[-*+]\s+|\d+[.)]\s+)", RegexOptions.Multiline)]
+    private static partial Regex LineMarkerRegex();
+
+    // Closing hashes of headings like "## Examples ##"
+    [GeneratedRegex(@"\s+#+\s*$", RegexOptions.Multiline)]
+    private static partial Regex ClosingHashesRegex();
+
+    [GeneratedRegex(@"(`+)(.+?)\1")]
+    private static partial Regex InlineCodeRegex();
+
+    [GeneratedRegex(@"(\*{1,3}|_{1,3})(\S(.*?\S)?)\1")]
+    private static partial Regex EmphasisRegex();
+
+    public string Process(string text)
+    {
+        text = CodeBlockRegex().Replace(text, "");
+        text = ImageRegex().Replace(text, "");
+        text = LinkRegex().Replace(text, "$1");
+        text = LinkTargetRegex().Replace(text, "");
+        text = RuleRegex().Replace(text, "");
+        text = LineMarkerRegex().Replace(text, "");
+        text = ClosingHashesRegex().Replace(text, "");
+        text = InlineCodeRegex().Replace(text, "$2");
+        text = EmphasisRegex().Replace(text, "$2");
+        return text.Replace('|', ' ');
+    }
+}
@@ -0,0 +1,126 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// The number of occurrences of each distinct successor of each state of a MarkovChain, and how many distinct transitions occur
+/// each number of times, e.g., 1 => 5000 transitions seen once, so the least frequent transitions are found without scanning the chain.
+/// Successors that replaced others under a limit of successors per state also keep the counts they inherited, on top of their own.
+/// </summary>
+internal class SuccessorCounts<T> where T : notnull
+{
+    // The own occurrences of each successor of each state, and the counts it inherited from the successors it replaced
+    private readonly Dictionary<T[], Dictionary<T[], (int Count, int Inherited)>> Counts;
+
+    // The number of distinct transitions with each number of occurrences
+    private readonly SortedDictionary<int, int> Histogram = new();
+
+    private readonly IEqualityComparer<T[]> StateComparer;
+
+    // Estimated size of the counts of one state
+    private const int StateBytes = MemorySize.DictionaryEntryOverhead + MemorySize.ObjectOverhead;
+
+    // Estimated size of the count of one successor: the successor, its count, and what it inherited
+    private const int SuccessorBytes = MemorySize.DictionaryEntryOverhead + MemorySize.Reference + 2 * sizeof(int);
+
+    /// <summary>
+    /// Counts the successors of the states of the chain, in the order they were added
+    /// </summary>
+    public SuccessorCounts(MarkovChain<T> chain)
+    {
+        StateComparer = chain.StateComparer;
+        Counts = new Dictionary<T[], Dictionary<T[], (int Count, int Inherited)>>(StateComparer);
+
+        foreach (var state in chain.ContinuedStates)
+        {
+            chain.TryGetTransitions(state, out var transitions);
+
+            foreach (var next in transitions!)
+            {
+                Add(state, next);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the counts in bytes, kept up to date as they change
+    /// </summary>
+    public long MemoryBytes { get; private set; }
+
+    /// <summary>
+    /// The fewest occurrences of a distinct transition, null if there are no transitions
+    /// </summary>
+    public int? LeastCount => Histogram.Count > 0 ? Histogram.Keys.First() : null;
+
+    /// <summary>
+    /// Whether the successor is new to the state, which already has at least maxSuccessors successors
+    /// </summary>
+    public bool IsFull(T[] state, T[] successor, int? maxSuccessors) =>
+        Counts.GetValueOrDefault(state) is { } successors && !successors.ContainsKey(successor) && successors.Count >= maxSuccessors;
+
+    /// <summary>
+    /// The first successor of the state with the lowest count including what it inherited, its own count, and that total
+    /// </summary>
+    public (T[] Successor, int Count, int Total) LeastFrequent(T[] state)
+    {
+        var (successor, (count, inherited)) = Counts[state].MinBy(successor => successor.Value.Count + successor.Value.Inherited);
+        return (successor, count, count + inherited);
+    }
+
+    /// <summary>
+    /// Adds an occurrence of the transition, whose successor inherits the count if it is new to the state
+    /// </summary>
+    public void Add(T[] state, T[] successor, int inherited = 0)
+    {
+        if (!Counts.TryGetValue(state, out var successors))
+        {
+            successors = new Dictionary<T[], (int Count, int Inherited)>(StateComparer);
+            Counts[state] = successors;
+            MemoryBytes += StateBytes;
+        }
+
+        if (successors.TryGetValue(successor, out var counts))
+        {
+            successors[successor] = (counts.Count + 1, counts.Inherited);
+            MoveInHistogram(counts.Count, counts.Count + 1);
+        }
+        else
+        {
+            successors[successor] = (1, inherited);
+            MoveInHistogram(0, 1);
+            MemoryBytes += SuccessorBytes;
+        }
+    }
+
+    /// <summary>
+    /// Forgets the counts of a successor whose occurrences were all removed from the state
+    /// </summary>
+    public void Remove(T[] state, T[] successor)
+    {
+        if (!Counts.TryGetValue(state, out var successors) || !successors.Remove(successor, out var counts))
+        {
+            return;
+        }
+
+        MoveInHistogram(counts.Count, 0);
+        MemoryBytes -= SuccessorBytes;
+
+        if (successors.Count == 0)
+        {
+            Counts.Remove(state);
+            MemoryBytes -= StateBytes;
+        }
+    }
+
+    // Moves a transition from the histogram bucket of one count to another, where 0 is no bucket
+    private void MoveInHistogram(int fromCount, int toCount)
+    {
+        if (fromCount > 0 && --Histogram[fromCount] == 0)
+        {
+            Histogram.Remove(fromCount);
+        }
+
+        if (toCount > 0)
+        {
+            Histogram[toCount] = Histogram.GetValueOrDefault(toCount) + 1;
+        }
+    }
+}
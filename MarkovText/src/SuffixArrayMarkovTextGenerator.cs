@@ -0,0 +1,261 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Class that generates text based on the Markov chain algorithm, sampling continuations directly from the corpus positions
+/// found in a suffix array, for very large static corpora. The model is just the corpus as word IDs and the sorted suffixes,
+/// so it needs little memory beyond the index, and the order can be chosen when generating instead of when building.
+/// Continuations are in word ID order instead of corpus order, so a given random sequence picks different words than the other generators.
+/// </summary>
+public class SuffixArrayMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // The default order of the Markov chain, used when no order is given when generating
+    private int Order;
+
+    // Separates the sentences of the corpus, and sorts before every word
+    private const int Boundary = -1;
+
+    private readonly SymbolTable Words = new();
+
+    // The word IDs of the corpus, with a boundary after each sentence
+    private int[] Tokens = Array.Empty<int>();
+
+    // The positions of the words of the corpus, sorted by the words starting there up to the end of the sentence
+    private int[] Suffixes = Array.Empty<int>();
+
+    // The start position and word count of each corpus sentence, longest sentences first
+    private int[] SentenceStarts = Array.Empty<int>();
+    private int[] SentenceLengths = Array.Empty<int>();
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    public override string ToString() => "SuffixArray";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        Order = order;
+
+        Words.Clear();
+
+        AnalyzeCorpus(corpus);  // Analyze the corpus and build the suffix array
+
+        if (SentenceCount(Order) == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random) => GenerateSentence(random, Order);
+
+    /// <summary>
+    /// Generate a random Markov text using a Markov chain of the given order, which can differ from the order the model was built with
+    /// </summary>
+    public string GenerateSentence(IRandomNumberGenerator random, int order)
+    {
+        if (Tokens.Length == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var sentenceCount = SentenceCount(order);
+
+        if (order < 1 || sentenceCount == 0)
+        {
+            throw new ArgumentException($"No corpus sentence has {order} words.", nameof(order));
+        }
+
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+
+        // Choose a random corpus sentence with enough words and start with its first words
+        var start = SentenceStarts[random.Next(sentenceCount)];
+        var phrase = new List<int>(Tokens[start..(start + order)]);
+
+        stringBuilder.AppendJoin(' ', phrase.Select(word => Words[word]));   // Write the entire sentence starter phrase
+
+        // Continuously generate words based on the Markov chain, until the phrase only occurs at the end of corpus sentences
+        while (FindContinuations(phrase, order) is var (first, end) && first < end)
+        {
+            if (phrase.Count + 1 >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {phrase.Count + 1} reached for sentence:\n{stringBuilder}");
+            }
+
+            var word = Tokens[Suffixes[first + random.Next(end - first)] + order];
+            phrase.Add(word);
+
+            stringBuilder.Append(' ');
+            stringBuilder.Append(Words[word]);   // Write the last word of the phrase to the generated text
+        }
+
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    /// <summary>
+    /// The distinct words following the phrase in the corpus and how often they follow it, most frequent first.
+    /// The phrase can have any number of words.
+    /// </summary>
+    public IReadOnlyList<(string Word, int Count)> GetSuccessors(string phrase)
+    {
+        var words = new List<int>();
+
+        foreach (var word in phrase.Split(' ', StringSplitOptions.RemoveEmptyEntries))
+        {
+            if (!Words.TryGetId(word, out var id))
+            {
+                return Array.Empty<(string, int)>();
+            }
+
+            words.Add(id);
+        }
+
+        var (first, end) = FindContinuations(words, words.Count);
+
+        return Suffixes[first..end]
+            .Select(position => Tokens[position + words.Count])
+            .GroupBy(word => word)
+            .Select(group => (Words[group.Key], group.Count()))
+            .OrderByDescending(successor => successor.Item2)
+            .ToList();
+    }
+
+    // The number of sentences with at least the given number of words, which are the first sentences
+    private int SentenceCount(int order)
+    {
+        var low = 0;
+        var high = SentenceLengths.Length;
+
+        while (low < high)
+        {
+            var middle = (low + high) / 2;
+
+            if (SentenceLengths[middle] >= order)
+            {
+                low = middle + 1;
+            }
+            else
+            {
+                high = middle;
+            }
+        }
+
+        return low;
+    }
+
+    // The range of suffixes starting with the last words of the phrase and continuing with another word
+    // The suffixes followed by the end of the sentence sort first, before the continued ones
+    private (int First, int End) FindContinuations(List<int> phrase, int order)
+    {
+        var key = phrase.GetRange(phrase.Count - order, order);
+
+        var first = Search(position => Compare(position, key) >= 0);
+        var end = Search(position => Compare(position, key) > 0);
+
+        // Skip the suffixes where the sentence ends after the phrase
+        var continued = Search(position => Tokens[position + order] != Boundary, first, end);
+
+        return (continued, end);
+    }
+
+    // The first index of the suffix array, within the range, where the condition holds, which must hold for all later indices
+    private int Search(Func<int, bool> condition, int low = 0, int? end = null)
+    {
+        var high = end ?? Suffixes.Length;
+
+        while (low < high)
+        {
+            var middle = (low + high) / 2;
+
+            if (condition(Suffixes[middle]))
+            {
+                high = middle;
+            }
+            else
+            {
+                low = middle + 1;
+            }
+        }
+
+        return low;
+    }
+
+    // Compares the words starting at the position with the key, the end of a sentence sorts first
+    private int Compare(int position, List<int> key)
+    {
+        for (var i = 0; i < key.Count; i++)
+        {
+            var comparison = Tokens[position + i].CompareTo(key[i]);
+
+            if (comparison != 0)
+            {
+                return comparison;
+            }
+        }
+
+        return 0;
+    }
+
+    // Compares the suffixes up to the end of their sentences, ties by position so the order is deterministic
+    private int CompareSuffixes(int first, int second)
+    {
+        for (var i = 0; ; i++)
+        {
+            var comparison = Tokens[first + i].CompareTo(Tokens[second + i]);
+
+            if (comparison != 0 || Tokens[first + i] == Boundary)
+            {
+                return comparison != 0 ? comparison : first.CompareTo(second);
+            }
+        }
+    }
+
+    private void AnalyzeCorpus(string corpus)
+    {
+        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
+        corpus = Preprocessing.Default.Apply(corpus);
+
+        var tokens = new List<int>();
+        var sentences = new List<(int Start, int Length)>();
+        var sentenceStart = 0;
+
+        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            tokens.Add(Words.Intern(word));
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                sentences.Add((sentenceStart, tokens.Count - sentenceStart));
+                tokens.Add(Boundary);
+                sentenceStart = tokens.Count;
+            }
+        }
+
+        if (tokens.Count > sentenceStart)
+        {
+            sentences.Add((sentenceStart, tokens.Count - sentenceStart));   // The corpus may end without a sentence delimiter
+            tokens.Add(Boundary);
+        }
+
+        Tokens = tokens.ToArray();
+        Suffixes = Enumerable.Range(0, Tokens.Length).Where(position => Tokens[position] != Boundary).ToArray();
+        Array.Sort(Suffixes, CompareSuffixes);
+
+        // Stable, so sentences of the same length stay in corpus order
+        var byLength = sentences.OrderByDescending(sentence => sentence.Length).ToList();
+        SentenceStarts = byLength.Select(sentence => sentence.Start).ToArray();
+        SentenceLengths = byLength.Select(sentence => sentence.Length).ToArray();
+    }
+}
@@ -0,0 +1,38 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Rhymes English words by their spelling from the last vowel group on, e.g., "cat" and "sat" by "at", and "make" and "take"
+/// by "ake", skipping a silent final e. Spelling only approximates pronunciation, so words like "love" and "move" also rhyme;
+/// implement IRhymer with a pronouncing dictionary like CMUdict for phonetic rhymes.
+/// </summary>
+public class SuffixRhymer : IRhymer
+{
+    private const string Vowels = "aeiouy";
+
+    public string RhymeKey(string word)
+    {
+        var letters = new string(word.ToLowerInvariant().Where(char.IsLetter).ToArray());
+
+        // A final e after a consonant is silent, so the vowel before it rhymes
+        var end = letters.Length > 2 && letters[^1] == 'e' && !Vowels.Contains(letters[^2]) ? letters.Length - 1 : letters.Length;
+        var start = letters.AsSpan(0, end).LastIndexOfAny(Vowels);
+
+        if (start < 0)
+        {
+            start = letters.AsSpan().LastIndexOfAny(Vowels);
+        }
+
+        if (start < 0)
+        {
+            return "";
+        }
+
+        // The whole last vowel group, e.g., "oa" of "boat"
+        while (start > 0 && Vowels.Contains(letters[start - 1]))
+        {
+            start--;
+        }
+
+        return letters[start..];
+    }
+}
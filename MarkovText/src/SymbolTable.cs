@@ -0,0 +1,45 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Interns tokens as integer symbol IDs, so each distinct token is stored once and phrases can be stored
+/// and hashed as arrays of integers instead of strings. The ID of a symbol is its index in the table.
+/// </summary>
+public class SymbolTable
+{
+    private readonly List<string> Symbols = new();
+    private readonly Dictionary<string, int> SymbolIds = new();
+
+    public int Count => Symbols.Count;
+
+    public string this[int id] => Symbols[id];
+
+    /// <summary>
+    /// The ID of the symbol, adding it to the table if it is new
+    /// </summary>
+    public int Intern(string symbol)
+    {
+        if (!SymbolIds.TryGetValue(symbol, out var id))
+        {
+            id = Symbols.Count;
+            Symbols.Add(symbol);
+            SymbolIds[symbol] = id;
+        }
+
+        return id;
+    }
+
+    public bool TryGetId(string symbol, out int id) => SymbolIds.TryGetValue(symbol, out id);
+
+    /// <summary>
+    /// Rough estimate of the heap memory used by the symbols and their IDs in bytes
+    /// </summary>
+    public long EstimateMemoryBytes() =>
+        Symbols.Sum(symbol => MemorySize.String(symbol)) + (long)Symbols.Capacity * MemorySize.Reference +
+        (long)SymbolIds.Count * (MemorySize.DictionaryEntryOverhead + MemorySize.Reference + sizeof(int));
+
+    public void Clear()
+    {
+        Symbols.Clear();
+        SymbolIds.Clear();
+    }
+}
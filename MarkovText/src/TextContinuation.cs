@@ -0,0 +1,95 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Predicts and appends the next words of arbitrary text with a StringBasedMarkovTextGenerator, e.g., for autocomplete
+/// </summary>
+public static class TextContinuation
+{
+    /// <summary>
+    /// The most likely next words after the last Order words of the context and their probabilities, most likely first,
+    /// e.g., for autocomplete. Empty if the context is shorter than a phrase or its last phrase ends every sentence it occurs in.
+    /// </summary>
+    public static IReadOnlyList<(string Word, double Probability)> PredictNext(this StringBasedMarkovTextGenerator generator, string context, int topN = 5)
+    {
+        generator.ThrowIfNoModel();
+
+        var tokens = generator.NormalizeTokens(generator.Tokenizer.Tokenize(context).ToArray());
+
+        if (tokens.Length < generator.Order || !generator.Chain.TryGetTransitions(tokens[^generator.Order..], out var transitions))
+        {
+            return Array.Empty<(string, double)>();
+        }
+
+        // Models imported from ARPA have separate transitions for a word continuing and ending the sentence
+        return generator.CountSuccessors(transitions)
+            .GroupBy(successor => generator.Word(successor.State))
+            .Select(word => (Word: word.Key, Count: word.Sum(successor => successor.Count)))
+            .OrderByDescending(word => word.Count)
+            .Take(topN)
+            .Select(word => (generator.Recase(word.Word), (double)word.Count / transitions.Count))
+            .ToList();
+    }
+
+    /// <summary>
+    /// Extends arbitrary text with up to maxWords words, continuing from its last Order words. If the model has never seen them,
+    /// it backs off to a random phrase ending with fewer of the last words, and starts a new sentence if none of them match.
+    /// Unlike sentence generation, the words are appended to the given text, which is returned with them.
+    /// </summary>
+    public static string ContinueText(this StringBasedMarkovTextGenerator generator, string text, IRandomNumberGenerator random, int maxWords = 20)
+    {
+        generator.ThrowIfNoModel();
+
+        var stringBuilder = new StringBuilder(text.TrimEnd());
+        var wordCount = 0;
+        var state = BackOffState(generator, generator.NormalizeTokens(generator.Tokenizer.Tokenize(text).ToArray()), random);
+
+        if (state == null)
+        {
+            state = generator.Chain.Starters.Random(random);
+
+            foreach (var token in state.Take(maxWords))
+            {
+                generator.Tokenizer.Append(stringBuilder, generator.Recase(token));
+                wordCount++;
+            }
+        }
+
+        while (wordCount < maxWords && generator.Chain.TryGetTransitions(state, out var transitions))
+        {
+            state = transitions.Random(random);
+
+            generator.Tokenizer.Append(stringBuilder, generator.Recase(generator.Word(state)));
+            wordCount++;
+        }
+
+        return stringBuilder.ToString();
+    }
+
+    // The state of the last Order tokens if it continues, or else a random state ending with as many of the last tokens as possible
+    private static string[]? BackOffState(StringBasedMarkovTextGenerator generator, string[] tokens, IRandomNumberGenerator random)
+    {
+        var order = generator.Order;
+
+        if (tokens.Length >= order && tokens[^order..] is var lastState && generator.Chain.TryGetTransitions(lastState, out _))
+        {
+            return lastState;
+        }
+
+        for (var length = Math.Min(order - 1, tokens.Length); length > 0; length--)
+        {
+            var suffix = tokens[^length..];
+            var candidates = generator.Chain.ContinuedStates
+                .Where(state => state.Length > length && state[^length..].SequenceEqual(suffix, StringComparer.Ordinal))
+                .ToList();
+
+            if (candidates.Count > 0)
+            {
+                return candidates.Random(random);
+            }
+        }
+
+        return null;
+    }
+}
@@ -0,0 +1,13 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// How continuations with the same frequency are ordered when only the most frequent continuations are considered
+/// </summary>
+public enum TieBreaking
+{
+    // The order the continuations first appear in the corpus, which may differ for a model loaded from a patch or persona
+    CorpusOrder,
+
+    // Ordinal order of the continuation words, which is reproducible regardless of how the model was built or loaded
+    Lexicographic,
+}
@@ -0,0 +1,14 @@
+﻿using CommandLine;
+
+namespace MarkovText;
+
+/// <summary>
+/// Options of the train verb, which analyzes the corpus once and writes the model file read by the generate verb
+/// </summary>
+[Verb("train", HelpText = "Build a model from the corpus and save it as a compressed model file.")]
+public class TrainCommandLineOptions : CorpusCommandLineOptions
+{
+    // No short name, since -o is the order like in the other verbs
+    [Option("output", Required = true, HelpText = "Path of the model file to write, e.g., model.mkv.")]
+    public required string Output { get; set; }
+}
@@ -0,0 +1,185 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// Chooses the transitions of StringBasedMarkovTextGenerator with the sampling options of GenerationOptions: greedy, top-k, and
+/// top-p sampling, replayed choices, content filtering, stopword weights, and the veto of the observer
+/// </summary>
+internal static class TransitionSampling
+{
+    // Weights are scaled to integers with this resolution for sampling with IRandomNumberGenerator
+    private const int WeightResolution = 100;
+
+    public static void ThrowIfInvalidSampling(GenerationOptions options)
+    {
+        if (options.TopK is <= 0)
+        {
+            throw new ArgumentOutOfRangeException(nameof(options), options.TopK, $"{nameof(GenerationOptions.TopK)} must be at least 1.");
+        }
+
+        if (options.TopP is <= 0 or > 1 or double.NaN)
+        {
+            throw new ArgumentOutOfRangeException(nameof(options), options.TopP, $"{nameof(GenerationOptions.TopP)} must be greater than 0 and at most 1.");
+        }
+    }
+
+    // Rejects banned and protected rare words as the options say, null when there is nothing to filter
+    public static Func<string[], bool>? IsAllowedTransition(this StringBasedMarkovTextGenerator generator, GenerationOptions options)
+    {
+        Func<string[], bool>? isAllowedTransition = null;
+
+        if (options.ContentFilter is { } filter)
+        {
+            isAllowedTransition = candidate => filter.IsAllowed(generator.Word(candidate));
+        }
+
+        if (options.ProtectRareWords)
+        {
+            var isAllowedByFilter = isAllowedTransition;
+            isAllowedTransition = candidate => !generator.Statistics.IsRareWord(generator.Word(candidate)) && (isAllowedByFilter == null || isAllowedByFilter(candidate));
+        }
+
+        return isAllowedTransition;
+    }
+
+    // Down-weights transitions to stopwords, null when all transitions are equally likely
+    public static Func<string[], double>? TransitionWeight(this StringBasedMarkovTextGenerator generator) =>
+        generator.Stopwords.Count > 0 ? candidate => (generator.IsStopword(generator.Word(candidate)) ? generator.StopwordWeight : 1) : null;
+
+    // Picks the next transition among the allowed ones the sampling options consider, and lets the observer veto it.
+    // False if all transitions are excluded.
+    public static bool TryChooseTransition(this StringBasedMarkovTextGenerator generator, string[] state, IReadOnlyList<string[]> transitions,
+        IRandomNumberGenerator random, int? replayedIndex, GenerationOptions options, Func<string[], bool>? isAllowedTransition,
+        Func<string[], double>? transitionWeight, out int index)
+    {
+        var isAllowed = isAllowedTransition;
+
+        if (MostFrequentContinuations(generator, transitions, options, isAllowedTransition) is { } mostFrequent)
+        {
+            isAllowed = mostFrequent.Contains;
+        }
+
+        if (!TryChoose(transitions, random, replayedIndex, isAllowed, transitionWeight, out index))
+        {
+            return false;
+        }
+
+        if (options.Observer is { } observer)
+        {
+            index = Observe(generator, observer, state, transitions, index, random, isAllowed, transitionWeight);
+        }
+
+        return true;
+    }
+
+    // The most frequent allowed continuations kept by greedy, top-k, and top-p sampling, null when all continuations are kept.
+    // Disallowed continuations are excluded first, so the most frequent allowed ones take their place instead of leaving none.
+    private static HashSet<string[]>? MostFrequentContinuations(StringBasedMarkovTextGenerator generator, IReadOnlyList<string[]> transitions,
+        GenerationOptions options, Func<string[], bool>? isAllowedTransition)
+    {
+        if (options is { Greedy: false, TopK: null, TopP: null })
+        {
+            return null;
+        }
+
+        IEnumerable<(string[] State, int Count)> successors = generator.CountSuccessors(transitions, options.TieBreaking)
+            .Where(successor => isAllowedTransition == null || isAllowedTransition(successor.State))
+            .ToList();
+
+        // Probabilities are among the allowed continuations
+        var allowedCount = successors.Sum(successor => successor.Count);
+
+        if ((options.Greedy ? 1 : options.TopK) is { } topK)
+        {
+            successors = successors.Take(topK);
+        }
+
+        if (options.TopP is not { } topP)
+        {
+            return successors.Select(successor => successor.State).ToHashSet();
+        }
+
+        // The smallest set of continuations whose cumulative probability reaches p
+        var nucleus = new HashSet<string[]>();
+        var cumulativeProbability = 0.0;
+
+        foreach (var (next, count) in successors)
+        {
+            nucleus.Add(next);
+            cumulativeProbability += (double)count / allowedCount;
+
+            if (cumulativeProbability >= topP)
+            {
+                break;
+            }
+        }
+
+        return nucleus;
+    }
+
+    // Asks the observer to accept the chosen continuation, re-sampling among the remaining allowed continuations while it vetoes them
+    private static int Observe(StringBasedMarkovTextGenerator generator, IGenerationObserver observer, string[] state, IReadOnlyList<string[]> candidates,
+        int index, IRandomNumberGenerator random, Func<string[], bool>? isAllowed, Func<string[], double>? weight)
+    {
+        var phrase = generator.Phrase(state);
+        var candidateCount = candidates.Distinct().Count();
+        var vetoed = new HashSet<string[]>();
+
+        while (!observer.OnTransition(new TransitionStep(phrase, generator.Word(candidates[index]), candidateCount)))
+        {
+            vetoed.Add(candidates[index]);
+
+            if (!TryChoose(candidates, random, null, candidate => !vetoed.Contains(candidate) && (isAllowed == null || isAllowed(candidate)), weight, out index))
+            {
+                throw new ContentFilterException($"All transitions from '{phrase}' were vetoed by the observer.");
+            }
+        }
+
+        return index;
+    }
+
+    // Picks a candidate index, randomly unless it is replayed, and re-samples among the remaining allowed candidates if it is rejected
+    public static bool TryChoose<T>(IReadOnlyList<T> candidates, IRandomNumberGenerator random, int? replayedIndex, Func<T, bool>? isAllowed,
+        Func<T, double>? weight, out int index)
+    {
+        index = replayedIndex.HasValue ? replayedIndex.Value % candidates.Count : RandomIndex(candidates, random, weight);
+
+        if (isAllowed == null || isAllowed(candidates[index]))
+        {
+            return true;
+        }
+
+        var allowed = Enumerable.Range(0, candidates.Count).Where(i => isAllowed(candidates[i])).ToList();
+
+        if (allowed.Count == 0)
+        {
+            return false;
+        }
+
+        index = allowed[RandomIndex(allowed.Select(i => candidates[i]).ToList(), random, weight)];
+        return true;
+    }
+
+    // Picks a random index, with probability proportional to the weight of the candidate if weighted
+    private static int RandomIndex<T>(IReadOnlyList<T> candidates, IRandomNumberGenerator random, Func<T, double>? weight)
+    {
+        if (weight == null)
+        {
+            return random.Next(candidates.Count);
+        }
+
+        var weights = candidates.Select(candidate => Math.Max(1, (int)Math.Round(weight(candidate) * WeightResolution))).ToArray();
+        var value = random.Next(weights.Sum());
+
+        for (var i = 0; i < weights.Length - 1; i++)
+        {
+            if (value < weights[i])
+            {
+                return i;
+            }
+
+            value -= weights[i];
+        }
+
+        return weights.Length - 1;
+    }
+}
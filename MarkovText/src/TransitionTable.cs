@@ -0,0 +1,86 @@
+﻿using System.Text.Json;
+
+namespace MarkovText;
+
+/// <summary>
+/// The Markov model as a readable JSON transition table, so other tools can consume it and models can be inspected and diffed as text, e.g.,
+/// { "Version": 1, "Order": 2, "Tokenizer": "whitespace", "StarterPhrases": { "The big": 2 }, "Transitions": { "The big": { "dog": 2 } } }
+/// This is the interchange format between the generators, documented in the README.
+/// </summary>
+public class TransitionTable
+{
+    // Incremented when the format changes incompatibly
+    public const int CurrentVersion = 1;
+
+    public int Version { get; init; } = CurrentVersion;
+
+    // The order of the Markov chain
+    public required int Order { get; init; }
+
+    // The tokenizer the model was built with: "whitespace", "punctuation", or "unicode"
+    public string Tokenizer { get; init; } = "whitespace";
+
+    // The number of corpus sentences starting with each phrase
+    public Dictionary<string, int> StarterPhrases { get; init; } = new();
+
+    // The number of times each word follows each phrase
+    public Dictionary<string, Dictionary<string, int>> Transitions { get; init; } = new();
+
+    private static readonly JsonSerializerOptions SerializerOptions = new() { WriteIndented = true };
+
+    public string ToJson() => JsonSerializer.Serialize(this, SerializerOptions);
+
+    public static TransitionTable FromJson(string json)
+    {
+        var table = JsonSerializer.Deserialize<TransitionTable>(json) ?? throw new ArgumentException("The JSON does not contain a transition table.", nameof(json));
+
+        if (table.Version != CurrentVersion)
+        {
+            throw new ArgumentException($"Transition table version {table.Version} is not supported, expected version {CurrentVersion}.", nameof(json));
+        }
+
+        return table;
+    }
+
+    public static TransitionTable FromPatch(ModelPatch model, string tokenizer)
+    {
+        var table = new TransitionTable { Order = model.Order, Tokenizer = tokenizer };
+
+        foreach (var (phrase, count) in model.StarterPhrases)
+        {
+            table.StarterPhrases[phrase] = count;
+        }
+
+        foreach (var (phrase, _, word, count) in model.Transitions)
+        {
+            if (!table.Transitions.TryGetValue(phrase, out var words))
+            {
+                words = new Dictionary<string, int>();
+                table.Transitions[phrase] = words;
+            }
+
+            words[word] = count;
+        }
+
+        return table;
+    }
+
+    /// <summary>
+    /// The transition table as a patch from an empty model
+    /// </summary>
+    public ModelPatch ToPatch()
+    {
+        var patch = new ModelPatch { Order = Order };
+
+        patch.StarterPhrases.AddRange(StarterPhrases.Select(starter => new StarterPhraseDelta(starter.Key, starter.Value)));
+
+        foreach (var (phrase, words) in Transitions)
+        {
+            // The suffix phrase is the phrase shifted by the word, e.g., "big dog" => "dog was"
+            var shifted = phrase.Split(' ').Skip(1);
+            patch.Transitions.AddRange(words.Select(word => new TransitionDelta(phrase, string.Join(' ', shifted.Append(word.Key)), word.Key, word.Value)));
+        }
+
+        return patch;
+    }
+}
@@ -0,0 +1,235 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Class that generates text based on the Markov chain algorithm, with the phrases stored in a token trie.
+/// Phrases sharing their first words share the trie nodes of those words instead of duplicating them, and the transitions
+/// point directly at the node of the next phrase, so generation needs no lookups. Querying the phrases starting with
+/// some words only visits the subtree of those words, which makes prompt-conditioned generation cheap.
+/// </summary>
+public class TrieMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // The order of the Markov chain (how many words in the "state" of the chain)
+    private int Order;
+
+    // The root of the trie, the nodes at depth Order are the phrases
+    private TrieNode Root = new(null, "");
+
+    // Phrases at the start of sentences are the initial states of the Markov chain
+    private readonly List<TrieNode> SentenceStarterPhrases = new();
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    public override string ToString() => "Trie";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        Order = order;
+
+        Root = new TrieNode(null, "");
+        SentenceStarterPhrases.Clear();
+
+        AnalyzeCorpus(corpus);  // Analyze the corpus and build the Markov model
+
+        if (SentenceStarterPhrases.Count == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        ThrowIfNoModel();
+        return Generate(SentenceStarterPhrases.Random(random), random); // Choose a random starter key from the available starter keys
+    }
+
+    /// <summary>
+    /// Generates a sentence starting with the words of the prompt, e.g., "The big" for a model of order 2 or higher.
+    /// The prompt can have at most Order words.
+    /// </summary>
+    public string GenerateSentence(IRandomNumberGenerator random, string prompt)
+    {
+        ThrowIfNoModel();
+
+        var starters = FindPhrases(prompt).Where(node => node.StarterCount > 0).ToList();
+
+        if (starters.Count == 0)
+        {
+            throw new ArgumentException($"No corpus sentence starts with: {prompt}", nameof(prompt));
+        }
+
+        // Sentence starter phrases are chosen in proportion to how many sentences they start, like without a prompt
+        return Generate(starters.WeightedRandom(random, node => node.StarterCount), random);
+    }
+
+    /// <summary>
+    /// The phrases starting with the words of the prefix, e.g., "of" => "of the", "of a", ...
+    /// The prefix can have at most Order words.
+    /// </summary>
+    public IEnumerable<string> GetPhrases(string prefix = "") => FindPhrases(prefix).Select(node => string.Join(' ', node.Tokens()));
+
+    private IEnumerable<TrieNode> FindPhrases(string prefix)
+    {
+        var words = prefix.Split(' ', StringSplitOptions.RemoveEmptyEntries);
+
+        if (words.Length > Order)
+        {
+            throw new ArgumentException($"The prefix has {words.Length} words but the phrases of the model only have {Order}.", nameof(prefix));
+        }
+
+        var node = Root;
+
+        foreach (var word in words)
+        {
+            if (!node.Children.TryGetValue(word, out node))
+            {
+                return Array.Empty<TrieNode>();
+            }
+        }
+
+        return node.Descendants(Order - words.Length);
+    }
+
+    private string Generate(TrieNode phrase, IRandomNumberGenerator random)
+    {
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+        var wordCount = Order;  // Track the current word count to prevent infinite loops
+
+        stringBuilder.AppendJoin(' ', phrase.Tokens());   // Write the entire sentence starter phrase
+
+        // Continuously generate words based on the Markov chain
+        while (phrase.Transitions != null)
+        {
+            if (++wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+            }
+
+            phrase = phrase.Transitions.Random(random);
+
+            stringBuilder.Append(' ');
+            stringBuilder.Append(phrase.Token);   // Write the last word of the phrase to the generated text
+        }
+
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    private void ThrowIfNoModel()
+    {
+        if (SentenceStarterPhrases.Count == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+    }
+
+    private void AnalyzeCorpus(string corpus)
+    {
+        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
+        corpus = Preprocessing.Default.Apply(corpus);
+
+        var window = new List<string>();
+        TrieNode? previousPhrase = null;
+
+        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            window.Add(word);
+
+            if (window.Count > Order)
+            {
+                window.RemoveAt(0);
+            }
+
+            if (window.Count == Order)
+            {
+                var phrase = Root;
+
+                foreach (var token in window)
+                {
+                    phrase = phrase.Child(token);
+                }
+
+                if (previousPhrase == null)
+                {
+                    SentenceStarterPhrases.Add(phrase);
+                    phrase.StarterCount++;
+                }
+                else
+                {
+                    (previousPhrase.Transitions ??= new List<TrieNode>()).Add(phrase);
+                }
+
+                previousPhrase = phrase;
+            }
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                previousPhrase = null;
+                window.Clear();
+            }
+        }
+    }
+
+    private sealed class TrieNode
+    {
+        public readonly TrieNode? Parent;
+
+        // The word of this node, the last word of the phrase if the node is a phrase
+        public readonly string Token;
+
+        public readonly Dictionary<string, TrieNode> Children = new();
+
+        // The next phrases, once per occurrence in the corpus, null if the phrase ends the sentence
+        public List<TrieNode>? Transitions;
+
+        // Number of corpus sentences starting with the phrase
+        public int StarterCount;
+
+        public TrieNode(TrieNode? parent, string token)
+        {
+            Parent = parent;
+            Token = token;
+        }
+
+        public TrieNode Child(string token)
+        {
+            if (!Children.TryGetValue(token, out var child))
+            {
+                child = new TrieNode(this, token);
+                Children[token] = child;
+            }
+
+            return child;
+        }
+
+        // The words of the phrase, from the root to this node
+        public IEnumerable<string> Tokens()
+        {
+            var tokens = new Stack<string>();
+
+            for (var node = this; node.Parent != null; node = node.Parent)
+            {
+                tokens.Push(node.Token);
+            }
+
+            return tokens;
+        }
+
+        // The nodes the given number of levels below this node, in the order they were added
+        public IEnumerable<TrieNode> Descendants(int depth) =>
+            depth == 0 ? new[] { this } : Children.Values.SelectMany(child => child.Descendants(depth - 1));
+    }
+}
@@ -0,0 +1,182 @@
+﻿using System.Globalization;
+using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Tokenizes on Unicode character categories instead of splitting on spaces, so corpora with non-ASCII punctuation,
+/// combining characters, and non-Latin scripts tokenize correctly:
+/// - Grapheme clusters are never split, e.g., "e" followed by a combining acute accent
+/// - Punctuation is split from words like PunctuationTokenizer, including punctuation like "¿" and "。"
+/// - Apostrophes and hyphens between letters belong to the word, e.g., "isn't" and "well-known"
+/// - Chinese and Japanese characters, which are written without spaces, become one token each
+/// </summary>
+public class UnicodeTokenizer : ITokenizer
+{
+    private enum Kind { Space, Word, Ideograph, Punctuation }
+
+    // Sentence delimiters used to detect sentence boundaries, including their ideographic and fullwidth variants
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!', '。', '？', '！' };
+
+    // Characters that join two parts of a word
+    private static readonly char[] WordJoiners = { '\'', '’', '-', '‐' };
+
+    public IEnumerable<string> Tokenize(string text)
+    {
+        var elements = new List<string>();
+        var enumerator = StringInfo.GetTextElementEnumerator(text);
+
+        while (enumerator.MoveNext())
+        {
+            elements.Add(enumerator.GetTextElement());
+        }
+
+        var token = new StringBuilder();
+        var tokenKind = Kind.Space;
+
+        for (var i = 0; i < elements.Count; i++)
+        {
+            var element = elements[i];
+            var kind = Classify(element);
+
+            if (kind == Kind.Punctuation && tokenKind == Kind.Word && WordJoiners.Contains(element[0]) &&
+                i + 1 < elements.Count && Classify(elements[i + 1]) == Kind.Word)
+            {
+                kind = Kind.Word;
+            }
+
+            if (token.Length > 0 && (kind != tokenKind || kind == Kind.Ideograph))
+            {
+                yield return token.ToString();
+                token.Clear();
+            }
+
+            if (kind != Kind.Space)
+            {
+                token.Append(element);
+            }
+
+            tokenKind = kind;
+        }
+
+        if (token.Length > 0)
+        {
+            yield return token.ToString();
+        }
+    }
+
+    public bool IsSentenceEnd(string token)
+    {
+        var end = token.Length;
+
+        // Closing quotes and brackets can follow the sentence delimiter in the same punctuation token, e.g., .” or 。」
+        while (end > 1 && IsClosingQuoteOrBracket(token[end - 1]))
+        {
+            end--;
+        }
+
+        return SentenceDelimiters.Contains(token[end - 1]);
+    }
+
+    public void Append(StringBuilder builder, string token) => Append(builder, token.AsSpan());
+
+    public void Append(StringBuilder builder, ReadOnlySpan<char> token)
+    {
+        if (builder.Length > 0 && NeedsSpace(LastCodePoint(builder), FirstCodePoint(token)))
+        {
+            builder.Append(' ');
+        }
+
+        builder.Append(token);
+    }
+
+    private static bool NeedsSpace(int previous, int next)
+    {
+        // No space before closing punctuation or after opening punctuation, e.g., "(dog)."
+        if (IsClosingPunctuation(next) || IsOpeningPunctuation(previous))
+        {
+            return false;
+        }
+
+        // Chinese and Japanese text is written without spaces
+        if ((IsIdeograph(previous) || IsIdeographicPunctuation(previous)) && (IsIdeograph(next) || IsIdeographicPunctuation(next)))
+        {
+            return false;
+        }
+
+        return !IsIdeographicPunctuation(previous) && !IsIdeographicPunctuation(next);
+    }
+
+    private static Kind Classify(string element)
+    {
+        var codePoint = CodePoint(element, 0);
+
+        if (char.IsWhiteSpace(element, 0))
+        {
+            return Kind.Space;
+        }
+
+        if (IsIdeograph(codePoint))
+        {
+            return Kind.Ideograph;
+        }
+
+        return CharUnicodeInfo.GetUnicodeCategory(element, 0) switch
+        {
+            UnicodeCategory.UppercaseLetter or UnicodeCategory.LowercaseLetter or UnicodeCategory.TitlecaseLetter or
+            UnicodeCategory.ModifierLetter or UnicodeCategory.OtherLetter or
+            UnicodeCategory.NonSpacingMark or UnicodeCategory.SpacingCombiningMark or UnicodeCategory.EnclosingMark or
+            UnicodeCategory.DecimalDigitNumber or UnicodeCategory.LetterNumber or UnicodeCategory.OtherNumber or
+            UnicodeCategory.ConnectorPunctuation => Kind.Word,
+            UnicodeCategory.Control or UnicodeCategory.Format or UnicodeCategory.Surrogate => Kind.Space,
+            _ => Kind.Punctuation
+        };
+    }
+
+    private static bool IsClosingPunctuation(int codePoint)
+    {
+        if (codePoint is '.' or ',' or ';' or ':' or '?' or '!' or '%' or '…')
+        {
+            return true;
+        }
+
+        var category = CharUnicodeInfo.GetUnicodeCategory(codePoint);
+        return category is UnicodeCategory.ClosePunctuation or UnicodeCategory.FinalQuotePunctuation;
+    }
+
+    private static bool IsClosingQuoteOrBracket(char character) =>
+        character is '"' or '\'' || CharUnicodeInfo.GetUnicodeCategory(character) is UnicodeCategory.ClosePunctuation or UnicodeCategory.FinalQuotePunctuation;
+
+    private static bool IsOpeningPunctuation(int codePoint)
+    {
+        if (codePoint is '¿' or '¡')
+        {
+            return true;
+        }
+
+        var category = CharUnicodeInfo.GetUnicodeCategory(codePoint);
+        return category is UnicodeCategory.OpenPunctuation or UnicodeCategory.InitialQuotePunctuation;
+    }
+
+    // CJK ideographs, and the Japanese kana that are written together with them
+    private static bool IsIdeograph(int codePoint) =>
+        codePoint is >= 0x4E00 and <= 0x9FFF        // CJK Unified Ideographs
+            or >= 0x3400 and <= 0x4DBF              // CJK Unified Ideographs Extension A
+            or >= 0x20000 and <= 0x2FA1F            // CJK Unified Ideographs Extension B and later
+            or >= 0xF900 and <= 0xFAFF              // CJK Compatibility Ideographs
+            or >= 0x3040 and <= 0x30FF              // Hiragana and Katakana
+            or >= 0x31F0 and <= 0x31FF;             // Katakana Phonetic Extensions
+
+    // CJK symbols and punctuation, and fullwidth forms like "，"
+    private static bool IsIdeographicPunctuation(int codePoint) =>
+        codePoint is >= 0x3000 and <= 0x303F or >= 0xFF00 and <= 0xFF65;
+
+    private static int CodePoint(string text, int index) =>
+        char.IsSurrogatePair(text, index) ? char.ConvertToUtf32(text, index) : text[index];
+
+    private static int FirstCodePoint(ReadOnlySpan<char> text) =>
+        text.Length > 1 && char.IsSurrogatePair(text[0], text[1]) ? char.ConvertToUtf32(text[0], text[1]) : text[0];
+
+    private static int LastCodePoint(StringBuilder builder) =>
+        builder.Length > 1 && char.IsSurrogatePair(builder[^2], builder[^1]) ? char.ConvertToUtf32(builder[^2], builder[^1]) : builder[^1];
+}
@@ -0,0 +1,11 @@
+﻿namespace MarkovText;
+
+/// <summary>
+/// The result of checking the invariants of a model, see StringBasedMarkovTextGenerator.Validate
+/// </summary>
+/// <param name="Errors">Broken invariants, e.g., phrases with the wrong number of tokens, which can make generation fail or produce garbage</param>
+/// <param name="Warnings">Dead ends that are valid but may be unintended, e.g., phrases that end sentences without a sentence delimiter</param>
+public record ValidationReport(IReadOnlyList<string> Errors, IReadOnlyList<string> Warnings)
+{
+    public bool IsValid => Errors.Count == 0;
+}
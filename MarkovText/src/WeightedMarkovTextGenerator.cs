@@ -0,0 +1,219 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Class that generates text based on the Markov chain algorithm, with deduplicated transitions.
+/// Each phrase stores its distinct continuations with explicit probabilities instead of one entry per occurrence,
+/// so the model can be inspected as a probability table, and a continuation is sampled in O(log n).
+/// </summary>
+public class WeightedMarkovTextGenerator : IGenerator
+{
+    // Safety limit for longest sentence that can be generated, to prevent infinite loops
+    public int MaxWordCount = 1000;
+
+    // Build alias tables to sample continuations in constant time, which pays off for phrases with thousands of continuations
+    public bool AliasSampling;
+
+    // The order of the Markov chain (how many words in the "state" of the chain)
+    private int Order;
+
+    // Phrases at the start of sentences are the initial states of the Markov chain
+    private ProbabilityDistribution<string> SentenceStarterPhrases = new(Array.Empty<KeyValuePair<string, int>>());
+
+    // Maps prefix word phrases to the distribution of suffix phrases, e.g., "the big dog" => { "big dog was": 0.5, "big dog ran": 0.5 }
+    // Tuple also holds the last word of the suffix phrase, e.g., "was"
+    private readonly Dictionary<string, ProbabilityDistribution<(string SuffixPhrase, string Word)>> PhraseTransitions = new();
+
+    // Thread-local StringBuilder to avoid memory overhead from multiple threads
+    private readonly ThreadLocal<StringBuilder> threadLocalStringBuilder = new(() => new StringBuilder());
+
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    public override string ToString() => "Weighted";
+
+    public void BuildMarkovModel(string corpus, int order = 2)
+    {
+        Order = order;
+
+        PhraseTransitions.Clear();
+
+        AnalyzeCorpus(corpus);  // Analyze the corpus and build the Markov model
+
+        if (SentenceStarterPhrases.TotalCount == 0)
+        {
+            throw new ArgumentException($"No phrases of order {Order} could be generated from the corpus: {corpus}");
+        }
+    }
+
+    public string GenerateSentence(IRandomNumberGenerator random)
+    {
+        if (SentenceStarterPhrases.TotalCount == 0)
+        {
+            throw new InvalidOperationException($"There is no Markov model. You need to call {nameof(BuildMarkovModel)} first.");
+        }
+
+        var stringBuilder = threadLocalStringBuilder.Value;
+        stringBuilder!.Clear();  // Clear the StringBuilder for reuse
+        var wordCount = Order;  // Track the current word count to prevent infinite loops
+        var phrase = SentenceStarterPhrases.Sample(random); // Choose a random starter key, in proportion to how many sentences it starts
+
+        stringBuilder.Append(phrase);   // Write the entire sentence starter phrase
+
+        // Continuously generate words based on the Markov chain
+        while (PhraseTransitions.TryGetValue(phrase, out var possibleTransitions))
+        {
+            if (++wordCount >= MaxWordCount)    // Safety check to prevent infinite loops
+            {
+                throw new SentenceOverflowException($"Word limit {wordCount} reached for sentence:\n{stringBuilder}");
+            }
+
+            (phrase, var lastWordInPhrase) = possibleTransitions.Sample(random);
+
+            stringBuilder.Append(' ');
+            stringBuilder.Append(lastWordInPhrase);   // Write the last word of the phrase to the generated text
+        }
+
+        return stringBuilder.ToString();  // Return the generated Markov text
+    }
+
+    /// <summary>
+    /// The probability of each sentence starter phrase
+    /// </summary>
+    public IReadOnlyDictionary<string, double> GetStarterProbabilities() => ToTable(SentenceStarterPhrases, phrase => phrase);
+
+    /// <summary>
+    /// The probability of each word following the phrase, empty if the phrase ends the sentence
+    /// </summary>
+    public IReadOnlyDictionary<string, double> GetTransitionProbabilities(string phrase) =>
+        PhraseTransitions.TryGetValue(phrase, out var transitions)
+            ? ToTable(transitions, transition => transition.Word)
+            : new Dictionary<string, double>();
+
+    /// <summary>
+    /// Exports the model in the TransitionTable interchange format
+    /// </summary>
+    public TransitionTable ToTransitionTable()
+    {
+        var table = new TransitionTable { Order = Order };
+
+        for (var i = 0; i < SentenceStarterPhrases.Outcomes.Count; i++)
+        {
+            table.StarterPhrases[SentenceStarterPhrases.Outcomes[i]] = SentenceStarterPhrases.Counts[i];
+        }
+
+        foreach (var (phrase, transitions) in PhraseTransitions)
+        {
+            var words = new Dictionary<string, int>();
+
+            for (var i = 0; i < transitions.Outcomes.Count; i++)
+            {
+                words[transitions.Outcomes[i].Word] = transitions.Counts[i];
+            }
+
+            table.Transitions[phrase] = words;
+        }
+
+        return table;
+    }
+
+    /// <summary>
+    /// Imports a model in the TransitionTable interchange format, e.g., exported by StringBasedMarkovTextGenerator.ToJson
+    /// </summary>
+    public static WeightedMarkovTextGenerator FromTransitionTable(TransitionTable table, bool aliasSampling = false)
+    {
+        if (table.Tokenizer != "whitespace")
+        {
+            throw new ArgumentException($"The weighted generator joins words with spaces and cannot use the {table.Tokenizer} tokenizer.", nameof(table));
+        }
+
+        var generator = new WeightedMarkovTextGenerator { Order = table.Order, AliasSampling = aliasSampling };
+        generator.SentenceStarterPhrases = new ProbabilityDistribution<string>(table.StarterPhrases, aliasSampling);
+
+        foreach (var (phrase, words) in table.Transitions)
+        {
+            // The suffix phrase is the phrase shifted by the word, e.g., "big dog" => "dog was"
+            var shifted = phrase.Split(' ').Skip(1);
+            generator.PhraseTransitions[phrase] = new ProbabilityDistribution<(string SuffixPhrase, string Word)>(
+                words.Select(word => KeyValuePair.Create((string.Join(' ', shifted.Append(word.Key)), word.Key), word.Value)), aliasSampling);
+        }
+
+        return generator;
+    }
+
+    private static Dictionary<string, double> ToTable<T>(ProbabilityDistribution<T> distribution, Func<T, string> label)
+    {
+        var table = new Dictionary<string, double>();
+
+        for (var i = 0; i < distribution.Outcomes.Count; i++)
+        {
+            table[label(distribution.Outcomes[i])] = distribution.Probabilities[i];
+        }
+
+        return table;
+    }
+
+    private void AnalyzeCorpus(string corpus)
+    {
+        // Remove unwanted characters like page numbers, quotes, parentheses, etc.
+        corpus = Preprocessing.Default.Apply(corpus);
+
+        // Count the occurrences first, then freeze the counts into distributions
+        var starterCounts = new Dictionary<string, int>();
+        var transitionCounts = new Dictionary<string, Dictionary<(string, string), int>>();
+
+        var window = new List<string>();
+        string? previousPhrase = null;
+
+        foreach (var word in corpus.Trim().Split(' '))  // Split the corpus into words
+        {
+            if (string.IsNullOrWhiteSpace(word))
+            {
+                continue;
+            }
+
+            window.Add(word);
+
+            if (window.Count > Order)
+            {
+                window.RemoveAt(0);
+            }
+
+            if (window.Count == Order)
+            {
+                var phrase = string.Join(' ', window);
+
+                if (previousPhrase == null)
+                {
+                    starterCounts[phrase] = starterCounts.GetValueOrDefault(phrase) + 1;
+                }
+                else
+                {
+                    if (!transitionCounts.TryGetValue(previousPhrase, out var counts))
+                    {
+                        counts = new Dictionary<(string, string), int>();
+                        transitionCounts[previousPhrase] = counts;
+                    }
+
+                    counts[(phrase, word)] = counts.GetValueOrDefault((phrase, word)) + 1;
+                }
+
+                previousPhrase = phrase;
+            }
+
+            if (SentenceDelimiters.Contains(word[^1]))
+            {
+                previousPhrase = null;
+                window.Clear();
+            }
+        }
+
+        SentenceStarterPhrases = new ProbabilityDistribution<string>(starterCounts, AliasSampling);
+
+        foreach (var (phrase, counts) in transitionCounts)
+        {
+            PhraseTransitions[phrase] = new ProbabilityDistribution<(string SuffixPhrase, string Word)>(counts, AliasSampling);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// Keeps line breaks and indentation as tokens, so code or poetry is generated with its line structure instead of
+/// being flattened to a single line. Use it with preprocessors that keep line breaks, e.g., an empty pipeline.
+/// Single line breaks are "\n" tokens, blank lines are "\n\n" tokens, and indentation is stored with no-break spaces
+/// because tokens can't contain spaces. When line breaks are preserved, blank lines end sentences instead of full stops.
+/// </summary>
+public class WhitespacePreservingTokenizer : ITokenizer
+{
+    public const string LineBreak = "\n";
+    public const string BlankLine = "\n\n";
+
+    // Indentation spaces are stored as no-break spaces, since phrases are the tokens joined by spaces
+    private const char IndentationSpace = '\u00A0';
+
+    // Sentence delimiters used to detect sentence boundaries when line breaks aren't preserved
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    private readonly bool preserveLineBreaks;
+    private readonly bool preserveIndentation;
+
+    public WhitespacePreservingTokenizer(bool preserveLineBreaks = true, bool preserveIndentation = true)
+    {
+        this.preserveLineBreaks = preserveLineBreaks;
+        this.preserveIndentation = preserveIndentation;
+    }
+
+    public IEnumerable<string> Tokenize(string text)
+    {
+        var atLineStart = true;
+        var i = 0;
+
+        while (i < text.Length)
+        {
+            var start = i;
+
+            if (text[i] is '\n' or '\r')
+            {
+                var lineBreaks = 0;
+
+                for (; i < text.Length && text[i] is '\n' or '\r'; i++)
+                {
+                    lineBreaks += text[i] == '\n' ? 1 : 0;
+                }
+
+                if (preserveLineBreaks && lineBreaks > 0)
+                {
+                    yield return lineBreaks > 1 ? BlankLine : LineBreak;
+                }
+
+                atLineStart = true;
+            }
+            else if (char.IsWhiteSpace(text[i]))
+            {
+                while (i < text.Length && char.IsWhiteSpace(text[i]) && text[i] is not ('\n' or '\r'))
+                {
+                    i++;
+                }
+
+                // Trailing whitespace and the whitespace of blank lines is dropped
+                if (preserveIndentation && atLineStart && i < text.Length && text[i] is not ('\n' or '\r'))
+                {
+                    yield return text[start..i].Replace(' ', IndentationSpace);
+                }
+            }
+            else
+            {
+                while (i < text.Length && !char.IsWhiteSpace(text[i]))
+                {
+                    i++;
+                }
+
+                yield return text[start..i];
+                atLineStart = false;
+            }
+        }
+    }
+
+    public bool IsSentenceEnd(string token) =>
+        preserveLineBreaks ? token == BlankLine : !IsWhitespace(token) && SentenceDelimiters.Contains(token[^1]);
+
+    public void Append(StringBuilder builder, string token) => Append(builder, token.AsSpan());
+
+    public void Append(StringBuilder builder, ReadOnlySpan<char> token)
+    {
+        if (char.IsWhiteSpace(token[0]))
+        {
+            foreach (var character in token)
+            {
+                builder.Append(character == IndentationSpace ? ' ' : character);
+            }
+
+            return;
+        }
+
+        // Words at the start of a line follow the line break or indentation directly
+        if (builder.Length > 0 && !char.IsWhiteSpace(builder[^1]))
+        {
+            builder.Append(' ');
+        }
+
+        builder.Append(token);
+    }
+
+    private static bool IsWhitespace(string token) => char.IsWhiteSpace(token[0]);
+}
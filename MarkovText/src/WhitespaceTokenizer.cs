@@ -0,0 +1,37 @@
+﻿using System.Text;
+
+namespace MarkovText;
+
+/// <summary>
+/// The default tokenizer: words are separated by whitespace and punctuation stays glued to the words, e.g., "dog."
+/// </summary>
+public class WhitespaceTokenizer : ITokenizer
+{
+    // Sentence delimiters used to detect sentence boundaries
+    private static readonly char[] SentenceDelimiters = { '.', '?', '!' };
+
+    public IEnumerable<string> Tokenize(string text)
+    {
+        foreach (var word in text.Trim().Split(' '))
+        {
+            if (!string.IsNullOrWhiteSpace(word))
+            {
+                yield return word;
+            }
+        }
+    }
+
+    public bool IsSentenceEnd(string token) => SentenceDelimiters.Contains(token[^1]);
+
+    public void Append(StringBuilder builder, string token) => Append(builder, token.AsSpan());
+
+    public void Append(StringBuilder builder, ReadOnlySpan<char> token)
+    {
+        if (builder.Length > 0)
+        {
+            builder.Append(' ');
+        }
+
+        builder.Append(token);
+    }
+}